@@ -0,0 +1,149 @@
+//! Reference firmware for the [Walter](https://www.quickspot.io) board: gets a GNSS fix every 15
+//! minutes, then attaches to the LTE network to publish it over MQTT.
+//!
+//! Built with the `esp` toolchain (see [espup](https://github.com/esp-rs/espup)), the broker is
+//! set at build time:
+//!
+//! ```sh
+//! MQTT_HOST=broker.example.com cargo run --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write as _;
+
+use atat::ResponseSlot;
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use esp_backtrace as _;
+use esp_hal::{
+    Async,
+    gpio::{Level, Output, OutputConfig},
+    interrupt::software::SoftwareInterruptControl,
+    timer::timg::TimerGroup,
+    uart::{Config as UartConfig, Uart, UartRx, UartTx},
+};
+use log::{error, info};
+use monarch2::{prelude::*, walter};
+use static_cell::StaticCell;
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// Broker the fixes are published to.
+const MQTT_HOST: &str = match option_env!("MQTT_HOST") {
+    Some(host) => host,
+    None => "test.mosquitto.org",
+};
+
+const MQTT_TOPIC: &str = "monarch2/walter/fix";
+
+/// Time between two reports.
+const REPORT_PERIOD: Duration = Duration::from_secs(15 * 60);
+
+type WalterModem = DefaultModem<'static, walter::WalterClient<'static, UartTx<'static, Async>>>;
+
+static RES_SLOT: ResponseSlot<INGRESS_BUF_SIZE> = ResponseSlot::new();
+static URC_CHANNEL: DefaultUrcChannel = DefaultUrcChannel::new();
+static STATE: ModemState = ModemState::new();
+static INGRESS_BUF: StaticCell<[u8; INGRESS_BUF_SIZE]> = StaticCell::new();
+static CMD_BUF: StaticCell<[u8; MAX_CMD_LEN]> = StaticCell::new();
+
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    esp_println::logger::init_logger_from_env();
+
+    let p = esp_hal::init(esp_hal::Config::default());
+    let timg0 = TimerGroup::new(p.TIMG0);
+    let sw_int = SoftwareInterruptControl::new(p.SW_INTERRUPT);
+    esp_rtos::start(timg0.timer0, sw_int.software_interrupt0);
+
+    let config = UartConfig::default().with_baudrate(walter::BAUD_RATE);
+    let (rx, tx) = Uart::new(p.UART1, config)
+        .unwrap()
+        .with_rx(p.GPIO14)
+        .with_tx(p.GPIO48)
+        .with_rts(p.GPIO21)
+        .with_cts(p.GPIO47)
+        .into_async()
+        .split();
+    let mut reset = Output::new(p.GPIO45, Level::High, OutputConfig::default());
+
+    let ingress = walter::ingress(
+        INGRESS_BUF.init([0; INGRESS_BUF_SIZE]),
+        &RES_SLOT,
+        &URC_CHANNEL,
+        &STATE,
+    );
+    let mut modem = walter::modem(
+        tx,
+        &RES_SLOT,
+        CMD_BUF.init([0; MAX_CMD_LEN]),
+        &URC_CHANNEL,
+        &STATE,
+        ModemConfig::default(),
+    );
+
+    spawner.must_spawn(ingress_task(ingress, rx));
+    spawner.must_spawn(urc_task(modem.urc_handler()));
+
+    walter::reset(&mut reset, &modem).await.unwrap();
+    modem.begin().await.unwrap();
+    info!("Modem ready: {:?}", modem.info().await);
+
+    loop {
+        match report(&mut modem).await {
+            Ok(()) => info!("Fix published to {}", MQTT_HOST),
+            Err(err) => error!("Report failed: {:?}", err),
+        }
+        Timer::after(REPORT_PERIOD).await;
+    }
+}
+
+/// Feeds the data received from the modem to the ingress.
+#[embassy_executor::task]
+async fn ingress_task(mut ingress: walter::WalterIngress<'static>, mut rx: UartRx<'static, Async>) {
+    ingress.read_from(&mut rx).await
+}
+
+/// Dispatches the URCs to the waiting operations.
+#[embassy_executor::task]
+async fn urc_task(mut urc_handler: DefaultUrcHandler<'static>) {
+    urc_handler.run().await
+}
+
+/// Gets a GNSS fix, the radio is shared with LTE, then attaches to publish it.
+async fn report(modem: &mut WalterModem) -> Result<(), Error> {
+    modem
+        .set_gnss_config(gnss::types::FixSensitivity::Medium)
+        .await?;
+    let detached = modem.lte_force_disconnect(None).await?;
+    let detached = modem.update_gnss_asistance(detached).await?;
+    let fix = modem.get_gnss_fix(&detached).await?;
+    info!(
+        "GNSS fix: {} {} ({} m)",
+        fix.lat.0, fix.long.0, fix.confidence.0
+    );
+
+    let mut payload = heapless::String::<128>::new();
+    // Can't overflow, the floats are short.
+    let _ = write!(
+        payload,
+        r#"{{"lat":{},"lon":{},"confidence":{}}}"#,
+        fix.lat.0, fix.long.0, fix.confidence.0
+    );
+
+    let attached = modem.lte_connect(detached).await?;
+    modem.mqtt_configure("walter", None).await?;
+    modem.mqtt_connect(&attached, MQTT_HOST, None).await?;
+    let published = modem
+        .mqtt_send(
+            MQTT_TOPIC,
+            mqtt::types::Qos::AtLeastOnce,
+            payload.as_bytes(),
+        )
+        .await;
+    modem.mqtt_disconnect().await?;
+    modem.lte_disconnect(attached).await?;
+    published
+}
@@ -0,0 +1,61 @@
+//! Parsing cost of the URCs in the hot UART path: the ~1 kB GNSS fix and a burst of MQTT
+//! notifications, from the raw bytes received to the [`Urc`].
+//!
+//! ```sh
+//! cargo bench --features gm02sp --bench urc
+//! ```
+
+use std::hint::black_box;
+
+use atat::{AtDigester, AtatUrc, DigestResult, Digester};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use monarch2::Urc;
+
+/// Digests all the lines of `data` like the ingress does, returns the number of URCs parsed.
+fn digest(data: &[u8]) -> usize {
+    let mut digester = AtDigester::<Urc>::new();
+    let mut data = data;
+    let mut urcs = 0;
+    while !data.is_empty() {
+        let (res, used) = digester.digest(data);
+        if used == 0 {
+            break;
+        }
+        if let DigestResult::Urc(line) = res {
+            urcs += usize::from(Urc::parse(line).is_some());
+        }
+        data = &data[used..];
+    }
+    urcs
+}
+
+fn bench(c: &mut Criterion, name: &str, data: &[u8], urcs: usize) {
+    assert_eq!(digest(data), urcs, "{name}: URCs not parsed");
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("digest", |b| b.iter(|| digest(black_box(data))));
+    group.finish();
+}
+
+/// A fix with the raw data of the modem and the satellites in view, about 1 kB.
+fn gnss_fix(c: &mut Criterion) {
+    let raw = "A".repeat(900);
+    let data = format!(
+        "\r\n+LPGNSSFIXREADY: 0,\"2025-06-24T15:55:20.000000\",66563,\"12.500000\",\"50.850000\",\"4.350000\",\"60.000000\",\"0.000000\",\"0.000000\",\"0.000000\",\"{raw}\",(\"12\",38)\r\n"
+    );
+    bench(c, "gnss_fix", data.as_bytes(), 1);
+}
+
+/// Messages received and publications confirmed back to back, e.g. after waking up from PSM.
+fn mqtt_burst(c: &mut Criterion) {
+    let mut data = String::new();
+    for mid in 1..=16 {
+        data += &format!("\r\n+SQNSMQTTONMESSAGE: 0,\"devices/42/commands\",64,1,{mid}\r\n");
+        data += &format!("\r\n+SQNSMQTTONPUBLISH: 0,{mid},0\r\n");
+    }
+    bench(c, "mqtt_burst", data.as_bytes(), 32);
+}
+
+criterion_group!(benches, gnss_fix, mqtt_burst);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monarch2::gnss::responses::GnssAssistanceStatus;
+
+// The response to `AT+LPGNSSASSISTANCE?`, one line per assistance type.
+fuzz_target!(|data: &[u8]| {
+    let _ = GnssAssistanceStatus::parse(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monarch2::gnss::urc::GnssFixReady;
+
+// The largest URC, with the timestamp, the quoted floats and the satellite list parsed by hand.
+fuzz_target!(|data: &[u8]| {
+    let _ = atat::serde_at::from_slice::<GnssFixReady>(data);
+});
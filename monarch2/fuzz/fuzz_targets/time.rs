@@ -0,0 +1,17 @@
+#![no_main]
+
+use core::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use monarch2::{
+    CalendarTime,
+    device::responses::{Clock, Time},
+};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = atat::serde_at::from_slice::<Clock>(data);
+    if let Ok(s) = core::str::from_utf8(data) {
+        let _ = Time::from_str(s);
+        let _ = CalendarTime::from_str(s);
+    }
+});
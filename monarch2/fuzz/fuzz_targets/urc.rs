@@ -0,0 +1,10 @@
+#![no_main]
+
+use atat::AtatUrc;
+use libfuzzer_sys::fuzz_target;
+use monarch2::Urc;
+
+// Any line sent by the modem, recognized as a URC or not.
+fuzz_target!(|data: &[u8]| {
+    let _ = Urc::parse(data);
+});
@@ -0,0 +1,196 @@
+use embassy_time::Duration;
+
+use crate::error::Error;
+
+/// Port of the MQTT connections with mutual TLS, the others need ALPN or custom authorizers.
+pub const AWS_IOT_PORT: u16 = 8883;
+
+/// Longest thing name accepted by AWS IoT.
+pub const AWS_THING_NAME_LEN: usize = 128;
+
+/// Connection to AWS IoT Core, see [`Modem::aws_iot_connect`](crate::Modem::aws_iot_connect).
+///
+/// The Amazon root CA, the device certificate and its private key must be written to the NVM
+/// first, e.g. with [`Modem::nvm_write`](crate::Modem::nvm_write):
+///
+/// ```ignore
+/// let config = AwsIotConfig::new("a1b2c3-ats.iot.eu-west-1.amazonaws.com", "walter-01", 1, 5, 6, 6);
+/// modem.aws_iot_connect(&attached, &config).await?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AwsIotConfig<'a> {
+    /// Device data endpoint of the account, `<prefix>-ats.iot.<region>.amazonaws.com`.
+    pub endpoint: &'a str,
+
+    /// Name of the thing, used as MQTT client id as the default policies require.
+    pub thing_name: &'a str,
+
+    /// Security profile configured for the connection, 1 to 6.
+    pub sp_id: u8,
+
+    /// NVM index of the Amazon root CA certificate.
+    pub ca_cert_id: u8,
+
+    /// NVM index of the device certificate.
+    pub client_cert_id: u8,
+
+    /// NVM index of the private key of the device certificate.
+    pub client_private_key_id: u8,
+
+    /// Broker port, only [`AWS_IOT_PORT`] is supported.
+    pub port: u16,
+
+    /// Interval of the MQTT pings, AWS IoT accepts 30 s to 1200 s.
+    pub keepalive: Duration,
+}
+
+impl<'a> AwsIotConfig<'a> {
+    pub fn new(
+        endpoint: &'a str,
+        thing_name: &'a str,
+        sp_id: u8,
+        ca_cert_id: u8,
+        client_cert_id: u8,
+        client_private_key_id: u8,
+    ) -> Self {
+        Self {
+            endpoint,
+            thing_name,
+            sp_id,
+            ca_cert_id,
+            client_cert_id,
+            client_private_key_id,
+            port: AWS_IOT_PORT,
+            keepalive: Duration::from_secs(300),
+        }
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn with_keepalive(mut self, keepalive: Duration) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Checks the configuration against the requirements of AWS IoT, before anything is sent
+    /// to the modem.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !is_ats_endpoint(self.endpoint) {
+            return Err(Error::AwsIot(AwsIotError::NotAtsEndpoint));
+        }
+        if !is_valid_thing_name(self.thing_name) {
+            return Err(Error::AwsIot(AwsIotError::InvalidThingName));
+        }
+        if !(1..=6).contains(&self.sp_id) {
+            return Err(Error::AwsIot(AwsIotError::InvalidSecurityProfile(
+                self.sp_id,
+            )));
+        }
+        for index in [
+            self.ca_cert_id,
+            self.client_cert_id,
+            self.client_private_key_id,
+        ] {
+            if crate::nvm::is_reserved_index(index) {
+                return Err(Error::ReservedNvmIndex(index));
+            }
+        }
+        if self.port != AWS_IOT_PORT {
+            return Err(Error::AwsIot(AwsIotError::UnsupportedPort(self.port)));
+        }
+        if !(30..=1200).contains(&self.keepalive.as_secs()) {
+            return Err(Error::AwsIot(AwsIotError::KeepaliveOutOfRange));
+        }
+        Ok(())
+    }
+}
+
+/// Pitfall of an [`AwsIotConfig`], reported as [`Error::AwsIot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AwsIotError {
+    /// The endpoint isn't an ATS endpoint, the legacy ones present a certificate that doesn't
+    /// chain to the Amazon root CA.
+    NotAtsEndpoint,
+    /// The thing name is empty, longer than [`AWS_THING_NAME_LEN`] or has characters other than
+    /// letters, digits, `:`, `_` and `-`.
+    InvalidThingName,
+    /// The security profile is out of the 1 to 6 range.
+    InvalidSecurityProfile(u8),
+    /// The port doesn't accept mutual TLS without ALPN, see [`AWS_IOT_PORT`].
+    UnsupportedPort(u16),
+    /// The broker closes the connections with a keepalive out of 30 s to 1200 s.
+    KeepaliveOutOfRange,
+}
+
+fn is_ats_endpoint(endpoint: &str) -> bool {
+    (endpoint.ends_with(".amazonaws.com") || endpoint.ends_with(".amazonaws.com.cn"))
+        && endpoint.contains("-ats.iot.")
+}
+
+fn is_valid_thing_name(name: &str) -> bool {
+    (1..=AWS_THING_NAME_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b':' | b'_' | b'-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AwsIotConfig<'static> {
+        AwsIotConfig::new(
+            "a1b2c3d4e5f6g7-ats.iot.eu-west-1.amazonaws.com",
+            "walter-01",
+            1,
+            5,
+            6,
+            6,
+        )
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(config().validate(), Ok(()));
+
+        let legacy = AwsIotConfig {
+            endpoint: "a1b2c3d4e5f6g7.iot.eu-west-1.amazonaws.com",
+            ..config()
+        };
+        assert_eq!(
+            legacy.validate(),
+            Err(Error::AwsIot(AwsIotError::NotAtsEndpoint))
+        );
+
+        for name in ["", "walter 01", "walter/01"] {
+            let invalid = AwsIotConfig {
+                thing_name: name,
+                ..config()
+            };
+            assert_eq!(
+                invalid.validate(),
+                Err(Error::AwsIot(AwsIotError::InvalidThingName))
+            );
+        }
+
+        let reserved = AwsIotConfig {
+            ca_cert_id: 1,
+            ..config()
+        };
+        assert_eq!(reserved.validate(), Err(Error::ReservedNvmIndex(1)));
+
+        assert_eq!(
+            config().with_port(443).validate(),
+            Err(Error::AwsIot(AwsIotError::UnsupportedPort(443)))
+        );
+        assert_eq!(
+            config().with_keepalive(Duration::from_secs(10)).validate(),
+            Err(Error::AwsIot(AwsIotError::KeepaliveOutOfRange))
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use core::fmt::Write;
+
+use embassy_time::Duration;
+
+use crate::{capacity::MqttCredential, error::Error};
+
+/// Version of the IoT Hub MQTT API announced in the username.
+pub const AZURE_API_VERSION: &str = "2021-04-12";
+
+/// Longest device id accepted by IoT Hub.
+pub const AZURE_DEVICE_ID_LEN: usize = 128;
+
+/// Port of the MQTT connections to IoT Hub.
+pub const AZURE_IOT_PORT: u16 = 8883;
+
+/// Connection to an Azure IoT hub, see [`Modem::azure_iot_connect`](crate::Modem::azure_iot_connect).
+///
+/// The device authenticates with a [`AzureSasToken`] or with an X.509 certificate, whose
+/// certificate and private key must be written to the NVM like the root CA of the hub.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AzureIotConfig<'a> {
+    /// Host name of the hub, `<hub>.azure-devices.net`.
+    pub hub: &'a str,
+
+    /// Id of the device registered in the hub, also the MQTT client id.
+    pub device_id: &'a str,
+
+    /// Security profile configured for the connection, 1 to 6.
+    pub sp_id: u8,
+
+    /// NVM index of the root CA certificate of the hub.
+    pub ca_cert_id: u8,
+
+    /// NVM indexes of the device certificate and of its private key, for the X.509
+    /// authentication.
+    pub client_cert: Option<(u8, u8)>,
+}
+
+impl AzureIotConfig<'_> {
+    /// Checks the configuration before anything is sent to the modem, `sas_token` tells whether
+    /// the device authenticates with a token.
+    pub fn validate(&self, sas_token: bool) -> Result<(), Error> {
+        if !is_valid_device_id(self.device_id) {
+            return Err(Error::AzureIot(AzureIotError::InvalidDeviceId));
+        }
+        if !(1..=6).contains(&self.sp_id) {
+            return Err(Error::AzureIot(AzureIotError::InvalidSecurityProfile(
+                self.sp_id,
+            )));
+        }
+        if !sas_token && self.client_cert.is_none() {
+            return Err(Error::AzureIot(AzureIotError::MissingCredentials));
+        }
+
+        let (cert_id, key_id) = self.client_cert.unzip();
+        for index in [Some(self.ca_cert_id), cert_id, key_id]
+            .into_iter()
+            .flatten()
+        {
+            if crate::nvm::is_reserved_index(index) {
+                return Err(Error::ReservedNvmIndex(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the MQTT username expected by the hub, `{hub}/{device_id}/?api-version=…`.
+    pub fn username(&self) -> Result<MqttCredential, Error> {
+        let mut username = MqttCredential::new();
+        write!(
+            username,
+            "{}/{}/?api-version={}",
+            self.hub, self.device_id, AZURE_API_VERSION
+        )
+        .map_err(|_| Error::AzureIot(AzureIotError::CredentialTooLong))?;
+        Ok(username)
+    }
+}
+
+/// Shared access signature of a device, the MQTT password of the SAS authentication.
+///
+/// The crate doesn't implement the HMAC-SHA256 signature, the caller computes it with the key of
+/// the device, e.g. with a hardware accelerator:
+///
+/// ```ignore
+/// let now = modem.get_time(ClockSyncPolicy::NeverConnect).await?.unix_seconds().unwrap();
+/// let token = AzureSasToken::new(&config, now + 3600, |data| hmac_sha256(&device_key, data))?;
+/// modem.azure_iot_connect(&attached, &config, Some(&token)).await?;
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct AzureSasToken {
+    token: MqttCredential,
+    expiry: i64,
+}
+
+impl core::fmt::Debug for AzureSasToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never log the signature.
+        f.debug_struct("AzureSasToken")
+            .field("token", &"<redacted>")
+            .field("expiry", &self.expiry)
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AzureSasToken {
+    fn format(&self, f: defmt::Formatter) {
+        // Never log the signature.
+        defmt::write!(
+            f,
+            "AzureSasToken {{ token: <redacted>, expiry: {} }}",
+            self.expiry
+        );
+    }
+}
+
+impl AzureSasToken {
+    /// Creates a token for the device of `config` valid until `expiry` (Unix seconds).
+    ///
+    /// `sign` returns the HMAC-SHA256 of the given data with the decoded device key.
+    pub fn new(
+        config: &AzureIotConfig<'_>,
+        expiry: i64,
+        sign: impl FnOnce(&[u8]) -> [u8; 32],
+    ) -> Result<Self, Error> {
+        let too_long = |_| Error::AzureIot(AzureIotError::CredentialTooLong);
+
+        // The resource URI is signed URL-encoded, as it appears in the token.
+        let mut resource = MqttCredential::new();
+        write!(resource, "{}/devices/{}", config.hub, config.device_id).map_err(too_long)?;
+        let mut to_sign = MqttCredential::new();
+        url_encode(&mut to_sign, &resource).map_err(too_long)?;
+        let encoded_resource_len = to_sign.len();
+        write!(to_sign, "\n{expiry}").map_err(too_long)?;
+
+        let mut signature = heapless::String::<44>::new();
+        base64_encode(&mut signature, &sign(to_sign.as_bytes())).map_err(too_long)?;
+
+        let mut token = MqttCredential::new();
+        write!(
+            token,
+            "SharedAccessSignature sr={}&sig=",
+            &to_sign[..encoded_resource_len]
+        )
+        .map_err(too_long)?;
+        url_encode(&mut token, &signature).map_err(too_long)?;
+        write!(token, "&se={expiry}").map_err(too_long)?;
+
+        Ok(Self { token, expiry })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the expiry of the token in Unix seconds.
+    pub fn expiry(&self) -> i64 {
+        self.expiry
+    }
+
+    /// Whether the token expires within `margin` of `now` (Unix seconds) and should be renewed.
+    ///
+    /// The hub closes the connection when the token expires, a new token is only taken into
+    /// account by a new connection.
+    pub fn expires_within(&self, now: i64, margin: Duration) -> bool {
+        now.saturating_add(margin.as_secs() as i64) >= self.expiry
+    }
+}
+
+/// Pitfall of an [`AzureIotConfig`], reported as [`Error::AzureIot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AzureIotError {
+    /// The device id is empty, longer than [`AZURE_DEVICE_ID_LEN`] or has characters the hub
+    /// doesn't accept.
+    InvalidDeviceId,
+    /// The security profile is out of the 1 to 6 range.
+    InvalidSecurityProfile(u8),
+    /// Neither a SAS token nor a client certificate was given.
+    MissingCredentials,
+    /// The username or the SAS token doesn't fit into [`MqttCredential`].
+    CredentialTooLong,
+}
+
+fn is_valid_device_id(id: &str) -> bool {
+    (1..=AZURE_DEVICE_ID_LEN).contains(&id.len())
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-.%_*?!(),:=@$'".contains(&b))
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn url_encode(out: &mut impl Write, s: &str) -> core::fmt::Result {
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.write_char(b as char)?;
+        } else {
+            write!(out, "%{b:02X}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Standard base64 with padding.
+fn base64_encode(out: &mut impl Write, data: &[u8]) -> core::fmt::Result {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.write_char(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char)?;
+            } else {
+                out.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AzureIotConfig<'static> {
+        AzureIotConfig {
+            hub: "myhub.azure-devices.net",
+            device_id: "walter-01",
+            sp_id: 1,
+            ca_cert_id: 5,
+            client_cert: None,
+        }
+    }
+
+    #[test]
+    fn test_username() {
+        assert_eq!(
+            config().username().unwrap(),
+            "myhub.azure-devices.net/walter-01/?api-version=2021-04-12"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(config().validate(true), Ok(()));
+        assert_eq!(
+            config().validate(false),
+            Err(Error::AzureIot(AzureIotError::MissingCredentials))
+        );
+
+        let x509 = AzureIotConfig {
+            client_cert: Some((6, 6)),
+            ..config()
+        };
+        assert_eq!(x509.validate(false), Ok(()));
+
+        let reserved = AzureIotConfig {
+            client_cert: Some((6, 8)),
+            ..config()
+        };
+        assert_eq!(reserved.validate(false), Err(Error::ReservedNvmIndex(8)));
+
+        let invalid = AzureIotConfig {
+            device_id: "walter/01",
+            ..config()
+        };
+        assert_eq!(
+            invalid.validate(true),
+            Err(Error::AzureIot(AzureIotError::InvalidDeviceId))
+        );
+    }
+
+    #[test]
+    fn test_sas_token() {
+        let mut signed = std::vec::Vec::new();
+        let token = AzureSasToken::new(&config(), 1_700_000_000, |data| {
+            signed.extend_from_slice(data);
+            [0xFB; 32]
+        })
+        .unwrap();
+
+        assert_eq!(
+            signed,
+            b"myhub.azure-devices.net%2Fdevices%2Fwalter-01\n1700000000"
+        );
+        assert_eq!(
+            token.as_str(),
+            "SharedAccessSignature sr=myhub.azure-devices.net%2Fdevices%2Fwalter-01\
+             &sig=%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fv7%2B%2Fs%3D\
+             &se=1700000000"
+        );
+        assert!(!token.expires_within(1_699_990_000, Duration::from_secs(600)));
+        assert!(token.expires_within(1_699_999_500, Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        for (data, expected) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let mut out = heapless::String::<16>::new();
+            base64_encode(&mut out, data).unwrap();
+            assert_eq!(out, expected);
+        }
+    }
+}
@@ -0,0 +1,546 @@
+//! Blocking API for applications without an async executor.
+//!
+//! The [`BlockingModem`] drives the async [`Modem`] to completion on the current thread using
+//! [`embassy_futures::block_on`], the AT commands are sent over a blocking
+//! [`atat::blocking::AtatClient`]. While an operation is in progress the URC messages are
+//! processed in place, so operations waiting for the modem (e.g. [`Modem::mqtt_connect`]) work
+//! the same way as with the async API.
+//!
+//! The timeouts rely on `embassy-time`, a time driver has to be provided by the application,
+//! unless the modem is created with its own delay provider, see
+//! [`BlockingModem::new_with_delay`].
+
+use atat::{AtatCmd, UrcChannel};
+use embassy_futures::{
+    block_on,
+    select::{Either, select},
+};
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex};
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
+use heapless::String;
+
+#[cfg(feature = "gnss")]
+use crate::gnss::{types::FixSensitivity, urc::GnssFixReady};
+#[cfg(feature = "http")]
+use crate::http;
+#[cfg(feature = "nidd")]
+use crate::nidd;
+#[cfg(feature = "socket")]
+use crate::socket;
+use crate::{
+    Urc,
+    calendar::ClockTime,
+    config::{ClockSyncPolicy, ModemConfig, PingOptions},
+    delay::DefaultDelay,
+    device, diagnostics, dns,
+    error::Error,
+    info::ModemInfo,
+    listener::UrcListener,
+    modem::{Attached, Detached, Manufacturing, Modem, ModemState, UrcHandler},
+    network::types::NetworkRegistrationState,
+    ping, sim,
+};
+#[cfg(feature = "mqtt")]
+use crate::{
+    aws::AwsIotConfig,
+    azure::{AzureIotConfig, AzureSasToken},
+    modem::MqttAuth,
+    mqtt,
+};
+
+/// Adapter exposing a blocking [`atat::blocking::AtatClient`] as an [`atat::asynch::AtatClient`].
+///
+/// The commands are sent synchronously, the returned future completes on its first poll.
+pub struct BlockingClient<C>(C);
+
+impl<C> BlockingClient<C> {
+    pub fn new(client: C) -> Self {
+        Self(client)
+    }
+
+    /// Returns the wrapped blocking client.
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<C> atat::asynch::AtatClient for BlockingClient<C>
+where
+    C: atat::blocking::AtatClient,
+{
+    async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, atat::Error> {
+        self.0.send(cmd)
+    }
+}
+
+/// Blocking counterpart of [`Modem`].
+///
+/// The most common operations are available directly, any other [`Modem`] operation can be
+/// executed with [`BlockingModem::run`].
+pub struct BlockingModem<
+    'a,
+    C,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    M: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Modem<'a, BlockingClient<C>, N, L, D, M>,
+    urc_handler: UrcHandler<'a, N, L, M>,
+}
+
+impl<'a, C, const N: usize, const L: usize, M> BlockingModem<'a, C, N, L, DefaultDelay, M>
+where
+    C: atat::blocking::AtatClient,
+    M: RawMutex,
+{
+    /// Constructs a new `BlockingModem`, see [`Modem::new`].
+    ///
+    /// The URC handler is created internally and takes one of the `L` channel subscribers.
+    pub fn new(
+        client: C,
+        urc_chan: &'a UrcChannel<Urc, N, L>,
+        state: &'a ModemState<M>,
+        config: ModemConfig,
+    ) -> Self {
+        Self::new_with_delay(client, urc_chan, state, config, DefaultDelay {})
+    }
+}
+
+impl<'a, C, const N: usize, const L: usize, D, M> BlockingModem<'a, C, N, L, D, M>
+where
+    C: atat::blocking::AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Constructs a new `BlockingModem` like [`new`](BlockingModem::new), with the `delay`
+    /// provider used for the retry delays and timeouts, see [`Modem::new_with_delay`].
+    pub fn new_with_delay(
+        client: C,
+        urc_chan: &'a UrcChannel<Urc, N, L>,
+        state: &'a ModemState<M>,
+        config: ModemConfig,
+        delay: D,
+    ) -> Self {
+        let modem =
+            Modem::new_with_delay(BlockingClient::new(client), urc_chan, state, config, delay);
+        let urc_handler = modem.urc_handler();
+        Self { modem, urc_handler }
+    }
+
+    /// Runs an operation of the async [`Modem`] to completion, processing the URC messages
+    /// received in the meantime.
+    ///
+    /// ```ignore
+    /// let mode = modem.run(async |m| m.get_operation_mode().await)?;
+    /// ```
+    pub fn run<R>(
+        &mut self,
+        f: impl AsyncFnOnce(&mut Modem<'a, BlockingClient<C>, N, L, D, M>) -> R,
+    ) -> R {
+        match block_on(select(f(&mut self.modem), self.urc_handler.run())) {
+            Either::First(result) => result,
+            Either::Second(never) => never,
+        }
+    }
+
+    /// Processes the URC messages received since the last operation.
+    pub fn process_urcs(&mut self) {
+        self.urc_handler.process_pending();
+    }
+
+    /// See [`UrcHandler::set_listener`].
+    pub fn set_listener(&mut self, listener: &'a dyn UrcListener) {
+        self.urc_handler.set_listener(listener);
+    }
+
+    /// See [`Modem::send`].
+    pub fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, Error> {
+        self.run(async |m| m.send(cmd).await)
+    }
+
+    /// See [`Modem::send_with_timeout`].
+    pub fn send_with_timeout<Cmd: AtatCmd>(
+        &mut self,
+        cmd: &Cmd,
+        timeout: Duration,
+    ) -> Result<Cmd::Response, Error> {
+        self.run(async |m| m.send_with_timeout(cmd, timeout).await)
+    }
+
+    /// See [`Modem::send_raw`].
+    pub fn send_raw<const R: usize>(
+        &mut self,
+        cmd: &str,
+        timeout: Duration,
+    ) -> Result<String<R>, Error> {
+        self.run(async |m| m.send_raw(cmd, timeout).await)
+    }
+
+    /// See [`Modem::begin`].
+    pub fn begin(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.begin().await)
+    }
+
+    /// See [`Modem::ping`].
+    pub fn ping(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.ping().await)
+    }
+
+    /// See [`Modem::get_pin_status`].
+    pub fn get_pin_status(&mut self) -> Result<sim::types::SIMState, Error> {
+        self.run(async |m| m.get_pin_status().await)
+    }
+
+    /// See [`Modem::unblock_sim`].
+    pub fn unblock_sim(&mut self, puk: String<8>, new_pin: String<8>) -> Result<(), Error> {
+        self.run(async |m| m.unblock_sim(puk, new_pin).await)
+    }
+
+    /// See [`Modem::define_pdp_context`].
+    pub fn define_pdp_context(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.define_pdp_context().await)
+    }
+
+    /// See [`Modem::get_network_registration_state`].
+    pub fn get_network_registration_state(&mut self) -> NetworkRegistrationState {
+        self.process_urcs();
+        self.modem.get_network_registration_state()
+    }
+
+    /// See [`Modem::info`].
+    pub fn info(&mut self) -> Result<ModemInfo, Error> {
+        self.run(async |m| m.info().await)
+    }
+
+    /// See [`Modem::lte_connect`].
+    pub fn lte_connect(&mut self, detached: Detached) -> Result<Attached, Error> {
+        self.run(async |m| m.lte_connect(detached).await)
+    }
+
+    /// See [`Modem::lte_disconnect`].
+    pub fn lte_disconnect(&mut self, attached: Attached) -> Result<Detached, Error> {
+        self.run(async |m| m.lte_disconnect(attached).await)
+    }
+
+    /// See [`Modem::lte_force_disconnect`].
+    pub fn lte_force_disconnect(&mut self, attached: Option<Attached>) -> Result<Detached, Error> {
+        self.run(async |m| m.lte_force_disconnect(attached).await)
+    }
+
+    /// See [`Modem::get_autoconnect`].
+    pub fn get_autoconnect(&mut self) -> Result<bool, Error> {
+        self.run(async |m| m.get_autoconnect().await)
+    }
+
+    /// See [`Modem::set_autoconnect`].
+    pub fn set_autoconnect(&mut self, enabled: bool) -> Result<(), Error> {
+        self.run(async |m| m.set_autoconnect(enabled).await)
+    }
+
+    /// See [`Modem::reboot`].
+    pub fn reboot(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.reboot().await)
+    }
+
+    /// See [`Modem::get_carrier_profile`].
+    pub fn get_carrier_profile(
+        &mut self,
+    ) -> Result<String<{ device::responses::CARRIER_PROFILE_LEN }>, Error> {
+        self.run(async |m| m.get_carrier_profile().await)
+    }
+
+    /// See [`Modem::set_carrier_profile`].
+    pub fn set_carrier_profile(
+        &mut self,
+        detached: &Detached,
+        profile: &str,
+    ) -> Result<bool, Error> {
+        self.run(async |m| m.set_carrier_profile(detached, profile).await)
+    }
+
+    /// See [`Modem::enter_manufacturing_mode`].
+    pub fn enter_manufacturing_mode(&mut self, detached: Detached) -> Result<Manufacturing, Error> {
+        self.run(async |m| m.enter_manufacturing_mode(detached).await)
+    }
+
+    /// See [`Modem::leave_manufacturing_mode`].
+    pub fn leave_manufacturing_mode(
+        &mut self,
+        manufacturing: Manufacturing,
+    ) -> Result<Detached, Error> {
+        self.run(async |m| m.leave_manufacturing_mode(manufacturing).await)
+    }
+
+    /// See [`Modem::rf_test_transmit`].
+    pub fn rf_test_transmit(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+        power: i8,
+    ) -> Result<(), Error> {
+        self.run(async |m| m.rf_test_transmit(manufacturing, earfcn, power).await)
+    }
+
+    /// See [`Modem::rf_test_stop_transmit`].
+    pub fn rf_test_stop_transmit(&mut self, manufacturing: &Manufacturing) -> Result<(), Error> {
+        self.run(async |m| m.rf_test_stop_transmit(manufacturing).await)
+    }
+
+    /// See [`Modem::rf_test_measure`].
+    pub fn rf_test_measure(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+    ) -> Result<i16, Error> {
+        self.run(async |m| m.rf_test_measure(manufacturing, earfcn).await)
+    }
+
+    /// See [`Modem::get_time`].
+    pub fn get_time(&mut self, policy: ClockSyncPolicy) -> Result<device::responses::Clock, Error> {
+        self.run(async |m| m.get_time(policy).await)
+    }
+
+    /// See [`Modem::set_time`].
+    pub fn set_time(&mut self, time: &ClockTime) -> Result<(), Error> {
+        self.run(async |m| m.set_time(time).await)
+    }
+
+    /// See [`Modem::sync_time_ntp`].
+    pub fn sync_time_ntp(
+        &mut self,
+        attached: &Attached,
+        server: &str,
+    ) -> Result<device::responses::Clock, Error> {
+        self.run(async |m| m.sync_time_ntp(attached, server).await)
+    }
+
+    /// See [`Modem::resolve`].
+    pub fn resolve(
+        &mut self,
+        attached: &Attached,
+        hostname: &str,
+    ) -> Result<dns::responses::HostAddresses, Error> {
+        self.run(async |m| m.resolve(attached, hostname).await)
+    }
+
+    /// See [`Modem::icmp_ping`].
+    pub fn icmp_ping(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        options: &PingOptions,
+    ) -> Result<ping::responses::PingReplies, Error> {
+        self.run(async |m| m.icmp_ping(attached, host, options).await)
+    }
+
+    /// See [`Modem::socket_set_hex_mode`].
+    #[cfg(feature = "socket")]
+    pub fn socket_set_hex_mode(&mut self, conn_id: u8) -> Result<(), Error> {
+        self.run(async |m| m.socket_set_hex_mode(conn_id).await)
+    }
+
+    /// See [`Modem::socket_receive`].
+    #[cfg(feature = "socket")]
+    pub fn socket_receive(
+        &mut self,
+        attached: &Attached,
+        conn_id: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.run(async |m| m.socket_receive(attached, conn_id, buf).await)
+    }
+
+    /// See [`Modem::socket_data_usage`].
+    #[cfg(feature = "socket")]
+    pub fn socket_data_usage(&mut self, conn_id: u8) -> Result<socket::types::DataUsage, Error> {
+        self.run(async |m| m.socket_data_usage(conn_id).await)
+    }
+
+    /// See [`Modem::data_usage`].
+    #[cfg(feature = "socket")]
+    pub fn data_usage(&mut self) -> Result<socket::types::DataUsage, Error> {
+        self.run(async |m| m.data_usage().await)
+    }
+
+    /// See [`Modem::http_configure`].
+    #[cfg(feature = "http")]
+    pub fn http_configure(
+        &mut self,
+        prof_id: u8,
+        host: &str,
+        port: u16,
+        sp_id: Option<u8>,
+    ) -> Result<(), Error> {
+        self.run(async |m| m.http_configure(prof_id, host, port, sp_id).await)
+    }
+
+    /// See [`Modem::http_query`].
+    #[cfg(feature = "http")]
+    pub fn http_query(
+        &mut self,
+        attached: &Attached,
+        prof_id: u8,
+        command: http::types::QueryCommand,
+        resource: &str,
+    ) -> Result<http::urc::Ring, Error> {
+        self.run(async |m| m.http_query(attached, prof_id, command, resource).await)
+    }
+
+    /// See [`Modem::http_receive`].
+    #[cfg(feature = "http")]
+    pub fn http_receive(&mut self, prof_id: u8, buf: &mut [u8]) -> Result<usize, Error> {
+        self.run(async |m| m.http_receive(prof_id, buf).await)
+    }
+
+    /// See [`Modem::read_diagnostic_log`].
+    pub fn read_diagnostic_log(
+        &mut self,
+        log: diagnostics::types::DiagnosticLog,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.run(async |m| m.read_diagnostic_log(log, offset, buf).await)
+    }
+
+    /// See [`Modem::nidd_configure`].
+    #[cfg(feature = "nidd")]
+    pub fn nidd_configure(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.nidd_configure().await)
+    }
+
+    /// See [`Modem::nidd_send`].
+    #[cfg(feature = "nidd")]
+    pub fn nidd_send(&mut self, attached: &Attached, data: &[u8]) -> Result<(), Error> {
+        self.run(async |m| m.nidd_send(attached, data).await)
+    }
+
+    /// See [`Modem::control_plane_send`].
+    #[cfg(feature = "nidd")]
+    pub fn control_plane_send(
+        &mut self,
+        attached: &Attached,
+        cid: u8,
+        data: &[u8],
+        rai: Option<nidd::types::ReleaseAssistance>,
+        user_data_type: Option<nidd::types::UserDataType>,
+    ) -> Result<(), Error> {
+        self.run(async |m| {
+            m.control_plane_send(attached, cid, data, rai, user_data_type)
+                .await
+        })
+    }
+
+    /// See [`Modem::nidd_receive`].
+    #[cfg(feature = "nidd")]
+    pub fn nidd_receive(&mut self, timeout: Duration) -> Result<nidd::urc::DataReceived, Error> {
+        self.run(async |m| m.nidd_receive(timeout).await)
+    }
+
+    /// See [`Modem::set_gnss_config`].
+    #[cfg(feature = "gnss")]
+    pub fn set_gnss_config(&mut self, sensitivity: FixSensitivity) -> Result<(), Error> {
+        self.run(async |m| m.set_gnss_config(sensitivity).await)
+    }
+
+    /// See [`Modem::update_gnss_asistance`].
+    #[cfg(feature = "gnss")]
+    pub fn update_gnss_asistance(&mut self, detached: Detached) -> Result<Detached, Error> {
+        self.run(async |m| m.update_gnss_asistance(detached).await)
+    }
+
+    /// See [`Modem::get_gnss_fix`].
+    #[cfg(feature = "gnss")]
+    pub fn get_gnss_fix(&mut self, detached: &Detached) -> Result<GnssFixReady, Error> {
+        self.run(async |m| m.get_gnss_fix(detached).await)
+    }
+
+    /// See [`Modem::mqtt_configure`].
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_configure(&mut self, client_id: &str, auth: Option<MqttAuth>) -> Result<(), Error> {
+        self.run(async |m| m.mqtt_configure(client_id, auth).await)
+    }
+
+    /// See [`Modem::mqtt_connect`].
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_connect(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        port: Option<u32>,
+    ) -> Result<(), Error> {
+        self.run(async |m| m.mqtt_connect(attached, host, port).await)
+    }
+
+    /// See [`Modem::aws_iot_connect`].
+    #[cfg(feature = "mqtt")]
+    pub fn aws_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AwsIotConfig<'_>,
+    ) -> Result<(), Error> {
+        self.run(async |m| m.aws_iot_connect(attached, config).await)
+    }
+
+    /// See [`Modem::azure_iot_connect`].
+    #[cfg(feature = "mqtt")]
+    pub fn azure_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AzureIotConfig<'_>,
+        sas_token: Option<&AzureSasToken>,
+    ) -> Result<(), Error> {
+        self.run(async |m| m.azure_iot_connect(attached, config, sas_token).await)
+    }
+
+    /// See [`Modem::mqtt_send`].
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_send(
+        &mut self,
+        topic: &str,
+        qos: mqtt::types::Qos,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        self.run(async |m| m.mqtt_send(topic, qos, data).await)
+    }
+
+    /// See [`Modem::mqtt_subscribe`].
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_subscribe(&mut self, topic: &str, qos: mqtt::types::Qos) -> Result<(), Error> {
+        self.run(async |m| m.mqtt_subscribe(topic, qos).await)
+    }
+
+    /// See [`Modem::mqtt_disconnect`].
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_disconnect(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.mqtt_disconnect().await)
+    }
+
+    /// See [`Modem::lwm2m_register`].
+    #[cfg(feature = "lwm2m")]
+    pub fn lwm2m_register(&mut self, attached: &Attached) -> Result<(), Error> {
+        self.run(async |m| m.lwm2m_register(attached).await)
+    }
+
+    /// See [`Modem::lwm2m_set_resource`].
+    #[cfg(feature = "lwm2m")]
+    pub fn lwm2m_set_resource(
+        &mut self,
+        object: u16,
+        instance: u16,
+        resource: u16,
+        value: &str,
+    ) -> Result<(), Error> {
+        self.run(async |m| {
+            m.lwm2m_set_resource(object, instance, resource, value)
+                .await
+        })
+    }
+
+    /// See [`Modem::lwm2m_disable`].
+    #[cfg(feature = "lwm2m")]
+    pub fn lwm2m_disable(&mut self) -> Result<(), Error> {
+        self.run(async |m| m.lwm2m_disable().await)
+    }
+}
@@ -0,0 +1,302 @@
+//! Time representation of the modem clock and the GNSS fixes.
+//!
+//! The times are [`jiff`] types by default. Disabling the `jiff` feature swaps in the minimal
+//! [`CalendarTime`] instead, e.g. on small targets where jiff doesn't fit:
+//!
+//! ```toml
+//! monarch2 = { version = "*", default-features = false, features = ["mqtt"] }
+//! ```
+
+use core::{fmt, ops::Range, str::FromStr};
+
+use serde::{Deserialize, Deserializer, de};
+
+use crate::device::responses::TimeParseError;
+
+/// Time of the modem clock, a [`jiff::Zoned`] or a [`CalendarTime`] without the `jiff` feature.
+#[cfg(feature = "jiff")]
+pub type ClockTime = jiff::Zoned;
+/// Time of the modem clock, a [`jiff::Zoned`] or a [`CalendarTime`] without the `jiff` feature.
+#[cfg(not(feature = "jiff"))]
+pub type ClockTime = CalendarTime;
+
+/// UTC time of a GNSS fix, a [`jiff::civil::DateTime`] or a [`CalendarTime`] without the `jiff`
+/// feature.
+#[cfg(feature = "jiff")]
+pub type FixTime = jiff::civil::DateTime;
+/// UTC time of a GNSS fix, a [`jiff::civil::DateTime`] or a [`CalendarTime`] without the `jiff`
+/// feature.
+#[cfg(not(feature = "jiff"))]
+pub type FixTime = CalendarTime;
+
+/// Date and time of day with its offset from UTC, to the second.
+///
+/// The accessors match the ones of [`jiff::Zoned`] so that code reading the modem clock builds
+/// with and without the `jiff` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct CalendarTime {
+    year: i16,
+    month: i8,
+    day: i8,
+    hour: i8,
+    minute: i8,
+    second: i8,
+    offset_seconds: i32,
+}
+
+impl CalendarTime {
+    /// Returns the given time, `None` if a field is out of range (e.g. the 30th of February or an
+    /// offset over a day).
+    pub fn new(
+        year: i16,
+        month: i8,
+        day: i8,
+        hour: i8,
+        minute: i8,
+        second: i8,
+        offset_seconds: i32,
+    ) -> Option<Self> {
+        let valid = (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && (0..24).contains(&hour)
+            && (0..60).contains(&minute)
+            && (0..60).contains(&second)
+            && offset_seconds.abs() <= 24 * 3600;
+
+        valid.then_some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            offset_seconds,
+        })
+    }
+
+    pub fn year(&self) -> i16 {
+        self.year
+    }
+
+    pub fn month(&self) -> i8 {
+        self.month
+    }
+
+    pub fn day(&self) -> i8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> i8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> i8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> i8 {
+        self.second
+    }
+
+    /// Offset from UTC, in seconds.
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset_seconds
+    }
+
+    /// Seconds since the Unix epoch.
+    pub fn unix_seconds(&self) -> i64 {
+        let days = days_from_civil(self.year as i64, self.month as i64, self.day as i64);
+        days * 86_400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+            - self.offset_seconds as i64
+    }
+
+    #[cfg(feature = "jiff")]
+    pub fn to_zoned(&self) -> jiff::Zoned {
+        // Can't fail, the fields and the offset are in range.
+        let offset = jiff::tz::Offset::from_seconds(self.offset_seconds).unwrap();
+        jiff::civil::date(self.year, self.month, self.day)
+            .at(self.hour, self.minute, self.second, 0)
+            .to_zoned(offset.to_time_zone())
+            .unwrap()
+    }
+}
+
+/// Formats the time in ISO 8601, e.g. "2025-06-24T15:55:20+02:00".
+impl fmt::Display for CalendarTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.offset_seconds < 0 { '-' } else { '+' };
+        let offset_minutes = self.offset_seconds.abs() / 60;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            sign,
+            offset_minutes / 60,
+            offset_minutes % 60
+        )
+    }
+}
+
+/// Parses an ISO 8601 time, e.g. "2025-06-24T15:55:20.000000" as reported with the GNSS fixes.
+///
+/// The fraction of the second is dropped, a missing offset is UTC.
+impl FromStr for CalendarTime {
+    type Err = TimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = |range: Range<usize>| s.get(range).ok_or(TimeParseError::InvalidFormat);
+        let separators = [(4, b'-'), (7, b'-'), (10, b'T'), (13, b':'), (16, b':')];
+        if separators
+            .iter()
+            .any(|&(i, c)| s.as_bytes().get(i) != Some(&c))
+        {
+            return Err(TimeParseError::InvalidFormat);
+        }
+
+        let mut rest = s.get(19..).ok_or(TimeParseError::InvalidFormat)?;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            rest = &fraction[digits..];
+        }
+
+        let offset_seconds = match rest.as_bytes().first() {
+            None | Some(b'Z') if rest.len() <= 1 => 0,
+            Some(&sign @ (b'+' | b'-')) if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+                let part =
+                    |range: Range<usize>| rest.get(range).ok_or(TimeParseError::InvalidFormat);
+                let seconds = parse_number(part(1..3)?)? as i32 * 3600
+                    + parse_number(part(4..6)?)? as i32 * 60;
+                if sign == b'-' { -seconds } else { seconds }
+            }
+            _ => return Err(TimeParseError::InvalidFormat),
+        };
+
+        CalendarTime::new(
+            parse_number(field(0..4)?)? as i16,
+            parse_number(field(5..7)?)? as i8,
+            parse_number(field(8..10)?)? as i8,
+            parse_number(field(11..13)?)? as i8,
+            parse_number(field(14..16)?)? as i8,
+            parse_number(field(17..19)?)? as i8,
+            offset_seconds,
+        )
+        .ok_or(TimeParseError::InvalidFormat)
+    }
+}
+
+impl<'de> Deserialize<'de> for CalendarTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = heapless::String::<32>::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Parses a decimal number of at most 4 digits, without sign.
+pub(crate) fn parse_number(s: &str) -> Result<u16, TimeParseError> {
+    if s.is_empty() || s.len() > 4 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(TimeParseError::InvalidFormat);
+    }
+    Ok(s.bytes().fold(0, |n, b| n * 10 + (b - b'0') as u16))
+}
+
+/// Seconds since the Unix epoch of the modem clock.
+pub(crate) fn unix_seconds(time: &ClockTime) -> i64 {
+    #[cfg(feature = "jiff")]
+    {
+        time.timestamp().as_second()
+    }
+    #[cfg(not(feature = "jiff"))]
+    {
+        time.unix_seconds()
+    }
+}
+
+/// Offset from UTC of the modem clock, in seconds.
+pub(crate) fn offset_seconds(time: &ClockTime) -> i32 {
+    #[cfg(feature = "jiff")]
+    {
+        time.offset().seconds()
+    }
+    #[cfg(not(feature = "jiff"))]
+    {
+        time.offset_seconds()
+    }
+}
+
+fn days_in_month(year: i16, month: i8) -> i8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Counts from March so that the leap day ends the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_seconds() {
+        let time = CalendarTime::new(1970, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(time.unix_seconds(), 0);
+
+        let time = CalendarTime::new(2024, 5, 30, 13, 22, 45, 2 * 3600).unwrap();
+        assert_eq!(time.unix_seconds(), 1_717_068_165);
+
+        let time = CalendarTime::new(2024, 2, 29, 0, 0, 0, 0).unwrap();
+        assert_eq!(time.unix_seconds(), 1_709_164_800);
+    }
+
+    #[test]
+    fn test_new_out_of_range() {
+        assert!(CalendarTime::new(2023, 2, 29, 0, 0, 0, 0).is_none());
+        assert!(CalendarTime::new(2024, 13, 1, 0, 0, 0, 0).is_none());
+        assert!(CalendarTime::new(2024, 1, 1, 24, 0, 0, 0).is_none());
+        assert!(CalendarTime::new(2024, 1, 1, 0, 0, 0, 25 * 3600).is_none());
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        let time: CalendarTime = "2025-06-24T15:55:20.000000".parse().unwrap();
+        assert_eq!(time, CalendarTime::new(2025, 6, 24, 15, 55, 20, 0).unwrap());
+
+        let time: CalendarTime = "2025-06-24T15:55:20-01:30".parse().unwrap();
+        assert_eq!(time.offset_seconds(), -5400);
+
+        assert!("2025-06-24 15:55:20".parse::<CalendarTime>().is_err());
+        assert!("2025-06-24T15:55".parse::<CalendarTime>().is_err());
+        assert!("2025-06-24T15:55:20+0100".parse::<CalendarTime>().is_err());
+        assert!("2025-06-24T15:55:20+é:00".parse::<CalendarTime>().is_err());
+        assert!("2025-06-24T15:55:2é".parse::<CalendarTime>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let time = CalendarTime::new(2025, 6, 24, 15, 55, 20, -5400).unwrap();
+        assert_eq!(time.to_string(), "2025-06-24T15:55:20-01:30");
+        assert_eq!(time.to_string().parse::<CalendarTime>(), Ok(time));
+    }
+}
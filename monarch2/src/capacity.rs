@@ -0,0 +1,76 @@
+//! Capacities of the owned strings used by the commands, responses and URCs.
+//!
+//! Every capacity can be overridden at build time with the environment variable named in its
+//! documentation, e.g. to shrink the buffers on small targets:
+//!
+//! ```sh
+//! MONARCH2_MQTT_TOPIC_LEN=64 cargo build
+//! ```
+//!
+//! Borrowed `&str` arguments are not limited by these capacities, only by the buffer of the
+//! [`atat`] client.
+
+use heapless::String;
+
+/// Capacity of the MQTT topics, `MONARCH2_MQTT_TOPIC_LEN` (default 256).
+pub const MQTT_TOPIC_LEN: usize = env_or(option_env!("MONARCH2_MQTT_TOPIC_LEN"), 256);
+
+/// Capacity of the MQTT username and password, `MONARCH2_MQTT_CREDENTIALS_LEN` (default 256).
+pub const MQTT_CREDENTIALS_LEN: usize = env_or(option_env!("MONARCH2_MQTT_CREDENTIALS_LEN"), 256);
+
+/// Capacity of the APN, `MONARCH2_APN_LEN` (default 64).
+pub const APN_LEN: usize = env_or(option_env!("MONARCH2_APN_LEN"), 64);
+
+/// Capacity of the PDP context username and password, `MONARCH2_PDP_CREDENTIALS_LEN`
+/// (default 64).
+pub const PDP_CREDENTIALS_LEN: usize = env_or(option_env!("MONARCH2_PDP_CREDENTIALS_LEN"), 64);
+
+/// Capacity of the lines of the unknown URCs, `MONARCH2_UNKNOWN_URC_LEN` (default 128), see
+/// [`UrcParser`](crate::UrcParser).
+pub const UNKNOWN_URC_LEN: usize = env_or(option_env!("MONARCH2_UNKNOWN_URC_LEN"), 128);
+
+/// MQTT topic.
+pub type MqttTopic = String<MQTT_TOPIC_LEN>;
+
+/// MQTT username or password.
+pub type MqttCredential = String<MQTT_CREDENTIALS_LEN>;
+
+/// Access point name.
+pub type Apn = String<APN_LEN>;
+
+/// PDP context username or password.
+pub type PdpCredential = String<PDP_CREDENTIALS_LEN>;
+
+/// Parses the capacity set by an environment variable, falls back to `default` when unset.
+const fn env_or(value: Option<&str>, default: usize) -> usize {
+    let Some(value) = value else {
+        return default;
+    };
+
+    let digits = value.as_bytes();
+    assert!(!digits.is_empty(), "capacity must not be empty");
+
+    let mut capacity = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        assert!(
+            digits[i].is_ascii_digit(),
+            "capacity must be a decimal number"
+        );
+        capacity = capacity * 10 + (digits[i] - b'0') as usize;
+        i += 1;
+    }
+    capacity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_or() {
+        assert_eq!(env_or(None, 256), 256);
+        assert_eq!(env_or(Some("64"), 256), 64);
+        assert_eq!(env_or(Some("1024"), 256), 1024);
+    }
+}
@@ -0,0 +1,218 @@
+use atat::{AtatCmd, UrcChannel};
+
+use crate::{
+    command::Urc,
+    modem::{Modem, UrcHandler},
+};
+
+/// Recommended capacity of the [`UrcChannel`], in URCs.
+///
+/// Every slot holds a whole [`Urc`], so each one costs [`URC_SIZE`], the size of the largest URC
+/// (over 1 kB with the `gnss-raw-data` feature).
+pub const URC_CAPACITY: usize = 4;
+
+/// Size of a slot of the [`UrcChannel`], depends on the enabled features.
+///
+/// The largest URCs are the GNSS fixes with the `gnss` feature, then the MQTT messages with
+/// their [`MQTT_TOPIC_LEN`](crate::MQTT_TOPIC_LEN) topic: disabling the unused subsystems or
+/// shrinking the topics shrinks every slot.
+pub const URC_SIZE: usize = core::mem::size_of::<Urc>();
+
+/// Number of [`UrcChannel`] subscribers used by the driver: the [`UrcHandler`] and up to three
+/// [`Modem`]s waiting for the URC completing a command at the same time, see
+/// [`Modem::send_and_wait_for`], e.g. the handles of a [`SharedModem`](crate::SharedModem).
+///
+/// Add one for every other subscription made by the application.
+pub const URC_SUBSCRIBERS: usize = 4;
+
+/// Length of a GNSS fix notification, with its raw data and satellite list.
+#[cfg(feature = "gnss")]
+const GNSS_FIX_LINE_LEN: usize = "+LPGNSSFIXREADY: ".len()
+    + 3 // fix id
+    + 28 // quoted timestamp
+    + 10 // time to fix
+    + 7 * 18 // quoted confidence, position and speeds
+    + crate::gnss::urc::GNSS_RAW_DATA_LEN + 2 // quoted raw data
+    + crate::gnss::urc::GNSS_SATELLITES_LEN // satellite list
+    + 11; // separators
+
+#[cfg(not(feature = "gnss"))]
+const GNSS_FIX_LINE_LEN: usize = 0;
+
+/// Length of an MQTT message notification, with its topic.
+#[cfg(feature = "mqtt")]
+const MQTT_MESSAGE_LINE_LEN: usize = "+SQNSMQTTONMESSAGE: ".len()
+    + 1 // client id
+    + crate::capacity::MQTT_TOPIC_LEN + 2 // quoted topic
+    + 5 // message length
+    + 1 // QoS
+    + 5 // message id
+    + 4; // separators
+
+#[cfg(not(feature = "mqtt"))]
+const MQTT_MESSAGE_LINE_LEN: usize = 0;
+
+/// Length of a NIDD data notification, with its hexadecimal payload.
+#[cfg(feature = "nidd")]
+const NIDD_DATA_LINE_LEN: usize = "+CRTDCP: ".len()
+    + 2 // cid
+    + 5 // data length
+    + 2 * crate::nidd::NIDD_PAYLOAD_LEN + 2 // quoted data
+    + 2; // separators
+
+#[cfg(not(feature = "nidd"))]
+const NIDD_DATA_LINE_LEN: usize = 0;
+
+/// Length of the other URC lines, e.g. network registration or CoAP connection notifications.
+const SHORT_URC_LINE_LEN: usize = 128;
+
+/// Largest of `lens`.
+const fn max(lens: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < lens.len() {
+        if lens[i] > max {
+            max = lens[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Length of the largest URC line sent by the modem: the GNSS fix with its raw data with the
+/// `gnss` feature, else an MQTT message notification with the `mqtt` feature.
+pub const MAX_URC_LEN: usize = max(&[
+    GNSS_FIX_LINE_LEN,
+    MQTT_MESSAGE_LINE_LEN,
+    NIDD_DATA_LINE_LEN,
+    SHORT_URC_LINE_LEN,
+]);
+
+/// Length of a SIM access response, with its hexadecimal data.
+const SIM_RESPONSE_LINE_LEN: usize = "+CRSM: ".len()
+    + 3 // status word 1
+    + 3 // status word 2
+    + crate::sim::responses::SIM_RESPONSE_LEN + 2 // quoted response
+    + 2; // separators
+
+/// Length of the data line of a socket read in the hexadecimal mode.
+#[cfg(feature = "socket")]
+const SOCKET_DATA_LINE_LEN: usize = 2 * crate::socket::SOCKET_RECV_LEN;
+
+#[cfg(not(feature = "socket"))]
+const SOCKET_DATA_LINE_LEN: usize = 0;
+
+/// Length of the data of an HTTP body read, after its marker.
+#[cfg(feature = "http")]
+const HTTP_DATA_LINE_LEN: usize = "<<<".len() + crate::http::HTTP_RECV_LEN;
+
+#[cfg(not(feature = "http"))]
+const HTTP_DATA_LINE_LEN: usize = 0;
+
+/// Length of the data line of a diagnostic log read.
+const LOG_DATA_LINE_LEN: usize = 2 * crate::diagnostics::LOG_READ_LEN;
+
+/// Length of the largest response line, a SIM access response with its hexadecimal data.
+pub const MAX_RESPONSE_LINE_LEN: usize = max(&[
+    SIM_RESPONSE_LINE_LEN,
+    SOCKET_DATA_LINE_LEN,
+    HTTP_DATA_LINE_LEN,
+    LOG_DATA_LINE_LEN,
+]);
+
+/// Length of the MQTT configuration, with the client id and credentials.
+#[cfg(feature = "mqtt")]
+const MQTT_CONFIGURE_LEN: usize = <crate::mqtt::Configure<'static> as AtatCmd>::MAX_LEN;
+
+#[cfg(not(feature = "mqtt"))]
+const MQTT_CONFIGURE_LEN: usize = 0;
+
+/// Length of an MQTT payload, copied in the command buffer.
+#[cfg(feature = "mqtt")]
+const MQTT_PUBLISH_LEN: usize = <crate::mqtt::Publish<'static> as AtatCmd>::MAX_LEN;
+
+#[cfg(not(feature = "mqtt"))]
+const MQTT_PUBLISH_LEN: usize = 0;
+
+/// Length of an HTTP request, with its resource.
+#[cfg(feature = "http")]
+const HTTP_QUERY_LEN: usize = <crate::http::Query<'static> as AtatCmd>::MAX_LEN;
+
+#[cfg(not(feature = "http"))]
+const HTTP_QUERY_LEN: usize = 0;
+
+/// Length of a NIDD payload sent with its hexadecimal data.
+#[cfg(feature = "nidd")]
+const NIDD_SEND_LEN: usize = <crate::nidd::SendData as AtatCmd>::MAX_LEN;
+
+#[cfg(not(feature = "nidd"))]
+const NIDD_SEND_LEN: usize = 0;
+
+/// Length of the largest command sent by the driver, to size the command buffer of the [`atat`]
+/// client, e.g. an MQTT payload or a slice of the data written to the NVM with
+/// [`Modem::nvm_write`].
+///
+/// The NVM entries are sent in slices of [`NVM_CHUNK_LEN`](crate::nvm::NVM_CHUNK_LEN) bytes. A
+/// payload larger than the buffer isn't sent and its command times out.
+pub const MAX_CMD_LEN: usize = max(&[
+    <crate::nvm::WriteChunk<'static> as AtatCmd>::MAX_LEN,
+    <crate::nvm::WriteLastChunk<'static> as AtatCmd>::MAX_LEN,
+    MQTT_CONFIGURE_LEN,
+    MQTT_PUBLISH_LEN,
+    HTTP_QUERY_LEN,
+    NIDD_SEND_LEN,
+    <crate::sim::RestrictedSimAccess<'static> as AtatCmd>::MAX_LEN,
+    <crate::ssl_tls::Configure as AtatCmd>::MAX_LEN,
+    <crate::pdp::DefinePDPContext as AtatCmd>::MAX_LEN,
+    crate::raw::MAX_RAW_CMD_LEN,
+]);
+
+/// Recommended size of the ingress buffer, fitting the largest URC and response lines.
+#[cfg(feature = "gnss")]
+pub const INGRESS_BUF_SIZE: usize = 2048;
+
+/// Recommended size of the ingress buffer, fitting the largest URC and response lines.
+#[cfg(not(feature = "gnss"))]
+pub const INGRESS_BUF_SIZE: usize = 1024;
+
+// A line longer than the ingress buffer is dropped, so a capacity raised past it (e.g. the
+// `MONARCH2_MQTT_TOPIC_LEN` override) must fail the build rather than lose the URCs.
+const _: () = {
+    assert!(
+        INGRESS_BUF_SIZE >= MAX_URC_LEN,
+        "INGRESS_BUF_SIZE doesn't fit the largest URC line"
+    );
+    assert!(
+        INGRESS_BUF_SIZE >= MAX_RESPONSE_LINE_LEN,
+        "INGRESS_BUF_SIZE doesn't fit the largest response line"
+    );
+};
+
+/// Fails the build if an ingress buffer of `B` bytes, e.g. the one of the transports, doesn't fit
+/// the largest URC and response lines.
+#[cfg(any(feature = "serial", feature = "walter"))]
+pub(crate) fn assert_ingress_buf_size<const B: usize>() {
+    const {
+        assert!(
+            B >= MAX_URC_LEN,
+            "the ingress buffer doesn't fit the largest URC line"
+        );
+        assert!(
+            B >= MAX_RESPONSE_LINE_LEN,
+            "the ingress buffer doesn't fit the largest response line"
+        );
+    }
+}
+
+/// [`UrcChannel`] sized with the recommended constants.
+///
+/// ```ignore
+/// static URC_CHANNEL: DefaultUrcChannel = DefaultUrcChannel::new();
+/// ```
+pub type DefaultUrcChannel = UrcChannel<Urc, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+/// [`Modem`] using a [`DefaultUrcChannel`].
+pub type DefaultModem<'a, AtCl> = Modem<'a, AtCl, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+/// [`UrcHandler`] of a [`DefaultModem`].
+pub type DefaultUrcHandler<'a> = UrcHandler<'a, URC_CAPACITY, URC_SUBSCRIBERS>;
@@ -0,0 +1,270 @@
+//! Frames of the basic option of 3GPP TS 27.010.
+//!
+//! A frame is `F9 <address> <control> <length> <information> <FCS> F9`. The length takes one
+//! byte up to 127 information bytes, two bytes above.
+
+/// Opening and closing flag of a frame.
+pub const FLAG: u8 = 0xF9;
+
+/// Bytes of a frame besides its information: flags, address, control, two length bytes and FCS.
+pub const MAX_OVERHEAD: usize = 7;
+
+/// Poll/final bit of the control field.
+const PF: u8 = 0x10;
+
+/// Type of a frame, from its control field without the poll/final bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameKind {
+    /// Set asynchronous balanced mode, opens a channel.
+    Sabm,
+    /// Unnumbered acknowledgement.
+    Ua,
+    /// Disconnected mode, the channel is refused or closed.
+    Dm,
+    /// Disconnect, closes a channel.
+    Disc,
+    /// Unnumbered information with header check, carries the data of the channels.
+    Uih,
+    /// Unnumbered information.
+    Ui,
+}
+
+impl FrameKind {
+    fn control(self) -> u8 {
+        match self {
+            Self::Sabm => 0x2F,
+            Self::Ua => 0x63,
+            Self::Dm => 0x0F,
+            Self::Disc => 0x43,
+            Self::Uih => 0xEF,
+            Self::Ui => 0x03,
+        }
+    }
+
+    fn from_control(control: u8) -> Option<Self> {
+        [
+            Self::Sabm,
+            Self::Ua,
+            Self::Dm,
+            Self::Disc,
+            Self::Uih,
+            Self::Ui,
+        ]
+        .into_iter()
+        .find(|kind| kind.control() == control & !PF)
+    }
+}
+
+/// Frame decoded by [`decode`], borrowing its information.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Frame<'a> {
+    /// Data link connection identifier, 0 is the control channel.
+    pub dlci: u8,
+    pub kind: FrameKind,
+    /// Poll/final bit.
+    pub pf: bool,
+    pub info: &'a [u8],
+}
+
+/// Outcome of [`decode`].
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Decoded<'a> {
+    /// A frame, followed by the number of bytes it took. Its closing flag is left, it may open
+    /// the next frame.
+    Frame(Frame<'a>, usize),
+    /// The data ends before the frame, more must be read.
+    Incomplete,
+    /// The data doesn't start with a valid frame, the given number of bytes must be skipped.
+    Invalid(usize),
+}
+
+/// Decodes the frame at the start of `data`.
+pub fn decode(data: &[u8]) -> Decoded<'_> {
+    if data.first().is_some_and(|&b| b != FLAG) {
+        // Skips to the next flag, e.g. the rest of a frame whose start was lost.
+        let skip = data.iter().position(|&b| b == FLAG).unwrap_or(data.len());
+        return Decoded::Invalid(skip);
+    }
+    if data.get(1) == Some(&FLAG) {
+        // Closing flag of the previous frame.
+        return Decoded::Invalid(1);
+    }
+    let [_, address, control, length, ..] = *data else {
+        return Decoded::Incomplete;
+    };
+
+    let (len, header_len) = if length & 1 != 0 {
+        ((length >> 1) as usize, 3)
+    } else {
+        let Some(&high) = data.get(4) else {
+            return Decoded::Incomplete;
+        };
+        ((length >> 1) as usize | (high as usize) << 7, 4)
+    };
+    let total = 1 + header_len + len + 2;
+    if data.len() < total {
+        return Decoded::Incomplete;
+    }
+
+    let header = &data[1..1 + header_len];
+    let info = &data[1 + header_len..1 + header_len + len];
+    let (Some(kind), true, true) = (
+        FrameKind::from_control(control),
+        address & 1 != 0 && fcs(header) == data[total - 2],
+        data[total - 1] == FLAG,
+    ) else {
+        return Decoded::Invalid(1);
+    };
+
+    let frame = Frame {
+        dlci: address >> 2,
+        kind,
+        pf: control & PF != 0,
+        info,
+    };
+    Decoded::Frame(frame, total - 1)
+}
+
+/// Encodes a frame sent by the host, the initiator of the multiplexer, into `buf`.
+///
+/// Returns the length of the frame, `None` if `buf` is too small. The information is at most
+/// 32767 bytes, the length field can't tell more.
+pub fn encode(dlci: u8, kind: FrameKind, pf: bool, info: &[u8], buf: &mut [u8]) -> Option<usize> {
+    let len = info.len();
+    if len > 0x7FFF {
+        return None;
+    }
+
+    let mut header = [0; 4];
+    // EA and C/R bits set, the host sends the commands.
+    header[0] = dlci << 2 | 0x03;
+    header[1] = kind.control() | if pf { PF } else { 0 };
+    let header_len = if len <= 127 {
+        header[2] = (len as u8) << 1 | 1;
+        3
+    } else {
+        header[2] = (len as u8) << 1;
+        header[3] = (len >> 7) as u8;
+        4
+    };
+    let header = &header[..header_len];
+
+    let total = 1 + header_len + len + 2;
+    let frame = buf.get_mut(..total)?;
+    frame[0] = FLAG;
+    frame[1..1 + header_len].copy_from_slice(header);
+    frame[1 + header_len..1 + header_len + len].copy_from_slice(info);
+    frame[total - 2] = fcs(header);
+    frame[total - 1] = FLAG;
+    Some(total)
+}
+
+/// Frame check sequence, the reversed CRC-8 (polynomial 0x07) of the header.
+fn fcs(header: &[u8]) -> u8 {
+    0xFF - header
+        .iter()
+        .fold(0xFF, |crc, &b| CRC_TABLE[(crc ^ b) as usize])
+}
+
+const CRC_TABLE: [u8; 256] = crc_table();
+
+const fn crc_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xE0
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let mut buf = [0; 16];
+
+        let len = encode(0, FrameKind::Sabm, true, &[], &mut buf).unwrap();
+        assert_eq!(&buf[..len], [0xF9, 0x03, 0x3F, 0x01, 0x1C, 0xF9]);
+
+        let len = encode(1, FrameKind::Uih, false, b"AT\r\n", &mut buf).unwrap();
+        assert_eq!(
+            &buf[..len],
+            [0xF9, 0x07, 0xEF, 0x09, b'A', b'T', b'\r', b'\n', 0x39, 0xF9]
+        );
+
+        assert_eq!(encode(1, FrameKind::Uih, false, &[0; 11], &mut buf), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        let ua = [0xF9, 0x03, 0x73, 0x01, 0xD7, 0xF9];
+        assert_eq!(
+            decode(&ua),
+            Decoded::Frame(
+                Frame {
+                    dlci: 0,
+                    kind: FrameKind::Ua,
+                    pf: true,
+                    info: &[],
+                },
+                5
+            )
+        );
+
+        let ok = [
+            0xF9, 0x05, 0xEF, 0x0D, b'\r', b'\n', b'O', b'K', b'\r', b'\n', 0x5F, 0xF9,
+        ];
+        assert_eq!(
+            decode(&ok),
+            Decoded::Frame(
+                Frame {
+                    dlci: 1,
+                    kind: FrameKind::Uih,
+                    pf: false,
+                    info: b"\r\nOK\r\n",
+                },
+                11
+            )
+        );
+        assert_eq!(decode(&ok[..8]), Decoded::Incomplete);
+
+        // Garbage before the frame, the closing flag of the previous one, a corrupted FCS.
+        assert_eq!(decode(&[0x00, 0x01, 0xF9, 0x03]), Decoded::Invalid(2));
+        assert_eq!(decode(&[0xF9, 0xF9, 0x03]), Decoded::Invalid(1));
+        assert_eq!(
+            decode(&[0xF9, 0x03, 0x73, 0x01, 0xD8, 0xF9]),
+            Decoded::Invalid(1)
+        );
+    }
+
+    #[test]
+    fn test_long_frame_roundtrip() {
+        let info = [0x41; 200];
+        let mut buf = [0; 200 + MAX_OVERHEAD];
+        let len = encode(2, FrameKind::Uih, false, &info, &mut buf).unwrap();
+        assert_eq!(len, 200 + MAX_OVERHEAD);
+        assert_eq!(&buf[3..5], [0x90, 0x01]);
+
+        let Decoded::Frame(frame, consumed) = decode(&buf[..len]) else {
+            panic!("frame not decoded");
+        };
+        assert_eq!((frame.dlci, frame.info), (2, &info[..]));
+        assert_eq!(consumed, len - 1);
+    }
+}
@@ -0,0 +1,286 @@
+//! 3GPP TS 27.010 multiplexer (CMUX), sharing the UART between the AT commands and data channels,
+//! e.g. a PPP session.
+//!
+//! Once [`EnableMultiplexer`](crate::device::EnableMultiplexer) is answered, the modem only
+//! exchanges frames. The [`Mux`] runs two tasks on the halves of the UART, one splitting the
+//! received frames into the channels, the other framing the data written to them. Every channel
+//! is an [`embedded_io_async`] stream: the channel 1 is given to the ingress and the AT client in
+//! place of the UART, the next ones to the application.
+//!
+//! ```ignore
+//! static MUX: Mux<CriticalSectionRawMutex, 2, 256> = Mux::new();
+//!
+//! modem.send(&EnableMultiplexer {
+//!     mode: 0,
+//!     subset: 0,
+//!     port_speed: PortSpeed::B115200,
+//!     max_frame_size: 127,
+//! }).await?;
+//!
+//! spawner.spawn(mux_rx(&MUX, uart_rx))?; // MUX.run_rx(uart_rx).await
+//! spawner.spawn(mux_tx(&MUX, uart_tx))?; // MUX.run_tx(uart_tx, 127).await
+//! let at = MUX.channel(1);
+//! let data = MUX.channel(2);
+//! ```
+//!
+//! Only the basic option with UIH frames is supported. The channels are opened without waiting
+//! for the modem to acknowledge them, and nothing is negotiated: the frame size passed to
+//! [`Mux::run_tx`] must not exceed the one of the command.
+
+mod frame;
+
+use core::convert::Infallible;
+
+use embassy_futures::select::{Either, select};
+use embassy_sync::{blocking_mutex::raw::RawMutex, pipe::Pipe, signal::Signal};
+use embedded_io_async::{ErrorType, Read, Write};
+
+pub use frame::{Decoded, Frame, FrameKind, MAX_OVERHEAD, decode, encode};
+
+/// Close-down command of the multiplexer, sent on the control channel.
+const CLOSE_DOWN: [u8; 2] = [0xC3, 0x01];
+
+/// Multiplexer of `C` channels, numbered from 1, buffering `B` bytes per channel and direction.
+///
+/// `B` also bounds the frames: it must hold the largest frame of the modem, its information and
+/// [`MAX_OVERHEAD`].
+pub struct Mux<M: RawMutex, const C: usize, const B: usize> {
+    rx: [Pipe<M, B>; C],
+    tx: [Pipe<M, B>; C],
+    tx_ready: Signal<M, ()>,
+    close: Signal<M, ()>,
+}
+
+impl<M: RawMutex, const C: usize, const B: usize> Mux<M, C, B> {
+    pub const fn new() -> Self {
+        Self {
+            rx: [const { Pipe::new() }; C],
+            tx: [const { Pipe::new() }; C],
+            tx_ready: Signal::new(),
+            close: Signal::new(),
+        }
+    }
+
+    /// Returns the channel `dlci`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dlci` is not between 1 and `C`.
+    pub fn channel(&self, dlci: u8) -> MuxChannel<'_, M, B> {
+        assert!(
+            (1..=C).contains(&(dlci as usize)),
+            "no multiplexer channel {}",
+            dlci
+        );
+        let index = dlci as usize - 1;
+        MuxChannel {
+            rx: &self.rx[index],
+            tx: &self.tx[index],
+            tx_ready: &self.tx_ready,
+        }
+    }
+
+    /// Closes the multiplexer down once the data written to the channels is sent, the modem is
+    /// back to AT commands on the UART. [`Mux::run_tx`] returns then.
+    pub fn close(&self) {
+        self.close.signal(());
+    }
+
+    /// Reads the frames of the modem from `reader` into the channels, until `reader` ends.
+    ///
+    /// The invalid frames are skipped, the frames of unknown channels dropped. A channel that
+    /// isn't read holds the others back once its buffer is full.
+    pub async fn run_rx<R: Read>(&self, mut reader: R) -> Result<(), R::Error> {
+        let mut buf = [0; B];
+        let mut len = 0;
+
+        loop {
+            let n = reader.read(&mut buf[len..]).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            len += n;
+
+            let mut start = 0;
+            loop {
+                match decode(&buf[start..len]) {
+                    Decoded::Frame(frame, consumed) => {
+                        self.dispatch(frame).await;
+                        start += consumed;
+                    }
+                    Decoded::Invalid(skip) => start += skip,
+                    Decoded::Incomplete => break,
+                }
+            }
+            buf.copy_within(start..len, 0);
+            len -= start;
+
+            if len == B {
+                warn!("Multiplexer frame larger than {} bytes, dropped", B);
+                len = 0;
+            }
+        }
+    }
+
+    async fn dispatch(&self, frame: Frame<'_>) {
+        let channel = (frame.dlci as usize)
+            .checked_sub(1)
+            .and_then(|i| self.rx.get(i));
+        match (frame.kind, channel) {
+            (FrameKind::Uih | FrameKind::Ui, Some(pipe)) => {
+                let mut info = frame.info;
+                while !info.is_empty() {
+                    let n = pipe.write(info).await;
+                    info = &info[n..];
+                }
+            }
+            (FrameKind::Dm, _) => warn!("Multiplexer channel {} refused", frame.dlci),
+            _ => trace!("Multiplexer frame {:?} ignored", frame.kind),
+        }
+    }
+
+    /// Opens the channels, then writes the data of the channels to `writer` in frames of at most
+    /// `max_frame_size` information bytes, until [`Mux::close`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a frame of `max_frame_size` doesn't fit in `B` bytes.
+    pub async fn run_tx<W: Write>(
+        &self,
+        mut writer: W,
+        max_frame_size: usize,
+    ) -> Result<(), W::Error> {
+        assert!(
+            max_frame_size + MAX_OVERHEAD <= B,
+            "multiplexer frames larger than the buffers"
+        );
+        let mut frame = [0; B];
+        let mut info = [0; B];
+
+        // The control channel first, then the channels.
+        for dlci in 0..=C as u8 {
+            let len = encode(dlci, FrameKind::Sabm, true, &[], &mut frame).unwrap();
+            writer.write_all(&frame[..len]).await?;
+        }
+
+        loop {
+            let mut idle = true;
+            for (index, pipe) in self.tx.iter().enumerate() {
+                let Ok(n) = pipe.try_read(&mut info[..max_frame_size]) else {
+                    continue;
+                };
+                let dlci = index as u8 + 1;
+                let len = encode(dlci, FrameKind::Uih, false, &info[..n], &mut frame).unwrap();
+                writer.write_all(&frame[..len]).await?;
+                idle = false;
+            }
+
+            if idle {
+                writer.flush().await?;
+                if let Either::Second(()) = select(self.tx_ready.wait(), self.close.wait()).await {
+                    break;
+                }
+            }
+        }
+
+        let len = encode(0, FrameKind::Uih, false, &CLOSE_DOWN, &mut frame).unwrap();
+        writer.write_all(&frame[..len]).await?;
+        writer.flush().await
+    }
+}
+
+impl<M: RawMutex, const C: usize, const B: usize> Default for Mux<M, C, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Channel of a [`Mux`], a stream of the data of one DLCI.
+pub struct MuxChannel<'a, M: RawMutex, const B: usize> {
+    rx: &'a Pipe<M, B>,
+    tx: &'a Pipe<M, B>,
+    tx_ready: &'a Signal<M, ()>,
+}
+
+impl<M: RawMutex, const B: usize> ErrorType for MuxChannel<'_, M, B> {
+    type Error = Infallible;
+}
+
+impl<M: RawMutex, const B: usize> Read for MuxChannel<'_, M, B> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        Ok(self.rx.read(buf).await)
+    }
+}
+
+impl<M: RawMutex, const B: usize> Write for MuxChannel<'_, M, B> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let n = self.tx.write(buf).await;
+        self.tx_ready.signal(());
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+    use super::*;
+
+    #[test]
+    fn test_run_rx() {
+        let mux = Mux::<NoopRawMutex, 2, 64>::new();
+        let received = [
+            // Garbage, UA of the control channel, "OK" on the channel 1, "data" on the channel 2.
+            &[0x00, 0x01][..],
+            &[0xF9, 0x03, 0x73, 0x01, 0xD7, 0xF9],
+            &[
+                0xF9, 0x05, 0xEF, 0x0D, b'\r', b'\n', b'O', b'K', b'\r', b'\n', 0x5F, 0xF9,
+            ],
+            &[0xF9, 0x09, 0xEF, 0x09, b'd', b'a', b't', b'a', 0xDF, 0xF9],
+        ]
+        .concat();
+
+        block_on(mux.run_rx(&received[..])).unwrap();
+
+        let mut buf = [0; 16];
+        let n = block_on(mux.channel(1).read(&mut buf)).unwrap();
+        assert_eq!(&buf[..n], b"\r\nOK\r\n");
+        let n = block_on(mux.channel(2).read(&mut buf)).unwrap();
+        assert_eq!(&buf[..n], b"data");
+    }
+
+    #[test]
+    fn test_run_tx() {
+        let mux = Mux::<NoopRawMutex, 2, 64>::new();
+        block_on(mux.channel(1).write_all(b"AT\r\n")).unwrap();
+        mux.close();
+
+        let mut sent = [0; 64];
+        let mut writer = &mut sent[..];
+        block_on(mux.run_tx(&mut writer, 16)).unwrap();
+        let len = 64 - writer.len();
+
+        let expected = [
+            // SABM of the control channel and the channels 1 and 2.
+            &[0xF9, 0x03, 0x3F, 0x01, 0x1C, 0xF9][..],
+            &[0xF9, 0x07, 0x3F, 0x01, 0xDE, 0xF9],
+            &[0xF9, 0x0B, 0x3F, 0x01, 0x59, 0xF9],
+            &[0xF9, 0x07, 0xEF, 0x09, b'A', b'T', b'\r', b'\n', 0x39, 0xF9],
+            // Close-down.
+            &[0xF9, 0x03, 0xEF, 0x05, 0xC3, 0x01, 0xF2, 0xF9],
+        ]
+        .concat();
+        assert_eq!(&sent[..len], expected);
+    }
+}
@@ -0,0 +1,92 @@
+use atat::atat_derive::AtatEnum;
+
+use crate::error::Error;
+
+/// CoAP message type.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum MessageType {
+    Confirmable = 0,
+    NonConfirmable = 1,
+    /// Acknowledgement, also confirms the delivery of a confirmable message sent.
+    Acknowledgement = 2,
+    Reset = 3,
+}
+
+/// Class of a CoAP [`ResponseCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResponseClass {
+    /// 2.xx, the request succeeded.
+    Success,
+    /// 4.xx, the request was rejected by the server.
+    ClientError,
+    /// 5.xx, the server failed to handle a valid request.
+    ServerError,
+    /// Any other class, not used by responses.
+    Other(u8),
+}
+
+/// CoAP response code `c.dd`, reported by the modem as `c * 100 + dd`, e.g. 404 for 4.04 Not Found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ResponseCode(pub u16);
+
+impl ResponseCode {
+    pub const CREATED: Self = Self(201);
+    pub const DELETED: Self = Self(202);
+    pub const VALID: Self = Self(203);
+    pub const CHANGED: Self = Self(204);
+    pub const CONTENT: Self = Self(205);
+    pub const BAD_REQUEST: Self = Self(400);
+    pub const UNAUTHORIZED: Self = Self(401);
+    pub const FORBIDDEN: Self = Self(403);
+    pub const NOT_FOUND: Self = Self(404);
+    pub const METHOD_NOT_ALLOWED: Self = Self(405);
+    pub const REQUEST_ENTITY_TOO_LARGE: Self = Self(413);
+    pub const INTERNAL_SERVER_ERROR: Self = Self(500);
+    pub const SERVICE_UNAVAILABLE: Self = Self(503);
+    pub const GATEWAY_TIMEOUT: Self = Self(504);
+
+    /// Returns the class of the code.
+    pub fn class(&self) -> ResponseClass {
+        match self.0 / 100 {
+            2 => ResponseClass::Success,
+            4 => ResponseClass::ClientError,
+            5 => ResponseClass::ServerError,
+            class => ResponseClass::Other(class as u8),
+        }
+    }
+
+    /// Returns the detail of the code, e.g. 4 for 4.04.
+    pub fn detail(&self) -> u8 {
+        (self.0 % 100) as u8
+    }
+
+    /// Turns the error classes into [`Error::Coap`].
+    pub fn check(self) -> Result<Self, Error> {
+        match self.class() {
+            ResponseClass::Success => Ok(self),
+            _ => Err(Error::Coap(self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_code() {
+        assert_eq!(ResponseCode::CONTENT.class(), ResponseClass::Success);
+        assert_eq!(ResponseCode::NOT_FOUND.class(), ResponseClass::ClientError);
+        assert_eq!(ResponseCode::NOT_FOUND.detail(), 4);
+        assert_eq!(ResponseCode(205).check(), Ok(ResponseCode::CONTENT));
+        assert_eq!(
+            ResponseCode(503).check(),
+            Err(Error::Coap(ResponseCode::SERVICE_UNAVAILABLE))
+        );
+    }
+}
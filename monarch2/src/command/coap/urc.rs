@@ -1,9 +1,12 @@
 use atat::atat_derive::AtatResp;
+use heapless::String;
 
+use super::types::{MessageType, ResponseCode};
 use crate::types::Bool;
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Connected {
     /// Profile id.
     #[at_arg(position = 0)]
@@ -11,7 +14,7 @@ pub struct Connected {
 
     /// Connection return code.
     #[at_arg(position = 1)]
-    pub server_address: heapless::String<64>,
+    pub server_address: String<64>,
 
     #[at_arg(position = 2)]
     pub port: u16,
@@ -22,3 +25,75 @@ pub struct Connected {
     #[at_arg(position = 4)]
     pub dtls_enabled: Bool,
 }
+
+/// The connection of a CoAP profile was closed.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Closed {
+    /// Profile id.
+    #[at_arg(position = 0)]
+    pub id: u8,
+
+    /// Reason of the closure, if reported.
+    #[at_arg(position = 1)]
+    pub reason: Option<String<32>>,
+}
+
+/// A CoAP message was received on a profile, it is read with AT+SQNCOAPRCV.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Ring {
+    /// Profile id.
+    #[at_arg(position = 0)]
+    pub id: u8,
+
+    /// Message id.
+    #[at_arg(position = 1)]
+    pub msg_id: u16,
+
+    /// Message type.
+    #[at_arg(position = 2)]
+    pub typ: MessageType,
+
+    /// Method of a request (1 GET, 2 POST, 3 PUT, 4 DELETE) or code of a response, e.g. 205
+    /// for 2.05 Content.
+    #[at_arg(position = 3)]
+    pub code: u16,
+
+    /// Payload length in bytes.
+    #[at_arg(position = 4)]
+    pub length: u16,
+}
+
+impl Ring {
+    /// Returns the response code, `None` for a request.
+    pub fn response_code(&self) -> Option<ResponseCode> {
+        // The requests carry their method, 1 to 4.
+        (self.code >= 200).then_some(ResponseCode(self.code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_ring_parse() {
+        let res: Ring = from_str("+SQNCOAPRING: 0,4711,2,205,12").unwrap();
+        assert_eq!(res.msg_id, 4711);
+        assert_eq!(res.typ, MessageType::Acknowledgement);
+        assert_eq!(res.code, 205);
+        assert_eq!(res.response_code(), Some(ResponseCode::CONTENT));
+        assert_eq!(res.length, 12);
+    }
+
+    #[test]
+    fn test_closed_parse() {
+        let res: Closed = from_str("+SQNCOAPCLOSED: 1,\"DTLS handshake failed\"").unwrap();
+        assert_eq!(res.id, 1);
+        assert_eq!(res.reason.as_deref(), Some("DTLS handshake failed"));
+    }
+}
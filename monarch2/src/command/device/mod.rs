@@ -1,8 +1,17 @@
+use core::fmt::Write;
+
 use atat::atat_derive::AtatCmd;
-use responses::{ActiveRAT, Clock};
-use types::RAT;
+use heapless::String;
+use responses::{
+    ActiveRAT, Autoconnect, CarrierProfile, Clock, FirmwareVersion, Imei, ManufacturerId, ModelId,
+};
+use types::{PortSpeed, RAT};
 
 use super::NoResponse;
+use crate::{
+    calendar::{self, ClockTime},
+    types::Bool,
+};
 
 pub mod responses;
 pub mod types;
@@ -19,19 +28,23 @@ pub mod urc;
 // Attention: The manufacturing command AT+SQNFACTORYSAVE must be used during the manufacturing process to define a restoration point for the AT+SQNSFACTORYRESET. Failing to create a restoration point can result in undefined behaviour.
 //
 // See also Mobile Termination Error Result Code: +CME ERROR (on page 282) for <err› values.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+SQNSFACTORYRESET", NoResponse)]
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct FactoryReset;
 
+static_cmd!(FactoryReset, "+SQNSFACTORYRESET", NoResponse);
+
 /// This command causes the device to detach from the network and shut down. Before turning off, it returns a final acknowledgement. This command proceeds despite any active or pending activity. The device does not accept any further command.
 ///
 /// Attention: On restart, the module MUST be reset using the RESETN line. Powering the power up is not enough to reboot the module.
 ///
 /// See also Mobile Termination Error Result Code: +CME ERROR (on page 282) for <err > values.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+SQNSSHDN", NoResponse, timeout = 1000)]
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Shutdown;
 
+static_cmd!(Shutdown, "+SQNSSHDN", NoResponse, timeout = 1000);
+
 /// This command causes device to revert to a previously saved state.
 ///
 /// This factory reset rewinds all non-volatile parameters of the module back to the last restoration point set by Save Module Configuration: AT+SQNFACTORYSAVE. The detail of the restoration point please refer to Save Module Configuration: AT+SQNFACTORYSAVE. If no restoration point has been created, the parameters are overwritten with their factory defaults.
@@ -41,19 +54,97 @@ pub struct Shutdown;
 /// A reboot is needed to commit the command.
 ///
 /// Attention: The manufacturing command AT+SQNFACTORYSAVE must be used during the manufacturing process to define a restoration point for the AT+SQNSFACTORYRESET. Failing to create a restoration point can result in undefined behaviour.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+SQNSFACTORYRESET", NoResponse, timeout = 10000)]
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ResetToFactoryState;
 
+static_cmd!(
+    ResetToFactoryState,
+    "+SQNSFACTORYRESET",
+    NoResponse,
+    timeout = 10000
+);
+
 /// Returns the current time.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+CCLK?", Clock)]
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetClock;
 
+static_cmd!(GetClock, "+CCLK?", Clock);
+
+/// Sets the modem clock, e.g. from the RTC of the host or an NTP client, so that GNSS assisted
+/// fixes are possible without attaching to the LTE network first.
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+CCLK", NoResponse)]
+pub struct SetClock {
+    /// Time formatted as "yy/MM/dd,hh:mm:ss+zz", the offset in quarters of an hour, see
+    /// [`SetClock::new`].
+    #[at_arg(position = 0, len = 20)]
+    pub time: String<20>,
+}
+
+impl SetClock {
+    /// Sets the clock to `time`, the offset is truncated to quarters of an hour.
+    pub fn new(time: &ClockTime) -> Self {
+        let offset_q = calendar::offset_seconds(time) / (15 * 60);
+        let sign = if offset_q < 0 { '-' } else { '+' };
+
+        let mut s = String::new();
+        // Can't fail, the formatted time always fits.
+        let _ = write!(
+            s,
+            "{:02}/{:02}/{:02},{:02}:{:02}:{:02}{}{:02}",
+            time.year() % 100,
+            time.month(),
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second(),
+            sign,
+            offset_q.abs()
+        );
+        Self { time: s }
+    }
+}
+
+/// Enables or disables the automatic update of the clock and time zone from the network (NITZ).
+///
+/// The setting is applied by [`Modem::begin`](crate::Modem::begin) from
+/// [`ModemConfig::automatic_time_zone_update`](crate::ModemConfig::automatic_time_zone_update).
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+CTZU", NoResponse)]
+pub struct ConfigureAutomaticTimeZoneUpdate {
+    #[at_arg(position = 0)]
+    pub enabled: Bool,
+}
+
+/// Synchronizes the modem clock with an NTP server.
+///
+/// The PDN must be active. The synchronization completes asynchronously, the +SQNNTP URC
+/// ([`NtpSynchronized`](urc::NtpSynchronized)) reports its result.
+///
+/// Type: `asynchronous`
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+SQNMODEACTIVE?", ActiveRAT)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SQNNTP", NoResponse)]
+pub struct SynchronizeTimeNtp<'a> {
+    /// Host name or IP address of the NTP server.
+    #[at_arg(position = 0, len = 256)]
+    pub server: &'a str,
+
+    /// UDP port of the NTP server, 123 by default.
+    #[at_arg(position = 1)]
+    pub port: Option<u16>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetOperatingMode;
 
+static_cmd!(GetOperatingMode, "+SQNMODEACTIVE?", ActiveRAT);
+
 /// This command chooses the operating mode between LTE-M and NB-loT
 /// on a device when both LTE-M and NB-IoT are allowed.
 /// This command can be run only if the device is in CFUN=0 state.
@@ -71,8 +162,143 @@ pub struct GetOperatingMode;
 /// Trying to switch the mode of operation when in CFUN=1 state returns +CME ERROR 591
 /// (Device is in active state).
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+SQNMODEACTIVE", NoResponse)]
 pub struct SetOperatingMode {
     #[at_arg(position = 0)]
     pub mode: RAT,
 }
+
+/// Reads whether the modem attaches to the network on its own after it starts.
+///
+/// Type: `synchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetAutoconnect;
+
+static_cmd!(GetAutoconnect, "+SQNAUTOCONNECT?", Autoconnect);
+
+/// Enables or disables the autonomous attach of the modem after it starts (CFUN=1 at boot).
+///
+/// The setting persists at reboot.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SQNAUTOCONNECT", NoResponse)]
+pub struct SetAutoconnect {
+    #[at_arg(position = 0)]
+    pub enabled: Bool,
+}
+
+/// Requests the manufacturer identification.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetManufacturerId;
+
+static_cmd!(GetManufacturerId, "+CGMI", ManufacturerId);
+
+/// Requests the model identification.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetModelId;
+
+static_cmd!(GetModelId, "+CGMM", ModelId);
+
+/// Requests the International Mobile Equipment Identity (IMEI).
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetImei;
+
+static_cmd!(GetImei, "+CGSN", Imei);
+
+/// Requests the firmware version of the modem.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetFirmwareVersion;
+
+static_cmd!(GetFirmwareVersion, "+CGMR", FirmwareVersion);
+
+/// Restarts the modem, which reports it started with the +SYSSTART URC.
+///
+/// Type: `synchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Reboot;
+
+static_cmd!(Reboot, "^RESET", NoResponse);
+
+/// Reads the carrier configuration profile (conformance test mode) of the modem.
+///
+/// Type: `synchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetCarrierProfile;
+
+static_cmd!(GetCarrierProfile, "+SQNCTM?", CarrierProfile);
+
+/// Selects the carrier configuration profile, e.g. `"standard"` or an operator profile like
+/// `"att"` or `"verizon"`, required by the certification of several networks.
+///
+/// The setting persists and is applied at the next reboot, see [`Reboot`].
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SQNCTM", NoResponse, timeout = 1000)]
+pub struct SetCarrierProfile<'a> {
+    #[at_arg(position = 0, len = 16)]
+    pub profile: &'a str,
+}
+
+/// Starts the 3GPP TS 27.010 multiplexer (CMUX) on the UART.
+///
+/// After the `OK`, the modem only exchanges frames: the AT commands must go through a channel
+/// of the multiplexer, see the `cmux` module.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CMUX", NoResponse)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EnableMultiplexer {
+    /// Transparency mechanism, only the basic option (0) is supported.
+    #[at_arg(position = 0)]
+    pub mode: u8,
+
+    /// Frames used by the channels, only UIH frames (0) are supported.
+    #[at_arg(position = 1)]
+    pub subset: u8,
+
+    /// Current speed of the UART, the multiplexer doesn't change it.
+    #[at_arg(position = 2)]
+    pub port_speed: PortSpeed,
+
+    /// Largest number of information bytes of a frame (N1), 31 by default.
+    #[at_arg(position = 3)]
+    pub max_frame_size: u16,
+}
+
+#[cfg(all(test, feature = "jiff"))]
+mod tests {
+    use jiff::{
+        civil::date,
+        tz::{TimeZone, offset},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_clock_format() {
+        let time = date(2024, 5, 30)
+            .at(13, 22, 45, 0)
+            .to_zoned(TimeZone::fixed(offset(2)))
+            .unwrap();
+        assert_eq!(SetClock::new(&time).time, "24/05/30,13:22:45+08");
+
+        let time = date(2025, 1, 2)
+            .at(3, 4, 5, 0)
+            .to_zoned(TimeZone::fixed(offset(-4)))
+            .unwrap();
+        assert_eq!(SetClock::new(&time).time, "25/01/02,03:04:05-16");
+    }
+}
@@ -1,24 +1,67 @@
-use core::str::FromStr;
+use core::{ops::Range, str::FromStr};
 
 use atat::{atat_derive::AtatResp, serde_at::serde::Deserialize};
-use jiff::{
-    Timestamp, Zoned,
-    civil::DateTime,
-    tz::{Offset, TimeZone},
-};
 use serde::Deserializer;
 
+use crate::{
+    calendar::{self, CalendarTime, ClockTime, parse_number},
+    types::Bool,
+};
+
 /// Any modem time below 1 Jan 2023 00:00:00 UTC is considered an invalid time.
 const MODEM_MIN_VALID_TIMESTAMP: i64 = 1_672_531_200;
 
 #[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Clock {
     /// The current timestamp.
     pub time: Time,
 }
 
+impl Clock {
+    /// Returns the current time, `None` if the modem clock is not synchronized.
+    pub fn time(&self) -> Option<&ClockTime> {
+        self.time.0.as_ref()
+    }
+
+    /// Whether the modem clock is synchronized.
+    pub fn is_valid(&self) -> bool {
+        self.time.0.is_some()
+    }
+
+    /// Seconds since the Unix epoch, `None` if the clock is not synchronized.
+    pub fn unix_seconds(&self) -> Option<i64> {
+        self.time().map(calendar::unix_seconds)
+    }
+
+    /// Converts the time into a [`chrono`] date time, keeping the offset of the modem.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let local = self.time()?;
+        // Can't fail, the modem offsets are within a day.
+        let offset = chrono::FixedOffset::east_opt(calendar::offset_seconds(local)).unwrap();
+        chrono::DateTime::from_timestamp(calendar::unix_seconds(local), 0)
+            .map(|utc| utc.with_timezone(&offset))
+    }
+
+    /// Converts the time into a [`time`] date time, keeping the offset of the modem.
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> Option<time::OffsetDateTime> {
+        let local = self.time()?;
+        // Can't fail, the modem offsets are within a day.
+        let offset = time::UtcOffset::from_whole_seconds(calendar::offset_seconds(local)).unwrap();
+        time::OffsetDateTime::from_unix_timestamp(calendar::unix_seconds(local))
+            .ok()
+            .map(|utc| utc.to_offset(offset))
+    }
+}
+
+/// Time of the modem clock, `None` until the clock is synchronized (by the network, NTP or
+/// [`SetClock`](super::SetClock)).
 #[derive(Clone, Debug)]
-pub struct Time(pub Zoned);
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Time(pub Option<ClockTime>);
 
 impl<'de> Deserialize<'de> for Time {
     /// Deserializes current time from the modem clock response.
@@ -38,35 +81,59 @@ impl FromStr for Time {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Example: "24/05/30,13:22:45+08"
-        if s.len() < 20 {
+        let field = |range: Range<usize>| s.get(range).ok_or(TimeParseError::InvalidFormat);
+        let separators = [(2, b'/'), (5, b'/'), (8, b','), (11, b':'), (14, b':')];
+        if separators
+            .iter()
+            .any(|&(i, c)| s.as_bytes().get(i) != Some(&c))
+        {
             return Err(TimeParseError::InvalidFormat);
         }
 
-        let date_time_str = &s[0..17]; // "yy/MM/dd,HH:mm:ss"
-        let tz_sign = s.chars().nth(17).ok_or(TimeParseError::InvalidFormat)?;
-        let tz_offset_q: i32 = s[18..].parse().map_err(|_| TimeParseError::InvalidFormat)?;
-
-        let offset_secs = match tz_sign {
-            '-' => -tz_offset_q * 15 * 60,
-            _ => tz_offset_q * 15 * 60,
+        let tz_offset_q = parse_number(s.get(18..).ok_or(TimeParseError::InvalidFormat)?)? as i32;
+        let offset_secs = match s.as_bytes().get(17) {
+            Some(b'-') => -tz_offset_q * 15 * 60,
+            Some(b'+') => tz_offset_q * 15 * 60,
+            _ => return Err(TimeParseError::InvalidFormat),
         };
 
-        let offset = Offset::from_seconds(offset_secs).unwrap().to_time_zone();
+        // Two digits years, as strptime: 69-99 are 1969-1999, 00-68 are 2000-2068.
+        let yy = parse_number(field(0..2)?)? as i16;
+        let year = if yy >= 69 { 1900 + yy } else { 2000 + yy };
 
-        let time = DateTime::strptime("%y/%m/%d,%H:%M:%S", date_time_str)
-            .map_err(|_| TimeParseError::InvalidFormat)?
-            .to_zoned(offset)
-            .unwrap();
+        let time = CalendarTime::new(
+            year,
+            parse_number(field(3..5)?)? as i8,
+            parse_number(field(6..8)?)? as i8,
+            parse_number(field(9..11)?)? as i8,
+            parse_number(field(12..14)?)? as i8,
+            parse_number(field(15..17)?)? as i8,
+            offset_secs,
+        )
+        .ok_or(TimeParseError::InvalidFormat)?;
 
-        if time.timestamp().as_second() < MODEM_MIN_VALID_TIMESTAMP {
-            Ok(Self(Zoned::new(Timestamp::UNIX_EPOCH, TimeZone::UTC)))
-        } else {
-            Ok(Self(time))
+        if time.unix_seconds() < MODEM_MIN_VALID_TIMESTAMP {
+            return Ok(Self(None));
         }
+
+        #[cfg(feature = "jiff")]
+        let time = time.to_zoned();
+        Ok(Self(Some(time)))
     }
 }
 
-#[derive(Debug)]
+#[cfg(feature = "defmt")]
+impl defmt::Format for Time {
+    fn format(&self, f: defmt::Formatter) {
+        match &self.0 {
+            Some(time) => defmt::write!(f, "Time({})", defmt::Display2Format(time)),
+            None => defmt::write!(f, "Time(invalid)"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TimeParseError {
     InvalidFormat,
 }
@@ -80,37 +147,151 @@ impl core::fmt::Display for TimeParseError {
 use super::types::RAT;
 
 #[derive(AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct ActiveRAT {
     #[at_arg(position = 0)]
     pub rat: RAT,
 }
 
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Autoconnect {
+    #[at_arg(position = 0)]
+    pub enabled: Bool,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ManufacturerId {
+    /// Manufacturer name, e.g. "SEQUANS Communications".
+    #[at_arg(position = 0)]
+    pub id: heapless::String<32>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ModelId {
+    /// Model name, e.g. "GM02SP".
+    #[at_arg(position = 0)]
+    pub id: heapless::String<32>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Imei {
+    /// 15 digits IMEI.
+    #[at_arg(position = 0)]
+    pub imei: heapless::String<16>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct FirmwareVersion {
+    /// Firmware version, e.g. "UE8.0.5.0".
+    #[at_arg(position = 0)]
+    pub version: heapless::String<64>,
+}
+
+/// Longest carrier profile name.
+pub const CARRIER_PROFILE_LEN: usize = 16;
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct CarrierProfile {
+    /// Name of the profile, e.g. "standard".
+    #[at_arg(position = 0)]
+    pub profile: heapless::String<CARRIER_PROFILE_LEN>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use jiff::Timestamp;
+    use atat::serde_at::from_str;
 
     #[test]
     fn test_valid_clock_with_valid_timestamp() {
         let input = "24/05/30,13:22:45+08";
-        let clock = Time::from_str(input).unwrap();
-        assert!(clock.0.timestamp().as_second() >= super::MODEM_MIN_VALID_TIMESTAMP);
-        assert_eq!(clock.0.offset().seconds(), 8 * 15 * 60);
+        let clock = Time::from_str(input).unwrap().0.unwrap();
+        assert!(calendar::unix_seconds(&clock) >= super::MODEM_MIN_VALID_TIMESTAMP);
+        assert_eq!(calendar::offset_seconds(&clock), 8 * 15 * 60);
     }
 
     #[test]
-    fn test_valid_clock_with_old_timestamp() {
+    fn test_invalid_clock_with_old_timestamp() {
         let input = "70/01/01,00:07:30+00";
         let clock = Time::from_str(input).unwrap();
-        assert_eq!(clock.0.timestamp(), Timestamp::UNIX_EPOCH);
-        assert_eq!(clock.0.offset(), Offset::UTC);
+        assert!(clock.0.is_none());
     }
 
     #[test]
     fn test_valid_clock_negative_offset() {
         let input = "24/05/30,13:22:45-04";
-        let clock = Time::from_str(input).unwrap();
-        assert_eq!(clock.0.offset().seconds(), -4 * 15 * 60);
+        let clock = Time::from_str(input).unwrap().0.unwrap();
+        assert_eq!(calendar::offset_seconds(&clock), -4 * 15 * 60);
+    }
+
+    #[test]
+    fn test_malformed_clock() {
+        let input = "24/05/30,13:22:45+08";
+        for len in 0..input.len() {
+            assert!(Time::from_str(&input[..len]).is_err());
+        }
+        for input in [
+            "24/05/30,13:22:45é",
+            "24/05/30,13:22:45+é",
+            "24/13/30,13:22:45+08",
+            "24/05/30,13:22:45+99999",
+            "2é/05/30,13:22:45+08",
+        ] {
+            assert_eq!(
+                Time::from_str(input).err(),
+                Some(TimeParseError::InvalidFormat)
+            );
+        }
+    }
+
+    #[test]
+    fn test_clock_unix_seconds() {
+        let clock = Clock {
+            time: Time::from_str("24/05/30,13:22:45+08").unwrap(),
+        };
+        assert!(clock.is_valid());
+        assert_eq!(clock.unix_seconds(), Some(1_717_068_165));
+
+        let clock = Clock {
+            time: Time::from_str("70/01/01,00:07:30+00").unwrap(),
+        };
+        assert!(!clock.is_valid());
+        assert_eq!(clock.unix_seconds(), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_clock_to_chrono() {
+        let clock = Clock {
+            time: Time::from_str("24/05/30,13:22:45+08").unwrap(),
+        };
+        let time = clock.to_chrono().unwrap();
+        assert_eq!(time.timestamp(), 1_717_068_165);
+        assert_eq!(time.offset().local_minus_utc(), 2 * 3600);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_clock_to_offset_date_time() {
+        let clock = Clock {
+            time: Time::from_str("24/05/30,13:22:45+08").unwrap(),
+        };
+        let time = clock.to_offset_date_time().unwrap();
+        assert_eq!(time.unix_timestamp(), 1_717_068_165);
+        assert_eq!(time.offset().whole_seconds(), 2 * 3600);
     }
 
     #[test]
@@ -133,4 +314,10 @@ mod tests {
         let err = Time::from_str(input).unwrap_err();
         matches!(err, TimeParseError::InvalidFormat);
     }
+
+    #[test]
+    fn test_imei_parse() {
+        let res: Imei = from_str("351234567890123").unwrap();
+        assert_eq!(res.imei, "351234567890123");
+    }
 }
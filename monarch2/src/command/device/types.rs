@@ -1,5 +1,19 @@
 use atat::atat_derive::AtatEnum;
 
+/// Speed of the UART announced to the multiplexer, see
+/// [`EnableMultiplexer`](super::EnableMultiplexer).
+#[derive(Clone, Copy, Debug, PartialEq, AtatEnum)]
+#[at_enum(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PortSpeed {
+    B9600 = 1,
+    B19200 = 2,
+    B38400 = 3,
+    B57600 = 4,
+    B115200 = 5,
+    B230400 = 6,
+}
+
 /// Modem's radio technology.
 #[derive(Clone, PartialEq, AtatEnum)]
 #[at_enum(u8)]
@@ -1 +1,52 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
 
+/// Progress of a firmware upgrade started with AT+SQNSUPGRADE.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct UpgradeStatus {
+    /// Name of the upgrade image.
+    #[at_arg(position = 0)]
+    pub image: String<64>,
+
+    /// Download progress, in percent.
+    #[at_arg(position = 1)]
+    pub progress: u8,
+
+    /// Result of the upgrade once finished, 0 on success.
+    #[at_arg(position = 2)]
+    pub result: Option<u8>,
+}
+
+/// Result of an NTP synchronization started with AT+SQNNTP.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct NtpSynchronized {
+    /// 0 if the clock was synchronized, otherwise an error code.
+    #[at_arg(position = 0)]
+    pub result: u8,
+}
+
+impl NtpSynchronized {
+    /// Whether the clock was synchronized.
+    pub fn is_success(&self) -> bool {
+        self.result == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_ntp_synchronized_parse() {
+        let res: NtpSynchronized = from_str("+SQNNTP: 0").unwrap();
+        assert!(res.is_success());
+
+        let res: NtpSynchronized = from_str("+SQNNTP: 3").unwrap();
+        assert!(!res.is_success());
+    }
+}
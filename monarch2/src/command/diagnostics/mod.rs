@@ -0,0 +1,58 @@
+//! Diagnostic logs kept by the modem, e.g. a crash dump or a protocol stack trace to forward to
+//! support without the vendor tooling.
+//!
+//! The logs are far larger than the ingress buffer, they are read in slices of at most
+//! [`LOG_READ_LEN`] bytes with [`ReadLog`], the data exchanged as hexadecimal strings.
+
+use atat::{
+    InternalError,
+    serde_at::{SerializeOptions, to_slice},
+};
+use responses::LogData;
+use serde::Serialize;
+use types::DiagnosticLog;
+
+pub mod responses;
+pub mod types;
+
+/// Largest number of bytes read at once with [`ReadLog`].
+pub const LOG_READ_LEN: usize = 256;
+
+/// Reads a slice of a diagnostic log, up to [`LOG_READ_LEN`] bytes from `offset`.
+///
+/// The modem answers with the number of bytes read, 0 past the end of the log, then the data on
+/// its own line, see [`LogData`].
+///
+/// Type: `synchronous`
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReadLog {
+    pub log: DiagnosticLog,
+
+    /// Offset of the slice in the log, in bytes.
+    pub offset: u32,
+
+    /// Number of bytes to read, up to [`LOG_READ_LEN`].
+    pub max_bytes: u16,
+}
+
+impl atat::AtatCmd for ReadLog {
+    type Response = LogData;
+
+    const MAX_LEN: usize = "AT+SQNSLOGREAD=".len()
+        + 1 // log
+        + 10 // offset
+        + 5 // max bytes
+        + 2 // separators
+        + 2; // termination
+    const MAX_TIMEOUT_MS: u32 = 1000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Can't fail, all the arguments are numbers.
+        to_slice(self, "+SQNSLOGREAD", buf, SerializeOptions::default()).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        LogData::parse(resp?).ok_or(atat::Error::Parse)
+    }
+}
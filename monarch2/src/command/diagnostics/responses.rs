@@ -0,0 +1,58 @@
+use atat::AtatResp;
+use heapless::Vec;
+
+use super::LOG_READ_LEN;
+use crate::types::hex_decode;
+
+/// Response to [`ReadLog`](super::ReadLog), the slice of the log read, decoded.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogData {
+    /// The data read, empty past the end of the log.
+    pub data: Vec<u8, LOG_READ_LEN>,
+}
+
+impl AtatResp for LogData {}
+
+impl LogData {
+    /// Parses the `+SQNSLOGREAD: <count>` line of the response and the hexadecimal data following
+    /// it.
+    ///
+    /// Returns `None` without the line, or if the data isn't `count` bytes of valid hexadecimal.
+    pub fn parse(resp: &[u8]) -> Option<Self> {
+        let mut lines = resp.split(|&b| b == b'\n').map(<[u8]>::trim_ascii);
+        let header = lines.find_map(|line| line.strip_prefix(b"+SQNSLOGREAD:"))?;
+        let count: usize = core::str::from_utf8(header).ok()?.trim().parse().ok()?;
+
+        let mut data = Vec::new();
+        if count > 0 {
+            let hex = core::str::from_utf8(lines.next()?).ok()?;
+            data.resize_default(LOG_READ_LEN).ok()?;
+            let len = hex_decode(hex, &mut data)?;
+            if len != count {
+                return None;
+            }
+            data.truncate(len);
+        }
+        Some(Self { data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_data_parse() {
+        let res = LogData::parse(b"+SQNSLOGREAD: 4\r\nDEADBEEF").unwrap();
+        assert_eq!(res.data, [0xDE, 0xAD, 0xBE, 0xEF]);
+
+        // The end of the log.
+        let res = LogData::parse(b"+SQNSLOGREAD: 0").unwrap();
+        assert!(res.data.is_empty());
+
+        // Truncated data.
+        assert_eq!(LogData::parse(b"+SQNSLOGREAD: 4\r\nDEAD"), None);
+        assert_eq!(LogData::parse(b""), None);
+    }
+}
@@ -0,0 +1,12 @@
+use atat::atat_derive::AtatEnum;
+
+/// Diagnostic log kept by the modem.
+#[derive(Clone, Copy, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum DiagnosticLog {
+    /// Dump of the last crash of the modem firmware, kept across the restarts.
+    Crash = 0,
+    /// Trace of the LTE protocol stack.
+    ProtocolStack = 1,
+}
@@ -0,0 +1,49 @@
+use atat::{
+    InternalError,
+    serde_at::{SerializeOptions, to_slice},
+};
+use responses::HostAddresses;
+use serde::Serialize;
+use types::IpVersion;
+
+pub mod responses;
+pub mod types;
+
+/// Longest host name resolved by [`ResolveHost`], see RFC 1035.
+pub const HOSTNAME_LEN: usize = 253;
+
+/// Resolves a host name with the DNS servers of the network, the PDN must be active.
+///
+/// The modem answers with the addresses of the host, see [`HostAddresses`].
+///
+/// Type: `synchronous`
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResolveHost<'a> {
+    /// Host name to resolve, up to [`HOSTNAME_LEN`] characters.
+    pub hostname: &'a str,
+
+    /// Version of the addresses to look up, IPv4 by default.
+    pub ip_version: Option<IpVersion>,
+}
+
+impl atat::AtatCmd for ResolveHost<'_> {
+    type Response = HostAddresses;
+
+    const MAX_LEN: usize = "AT+SQNDNSLKUP=".len()
+        + HOSTNAME_LEN + 2 // quoted host name
+        + 2 // IP version
+        + 2; // termination
+
+    /// The modem waits for the DNS servers, which may retry the query.
+    const MAX_TIMEOUT_MS: u32 = 30_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Can't fail as long as the host name fits `HOSTNAME_LEN`, like the derived commands.
+        to_slice(self, "+SQNDNSLKUP", buf, SerializeOptions::default()).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        HostAddresses::parse(resp?).ok_or(atat::Error::Parse)
+    }
+}
@@ -0,0 +1,104 @@
+use core::net::IpAddr;
+
+use atat::AtatResp;
+use heapless::Vec;
+
+/// Most addresses kept from a [`ResolveHost`](super::ResolveHost) response.
+pub const MAX_HOST_ADDRESSES: usize = 4;
+
+/// Response to [`ResolveHost`](super::ResolveHost), the A or AAAA records of the host.
+///
+/// The modem doesn't report the TTL of the records.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct HostAddresses {
+    pub addresses: Vec<IpAddr, MAX_HOST_ADDRESSES>,
+}
+
+impl AtatResp for HostAddresses {}
+
+impl HostAddresses {
+    /// Parses the `+SQNDNSLKUP: <hostname>,<address>[,<address>[...]]` line of the response.
+    ///
+    /// The addresses that can't be parsed are skipped, the ones beyond [`MAX_HOST_ADDRESSES`]
+    /// dropped. Returns `None` without the line.
+    pub fn parse(resp: &[u8]) -> Option<Self> {
+        let line = resp
+            .split(|&b| b == b'\n')
+            .find_map(|line| line.trim_ascii().strip_prefix(b"+SQNDNSLKUP:"))?;
+        let line = core::str::from_utf8(line).ok()?;
+
+        // The first field is the host name.
+        let mut addresses = Vec::new();
+        for field in line.split(',').skip(1) {
+            let Ok(address) = field.trim().trim_matches('"').parse() else {
+                continue;
+            };
+            if addresses.push(address).is_err() {
+                break;
+            }
+        }
+        Some(Self { addresses })
+    }
+
+    /// The first address, the one to connect to.
+    pub fn first(&self) -> Option<IpAddr> {
+        self.addresses.first().copied()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for HostAddresses {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "HostAddresses {{ addresses: [");
+        for (i, address) in self.addresses.iter().enumerate() {
+            if i > 0 {
+                defmt::write!(f, ", ");
+            }
+            defmt::write!(f, "{}", defmt::Display2Format(address));
+        }
+        defmt::write!(f, "] }}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    #[test]
+    fn test_host_addresses_parse() {
+        let res = HostAddresses::parse(
+            b"+SQNDNSLKUP: \"example.com\",\"93.184.215.14\",\"93.184.215.15\"",
+        )
+        .unwrap();
+        assert_eq!(
+            res.addresses,
+            [
+                IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)),
+                IpAddr::V4(Ipv4Addr::new(93, 184, 215, 15)),
+            ]
+        );
+
+        let res = HostAddresses::parse(
+            b"+SQNDNSLKUP: example.com,2606:2800:21f:cb07:6820:80da:af6b:8b2c",
+        )
+        .unwrap();
+        assert_eq!(
+            res.first(),
+            Some(IpAddr::V6(Ipv6Addr::new(
+                0x2606, 0x2800, 0x21f, 0xcb07, 0x6820, 0x80da, 0xaf6b, 0x8b2c
+            )))
+        );
+    }
+
+    #[test]
+    fn test_host_addresses_parse_invalid() {
+        assert_eq!(HostAddresses::parse(b"+CEREG: 1"), None);
+
+        let res = HostAddresses::parse(b"+SQNDNSLKUP: \"example.com\",\"not an address\",1.2.3.4,5.6.7.8,9.10.11.12,13.14.15.16").unwrap();
+        assert_eq!(res.addresses.len(), MAX_HOST_ADDRESSES);
+        assert_eq!(res.first(), Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))));
+    }
+}
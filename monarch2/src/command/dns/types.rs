@@ -0,0 +1,12 @@
+use atat::atat_derive::AtatEnum;
+
+/// Version of the addresses looked up by [`ResolveHost`](super::ResolveHost).
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum IpVersion {
+    /// A records.
+    V4 = 0,
+    /// AAAA records.
+    V6 = 1,
+}
@@ -1,11 +1,20 @@
-use atat::atat_derive::AtatCmd;
-use responses::{GnssAsssitance, GnssCloudServerName, GnssConfig, GnssTimeout};
+use atat::{
+    InternalError,
+    atat_derive::AtatCmd,
+    digest::{DigestResult, Digester},
+};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use responses::{GnssAssistanceStatus, GnssCloudServerName, GnssConfig, GnssTimeout};
 use types::{
-    AcquisitionMode, FixSensitivity, GnssAssitanceType, LocationMode, ProgramGnssAction,
+    AcquisitionMode, FixSensitivity, GnssAssistanceType, LocationMode, ProgramGnssAction,
     UrcNotificationSetting,
 };
 
-use crate::{gnss::types::QuotedF32, types::Bool};
+use crate::{
+    gnss::types::{GnssError, QuotedF32},
+    modem::ModemState,
+    types::Bool,
+};
 
 use super::{NoResponse, Reserved};
 
@@ -14,20 +23,21 @@ pub mod types;
 pub mod urc;
 
 /// Configures the GNSS (Global Navigation Satellite System) module.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+LPGNSSCFG?", GnssConfig)]
+#[derive(Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetGnssConfig;
 
+static_cmd!(GetGnssConfig, "+LPGNSSCFG?", GnssConfig);
+
 /// This AT command is used to set the GNSS approximate position as a hint for the next fix. It must be accurate within 100 km if <acq_mode> configuration is set to hot start by AT+LPGNSSCFG command.
 ///
 /// Unless set by this command, the default values after power-on/reset are those of Paris (latitude: 48.8616948, longitude: 2.3469252, altitude: 15).
 ///
 /// For subsequent fixes, unless overridden by this command, the last successful fix is taken as the approximate position.
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+LPGNSSCFG", NoResponse)]
+#[at_cmd("+LPGNSSAPPROXPOS", NoResponse)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct SetApproximatePositionAssitance {
+pub struct SetApproximatePositionAssistance {
     /// Approximate latitude in decimal degree (DD) format. Range is -90..90.
     #[at_arg(position = 0)]
     pub lat: QuotedF32,
@@ -44,7 +54,7 @@ pub struct SetApproximatePositionAssitance {
 /// Configures the GNSS (Global Navigation Satellite System) module.
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+LPGNSSCFG", NoResponse)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
 pub struct SetGnssConfig {
     /// The GNSS location mode.
     #[at_arg(position = 0)]
@@ -72,21 +82,56 @@ pub struct SetGnssConfig {
     pub early_abort: Bool,
 }
 
+compact_fmt!(defmt SetGnssConfig);
+
 /// Triggers a connection to the GNSS cloud, downloads the almanac or the ephemeris files and stores them in persistent memory. This AT command only works with an available LTE connection.
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+LPGNSSASSISTANCE", NoResponse)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct UpdateGnssAssitance {
+pub struct UpdateGnssAssistance {
     /// The GNSS location mode.
     #[at_arg(position = 0)]
-    pub typ: GnssAssitanceType,
+    pub typ: GnssAssistanceType,
 }
 
-/// Verify the status of the assistance, or check if an update is required. If both the real-time and predicted ephemeris are valid when a fix is requested, the real-time ephemeris takes precedence.
+/// Uploads assistance data obtained by the host, e.g. over its own backhaul, in place of a download from the GNSS cloud with [`UpdateGnssAssistance`].
+///
+/// After the command, the modem prompts for the <size> bytes of the data, sent like the data of [`PrepareWrite`](crate::nvm::PrepareWrite). Once <size> bytes have been received, the data is verified and stored in persistent memory, the response is OK.
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+LPGNSSASSISTANCE?", heapless::Vec<GnssAsssitance, 3>)]
+#[at_cmd("+LPGNSSUPLOADASSISTANCE", NoResponse)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct GetGnssAssitance;
+pub struct PrepareUploadGnssAssistance {
+    /// The type of the assistance data.
+    #[at_arg(position = 0)]
+    pub typ: GnssAssistanceType,
+
+    /// Size in bytes of the data to upload.
+    #[at_arg(position = 1)]
+    pub size: usize,
+}
+
+/// Verify the status of the assistance, or check if an update is required. If both the real-time and predicted ephemeris are valid when a fix is requested, the real-time ephemeris takes precedence.
+///
+/// The modem answers with one line per assistance type, see [`GnssAssistanceStatus::parse`].
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetGnssAssistance;
+
+impl atat::AtatCmd for GetGnssAssistance {
+    type Response = GnssAssistanceStatus;
+
+    const MAX_LEN: usize = b"AT+LPGNSSASSISTANCE?\r\n".len();
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        let cmd = b"AT+LPGNSSASSISTANCE?\r\n";
+        buf[..cmd.len()].copy_from_slice(cmd);
+        cmd.len()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        Ok(GnssAssistanceStatus::parse(resp?))
+    }
+}
 
 /// This AT command programs or cancels a GNSS fix.
 ///
@@ -99,6 +144,9 @@ pub struct GetGnssAssitance;
 /// • FIX
 /// _IN_PROGRESS: Another fix is already being processed.
 /// • NO_VALID_EPHEMERIS_FOR_ON-DEVICE_NAVIGATION: No ephemeris is available and <loc _mode> has been set to "on-device location" by AT+LPGNSSCFG (on page 231).
+///
+/// The extended errors are reported as [`GnssError`]s when the ingress uses a
+/// [`GnssErrorDigester`].
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+LPGNSSFIXPROG", NoResponse)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -119,11 +167,16 @@ pub struct SetGnssCloudServerName<'a> {
 }
 
 /// This AT command sets the name of the server the assistance data is downloaded from. The name is saved and preserved at reboot / reset.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+LPGNSSCLOUDSEL?", GnssCloudServerName)]
+#[derive(Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetGnssCloudServerName;
 
+static_cmd!(
+    GetGnssCloudServerName,
+    "+LPGNSSCLOUDSEL?",
+    GnssCloudServerName
+);
+
 /// This AT command sets a time-out for GNSS processing. If the time-out is reached, a +LPGNSSFIXSTOP URC is sent with "TIMEOUT" as the <reason> parameter.
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+LPGNSSTIMEOUT", NoResponse)]
@@ -135,7 +188,42 @@ pub struct SetGnssTimeout {
 }
 
 /// This AT command gets the currently configured time-out for GNSS processing.
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+LPGNSSTIMEOUT?", GnssTimeout)]
+#[derive(Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetGnssTimeout;
+
+static_cmd!(GetGnssTimeout, "+LPGNSSTIMEOUT?", GnssTimeout);
+
+/// [`Digester`] wrapper recording the extended [`GnssError`]s in the [`ModemState`], so that
+/// [`Modem::get_gnss_fix`](crate::Modem::get_gnss_fix) fails with
+/// [`Error::Gnss`](crate::Error::Gnss) instead of a generic AT error.
+///
+/// `atat` keeps only the numeric `+CME ERROR` codes, the text of the extended errors is lost
+/// without this wrapper.
+///
+/// ```ignore
+/// let digester = GnssErrorDigester::new(AtDigester::<Urc>::new(), &STATE);
+/// let ingress = Ingress::new(digester, &mut res_buf, &RES_SLOT, &URC_CHANNEL);
+/// ```
+pub struct GnssErrorDigester<'a, D, M: RawMutex> {
+    digester: D,
+    state: &'a ModemState<M>,
+}
+
+impl<'a, D, M: RawMutex> GnssErrorDigester<'a, D, M> {
+    pub fn new(digester: D, state: &'a ModemState<M>) -> Self {
+        Self { digester, state }
+    }
+}
+
+impl<D: Digester, M: RawMutex> Digester for GnssErrorDigester<'_, D, M> {
+    fn digest<'a>(&mut self, buf: &'a [u8]) -> (DigestResult<'a>, usize) {
+        let (result, len) = self.digester.digest(buf);
+        if let DigestResult::Response(Err(_)) = result {
+            if let Some(err) = GnssError::from_message(&buf[..len]) {
+                self.state.set_gnss_error(err);
+            }
+        }
+        (result, len)
+    }
+}
@@ -1,7 +1,7 @@
-use atat::atat_derive::AtatResp;
+use atat::{AtatResp, serde_at::from_slice};
 use heapless::String;
 
-use crate::gnss::types::GnssAssitanceType;
+use crate::gnss::types::GnssAssistanceType;
 
 use super::{
     Bool, Reserved,
@@ -9,6 +9,8 @@ use super::{
 };
 
 #[derive(Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct GnssConfig {
     /// The GNSS location mode.
     #[at_arg(position = 0)]
@@ -29,10 +31,12 @@ pub struct GnssConfig {
 }
 
 /// This structure represents the details of a certain GNSS assistance type.
-#[derive(Clone, AtatResp)]
+#[derive(Clone, Debug, PartialEq, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct GnssAsssitance {
     #[at_arg(position = 0)]
-    pub typ: GnssAssitanceType,
+    pub typ: GnssAssistanceType,
 
     // /// Whether the GNSS assitance is available.
     // #[at_arg(position = 0)]
@@ -54,7 +58,49 @@ pub struct GnssAsssitance {
     pub time_to_expiration: i32,
 }
 
+/// Response to [`GetGnssAssistance`](super::GetGnssAssistance), the details of every assistance
+/// type, `None` when not reported.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct GnssAssistanceStatus {
+    pub almanac: Option<GnssAsssitance>,
+    pub real_time_ephemeris: Option<GnssAsssitance>,
+    pub predicted_ephemeris: Option<GnssAsssitance>,
+}
+
+impl AtatResp for GnssAssistanceStatus {}
+
+impl GnssAssistanceStatus {
+    /// Parses the `+LPGNSSASSISTANCE: <type>,<available>,...` lines of the response.
+    ///
+    /// The lines that can't be parsed are skipped rather than failing the whole response, e.g. an
+    /// assistance type unknown to the driver or a line without the prefix.
+    pub fn parse(resp: &[u8]) -> Self {
+        let mut status = Self::default();
+        for line in resp.split(|&b| b == b'\n') {
+            let line = line.trim_ascii();
+            let args = line
+                .strip_prefix(b"+LPGNSSASSISTANCE:")
+                .unwrap_or(line)
+                .trim_ascii();
+            let Ok(assistance) = from_slice::<GnssAsssitance>(args) else {
+                continue;
+            };
+            let slot = match assistance.typ {
+                GnssAssistanceType::Almanac => &mut status.almanac,
+                GnssAssistanceType::RealTimeEphemeris => &mut status.real_time_ephemeris,
+                GnssAssistanceType::PredictedEphemeris => &mut status.predicted_ephemeris,
+            };
+            *slot = Some(assistance);
+        }
+        status
+    }
+}
+
 #[derive(Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct GnssCloudServerName {
     /// Server's hostname.
     #[at_arg(position = 0)]
@@ -66,6 +112,8 @@ pub struct GnssCloudServerName {
 }
 
 #[derive(Clone, Default, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct GnssTimeout {
     /// Time-out in seconds (0..999). 0 means no limit (default).
     #[at_arg(position = 0)]
@@ -93,9 +141,27 @@ mod tests {
 
     #[test]
     fn test_full_gnss_assistance_response_parsing() {
-        let input = "+LPGNSSASSISTANCE: 0,1,81390742,0,0\r\n+LPGNSSASSISTANCE: 1,0,0,0,0\r\n+LPGNSSASSISTANCE: 2,0,0,0,0";
-        let assistance: heapless::Vec<GnssAsssitance, 3> = from_str(input).unwrap();
+        let input = b"+LPGNSSASSISTANCE: 0,1,81390742,0,0\r\n+LPGNSSASSISTANCE: 1,0,0,0,0\r\n+LPGNSSASSISTANCE: 2,0,0,0,0";
+        let status = GnssAssistanceStatus::parse(input);
+
+        assert_eq!(status.almanac.unwrap().last_update, 81390742);
+        assert_eq!(status.real_time_ephemeris.unwrap().available, false.into());
+        assert!(status.predicted_ephemeris.is_some());
+    }
 
-        assert!(assistance.is_full());
+    #[test]
+    fn test_gnss_assistance_response_variations() {
+        // Unknown assistance type, garbage and a line without the space after the prefix.
+        let input = b"+LPGNSSASSISTANCE: 3,1,0,0,0\r\n\r\nNOT ASSISTANCE\r\n+LPGNSSASSISTANCE:1,1,12,3600,7200";
+        let status = GnssAssistanceStatus::parse(input);
+
+        assert_eq!(status.almanac, None);
+        assert_eq!(status.real_time_ephemeris.unwrap().time_to_update, 3600);
+        assert_eq!(status.predicted_ephemeris, None);
+
+        assert_eq!(
+            GnssAssistanceStatus::parse(b""),
+            GnssAssistanceStatus::default()
+        );
     }
 }
@@ -48,10 +48,10 @@ pub enum LocationMode {
 }
 
 /// Type of GNSS assistance.
-#[derive(Clone, PartialEq, AtatEnum)]
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
-pub enum GnssAssitanceType {
+pub enum GnssAssistanceType {
     /// Almanac data details, this is not needed when real-time ephemeris data is available.
     Almanac = 0,
     /// Real-time ephemeris data details. Use this kind of assistance data for the fastest and
@@ -73,7 +73,8 @@ pub enum FixSensitivity {
     High = 3,
 }
 
-#[derive(Clone, PartialEq, AtatEnum, Default)]
+/// Content of the +LPGNSSFIXREADY notification.
+#[derive(Clone, Debug, PartialEq, AtatEnum, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
 pub enum UrcNotificationSetting {
@@ -116,6 +117,37 @@ impl Serialize for ProgramGnssAction {
     }
 }
 
+/// Extended error reported by [`ProgramGnss`](super::ProgramGnss) when no fix can be programmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GnssError {
+    /// No RTC is available, attach to the LTE network to synchronize the clock first.
+    NoRtc,
+    /// The modem is attached to the LTE network, detach first.
+    LteConcurrency,
+    /// Another fix is already in progress.
+    FixInProgress,
+    /// No ephemeris is available while the on-device location mode is configured.
+    NoValidEphemeris,
+}
+
+impl GnssError {
+    /// Finds the extended error in an error line, e.g. `+CME ERROR: NO_RTC`.
+    pub fn from_message(line: &[u8]) -> Option<Self> {
+        const MESSAGES: [(&[u8], GnssError); 4] = [
+            (b"NO_RTC", GnssError::NoRtc),
+            (b"LTE_CONCURRENCY", GnssError::LteConcurrency),
+            (b"FIX_IN_PROGRESS", GnssError::FixInProgress),
+            (b"NO_VALID_EPHEMERIS", GnssError::NoValidEphemeris),
+        ];
+
+        MESSAGES
+            .iter()
+            .find(|(message, _)| line.windows(message.len()).any(|w| w == *message))
+            .map(|(_, err)| *err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,4 +170,17 @@ mod tests {
             heapless::String::<8>::try_from("\"single\"").unwrap()
         );
     }
+
+    #[test]
+    fn gnss_error_from_message() {
+        assert_eq!(
+            GnssError::from_message(b"+CME ERROR: NO_RTC\r\n"),
+            Some(GnssError::NoRtc)
+        );
+        assert_eq!(
+            GnssError::from_message(b"+CME ERROR: NO_VALID_EPHEMERIS_FOR_ON-DEVICE_NAVIGATION\r\n"),
+            Some(GnssError::NoValidEphemeris)
+        );
+        assert_eq!(GnssError::from_message(b"ERROR\r\n"), None);
+    }
 }
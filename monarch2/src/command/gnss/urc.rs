@@ -1,14 +1,22 @@
 use atat::atat_derive::AtatResp;
-use jiff::civil;
 use serde::{Deserialize, Deserializer, de};
 
-use crate::gnss::types::QuotedF32;
+use crate::{calendar::FixTime, gnss::types::QuotedF32};
 
 /// The maximum number of tracked GNSS satellites.
-static GNSS_MAX_SATS: usize = 32;
+pub const GNSS_MAX_SATS: usize = 32;
+
+/// Capacity of the base64 raw data of a GNSS fix.
+pub const GNSS_RAW_DATA_LEN: usize = 1024;
+
+/// Length of the satellite list of a GNSS fix with [`GNSS_MAX_SATS`] satellites, e.g.
+/// `("12",38),("25",41)`.
+pub const GNSS_SATELLITES_LEN: usize = GNSS_MAX_SATS * "(\"12\",100),".len();
 
 /// This notification is received when a GNSS fix is available. The notification information depends on <urc_settings> and <metrics> configuration set by the [`SetGnssConfig` (AT+LPGNSSCFG)](super::SetGnssConfig) command.
-#[derive(Debug, Clone, PartialEq, AtatResp)]
+#[derive(Clone, PartialEq, AtatResp)]
+#[cfg_attr(any(test, feature = "verbose-fmt"), derive(Debug))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct GnssFixReady {
     /// Fix identifier. The memory can store ten fixes. If no free slot remains, the oldest fix is overwritten.
     #[at_arg(position = 0)]
@@ -16,7 +24,7 @@ pub struct GnssFixReady {
 
     /// UTC time, in ISO 8601 format, of the GNSS fix. When <loc_mode> is set to "on-device location" mode by the [`SetGnssConfig` (AT+LPGNSSCFG)](super::SetGnssConfig) command, the time stamp is computed using GNSS.
     #[at_arg(position = 1)]
-    pub timestamp: civil::DateTime,
+    pub timestamp: FixTime,
 
     /// Duration (in milliseconds) of the fix. When <loc_mode> is set to "on-device location' mode by the [`SetGnssConfig` (AT+LPGNSSCFG)](super::SetGnssConfig) command, the duration runs from the start of the capture to the completion of the computation.
     #[at_arg(position = 2)]
@@ -50,17 +58,107 @@ pub struct GnssFixReady {
     #[at_arg(position = 9)]
     pub down_speed: QuotedF32,
 
-    // Base64 encoding of the GNSS raw data to be used with AT+LPGNSSSENDRAW. Maximum 256 chars.
-    // This field is ignored.
+    /// Base64 encoding of the GNSS raw data to be used with AT+LPGNSSSENDRAW, only kept with the
+    /// `gnss-raw-data` feature.
     #[at_arg(position = 10)]
-    pub raw_data: heapless::String<1024>,
+    pub raw_data: GnssRawData,
 
     #[at_arg(position = 11)]
     pub sats: Option<SateliteInfos>,
 }
 
+compact_fmt!(GnssFixReady);
+
+/// Raw data of a [`GnssFixReady`].
+///
+/// The data is only kept with the `gnss-raw-data` feature, only its length is otherwise: every slot
+/// of the URC channel holds a whole fix, and most applications only need the position.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GnssRawData {
+    #[cfg(feature = "gnss-raw-data")]
+    data: heapless::String<GNSS_RAW_DATA_LEN>,
+    #[cfg(not(feature = "gnss-raw-data"))]
+    len: usize,
+}
+
+#[cfg(feature = "gnss-raw-data")]
+impl GnssRawData {
+    /// Length of the base64 data, in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The base64 data.
+    pub fn as_str(&self) -> &str {
+        &self.data
+    }
+}
+
+#[cfg(not(feature = "gnss-raw-data"))]
+impl GnssRawData {
+    /// Length of the base64 data, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl GnssRawData {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl TryFrom<&str> for GnssRawData {
+    type Error = ();
+
+    /// Fails if the data doesn't fit [`GNSS_RAW_DATA_LEN`].
+    fn try_from(data: &str) -> Result<Self, Self::Error> {
+        if data.len() > GNSS_RAW_DATA_LEN {
+            return Err(());
+        }
+        Ok(GnssRawData {
+            #[cfg(feature = "gnss-raw-data")]
+            data: heapless::String::try_from(data)?,
+            #[cfg(not(feature = "gnss-raw-data"))]
+            len: data.len(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for GnssRawData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: &str = Deserialize::deserialize(deserializer)?;
+        GnssRawData::try_from(s).map_err(|_| de::Error::custom("raw data too long"))
+    }
+}
+
+#[cfg(all(feature = "std", feature = "gnss-raw-data"))]
+impl serde::Serialize for GnssRawData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_some(self.as_str())
+    }
+}
+
+/// Without the `gnss-raw-data` feature, the data isn't available.
+#[cfg(all(feature = "std", not(feature = "gnss-raw-data")))]
+impl serde::Serialize for GnssRawData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_none()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct SateliteInfo {
     // Sattelite number.
     pub sat_no: heapless::String<2>,
@@ -72,6 +170,7 @@ pub struct SateliteInfo {
 /// List of satellite information.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct SateliteInfos(pub heapless::Vec<SateliteInfo, GNSS_MAX_SATS>);
 
 impl<'de> Deserialize<'de> for SateliteInfos {
@@ -82,9 +181,9 @@ impl<'de> Deserialize<'de> for SateliteInfos {
         // This is a very unfortunate 🍝 code. atat splits on `,` by default
         // but in our case we have data such as `("XX",100),("YY",200)`
         // (notice the comma `,` inside the parentheses).
-        // What we do here is that we take all of these pairs at the end of the response
+        // What we do here is that we borrow all of these pairs at the end of the response
         // as one long string and then manually parse it into the sattelit info.
-        let s: heapless::String<256> = heapless::String::deserialize(deserializer)?;
+        let s: &str = Deserialize::deserialize(deserializer)?;
         let mut infos = heapless::Vec::new();
 
         for part in s.split_terminator("),(") {
@@ -119,10 +218,26 @@ impl<'de> Deserialize<'de> for SateliteInfos {
     }
 }
 
-#[cfg(feature = "defmt")]
+#[cfg(all(feature = "defmt", feature = "verbose-fmt"))]
 impl defmt::Format for GnssFixReady {
     fn format(&self, f: defmt::Formatter) {
-        defmt::write!(f, "GnssFixReady",);
+        // The raw data is only useful to the modem, report its size.
+        defmt::write!(
+            f,
+            "GnssFixReady {{ fix_id: {}, timestamp: {}, ttf: {}, confidence: {}, lat: {}, long: {}, elev: {}, north_speed: {}, east_speed: {}, down_speed: {}, raw_data: {} bytes, sats: {} }}",
+            self.fix_id,
+            defmt::Display2Format(&self.timestamp),
+            self.ttf,
+            self.confidence,
+            self.lat,
+            self.long,
+            self.elev,
+            self.north_speed,
+            self.east_speed,
+            self.down_speed,
+            self.raw_data.len(),
+            self.sats,
+        );
     }
 }
 
@@ -137,10 +252,7 @@ mod tests {
         let got = atat::serde_at::from_slice::<GnssFixReady>(input).ok();
         let expected = Some(GnssFixReady {
             fix_id: 0,
-            timestamp: civil::DateTime::from_parts(
-                civil::date(2025, 6, 24),
-                civil::time(15, 55, 20, 00)
-            ),
+            timestamp: "2025-06-24T15:55:20".parse().unwrap(),
             ttf: 66563,
             confidence: QuotedF32(20000000.000000),
             lat: QuotedF32(0.),
@@ -149,7 +261,7 @@ mod tests {
             north_speed: QuotedF32(0.),
             east_speed: QuotedF32(0.),
             down_speed: QuotedF32(0.),
-            raw_data: heapless::String::try_from(
+            raw_data: GnssRawData::try_from(
                 "+oyFVQ4AAADeYQAAAAAAAIADTG5IQAAAALCAxgJAAAAAAAAALkDoAwAAAwQBAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADQEnNBAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAaMpaaAAAAAA="
             ).unwrap(),
             sats: Some(SateliteInfos(heapless::Vec::from_slice(&[
@@ -161,4 +273,35 @@ mod tests {
         });
         assert_eq!(got, expected);
     }
+
+    #[test]
+    fn test_satelite_infos_parsing() {
+        let sats: Vec<_> = (0..GNSS_MAX_SATS)
+            .map(|sat| format!("(\"{sat:02}\",{})", 20 + sat))
+            .collect();
+        let input = sats.join(",");
+        // Longer than the 256 bytes the list used to be truncated to.
+        assert!(input.len() > 256);
+
+        #[derive(Deserialize)]
+        struct Args {
+            sats: SateliteInfos,
+        }
+        let args: Args = atat::serde_at::from_str(&input).unwrap();
+        assert_eq!(args.sats.0.len(), GNSS_MAX_SATS);
+        assert_eq!(args.sats.0[31].sat_no, "31");
+        assert_eq!(args.sats.0[31].signal_strength, 51);
+    }
+
+    /// Without the feature, only the length of the raw data is kept in the URC slots.
+    #[cfg(not(feature = "gnss-raw-data"))]
+    #[test]
+    fn test_gnss_raw_data_size() {
+        assert_eq!(
+            core::mem::size_of::<GnssRawData>(),
+            core::mem::size_of::<usize>()
+        );
+        assert_eq!(GnssRawData::try_from("+oyFVQ4A").unwrap().len(), 8);
+        assert!(GnssRawData::try_from("A".repeat(GNSS_RAW_DATA_LEN + 1).as_str()).is_err());
+    }
 }
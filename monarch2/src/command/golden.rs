@@ -0,0 +1,733 @@
+//! Exact lines written to the modem by every command, so that a change of the `atat_derive`
+//! attributes (or of `atat` itself) altering what goes on the wire fails here first.
+
+use atat::{AtatCmd, serde_bytes::Bytes};
+use heapless::String;
+
+use super::*;
+use crate::types::{Bool, Nullable};
+
+#[track_caller]
+fn assert_at<Cmd: AtatCmd>(cmd: &Cmd, expected: &[u8]) {
+    let mut buf = [0; 4096];
+    let len = cmd.write(&mut buf);
+    assert_eq!(
+        core::str::from_utf8(&buf[..len]),
+        core::str::from_utf8(expected)
+    );
+    assert!(
+        len <= Cmd::MAX_LEN,
+        "{len} bytes over MAX_LEN {}",
+        Cmd::MAX_LEN
+    );
+}
+
+fn string<const N: usize>(s: &str) -> String<N> {
+    String::try_from(s).unwrap()
+}
+
+#[test]
+fn test_basic() {
+    assert_at(&AT, b"AT\r\n");
+    assert_at(&AbortPrompt, b"\x1b");
+}
+
+#[test]
+fn test_device() {
+    use device::{
+        types::{PortSpeed, RAT},
+        *,
+    };
+
+    assert_at(&FactoryReset, b"AT+SQNSFACTORYRESET\r\n");
+    assert_at(&Shutdown, b"AT+SQNSSHDN\r\n");
+    assert_at(&GetClock, b"AT+CCLK?\r\n");
+    assert_at(
+        &SetClock {
+            time: string("24/05/30,13:22:45+08"),
+        },
+        b"AT+CCLK=\"24/05/30,13:22:45+08\"\r\n",
+    );
+    assert_at(
+        &ConfigureAutomaticTimeZoneUpdate {
+            enabled: Bool::True,
+        },
+        b"AT+CTZU=1\r\n",
+    );
+    assert_at(
+        &SynchronizeTimeNtp {
+            server: "pool.ntp.org",
+            port: Some(123),
+        },
+        b"AT+SQNNTP=\"pool.ntp.org\",123\r\n",
+    );
+    assert_at(&GetOperatingMode, b"AT+SQNMODEACTIVE?\r\n");
+    assert_at(
+        &SetOperatingMode { mode: RAT::NBIoT },
+        b"AT+SQNMODEACTIVE=2\r\n",
+    );
+    assert_at(&GetManufacturerId, b"AT+CGMI\r\n");
+    assert_at(&GetModelId, b"AT+CGMM\r\n");
+    assert_at(&GetImei, b"AT+CGSN\r\n");
+    assert_at(&GetFirmwareVersion, b"AT+CGMR\r\n");
+    assert_at(&GetAutoconnect, b"AT+SQNAUTOCONNECT?\r\n");
+    assert_at(
+        &SetAutoconnect {
+            enabled: Bool::False,
+        },
+        b"AT+SQNAUTOCONNECT=0\r\n",
+    );
+    assert_at(&Reboot, b"AT^RESET\r\n");
+    assert_at(&GetCarrierProfile, b"AT+SQNCTM?\r\n");
+    assert_at(
+        &SetCarrierProfile { profile: "att" },
+        b"AT+SQNCTM=\"att\"\r\n",
+    );
+    assert_at(
+        &EnableMultiplexer {
+            mode: 0,
+            subset: 0,
+            port_speed: PortSpeed::B115200,
+            max_frame_size: 127,
+        },
+        b"AT+CMUX=0,0,5,127\r\n",
+    );
+}
+
+#[test]
+fn test_diagnostics() {
+    use diagnostics::{types::*, *};
+
+    assert_at(
+        &ReadLog {
+            log: DiagnosticLog::ProtocolStack,
+            offset: 4096,
+            max_bytes: 256,
+        },
+        b"AT+SQNSLOGREAD=1,4096,256\r\n",
+    );
+}
+
+#[test]
+fn test_dns() {
+    use dns::{types::IpVersion, *};
+
+    assert_at(
+        &ResolveHost {
+            hostname: "example.com",
+            ip_version: None,
+        },
+        b"AT+SQNDNSLKUP=\"example.com\"\r\n",
+    );
+    assert_at(
+        &ResolveHost {
+            hostname: "example.com",
+            ip_version: Some(IpVersion::V6),
+        },
+        b"AT+SQNDNSLKUP=\"example.com\",1\r\n",
+    );
+}
+
+#[test]
+fn test_ping() {
+    use ping::*;
+
+    assert_at(
+        &Ping {
+            host: "8.8.8.8",
+            count: Some(4),
+            size: Some(32),
+            interval: Some(1),
+            timeout: Some(5),
+        },
+        b"AT+PING=\"8.8.8.8\",4,32,1,5\r\n",
+    );
+    assert_at(
+        &Ping {
+            host: "example.com",
+            count: None,
+            size: None,
+            interval: None,
+            timeout: None,
+        },
+        b"AT+PING=\"example.com\"\r\n",
+    );
+}
+
+#[test]
+fn test_mobile_equipment() {
+    use mobile_equipment::{
+        types::{FunctionalMode, ResetFlag},
+        *,
+    };
+
+    // The trailing optional arguments are left out.
+    assert_at(
+        &SetFunctionality {
+            fun: FunctionalMode::Full,
+            rst: None,
+        },
+        b"AT+CFUN=1\r\n",
+    );
+    assert_at(
+        &SetFunctionality {
+            fun: FunctionalMode::Minimum,
+            rst: Some(ResetFlag::On),
+        },
+        b"AT+CFUN=0,1\r\n",
+    );
+    assert_at(&GetFunctionality, b"AT+CFUN?\r\n");
+    assert_at(&GetSignalQuality, b"AT+CSQ\r\n");
+}
+
+#[test]
+fn test_network() {
+    use network::{
+        types::{NetworkSelectionMode, OperatorNameFormat},
+        *,
+    };
+
+    assert_at(
+        &PLMNSelection {
+            mode: NetworkSelectionMode::Automatic,
+            format: None,
+            oper: None,
+        },
+        b"AT+COPS=0\r\n",
+    );
+    assert_at(
+        &PLMNSelection {
+            mode: NetworkSelectionMode::Manual,
+            format: Some(OperatorNameFormat::Numeric),
+            oper: Some("20801"),
+        },
+        b"AT+COPS=1,2,\"20801\"\r\n",
+    );
+    assert_at(&GetPLMNSelection, b"AT+COPS?\r\n");
+}
+
+#[test]
+fn test_pdp() {
+    use pdp::{types::*, *};
+
+    // All the 15 arguments are always sent.
+    assert_at(
+        &DefinePDPContext {
+            cid: 1,
+            pdp_type: PDPType::IP,
+            apn: string("iot.1nce.net"),
+            pdp_addr: String::new(),
+            d_comp: PDPDComp::Off,
+            h_comp: PDPHComp::Off,
+            ipv4_alloc: PDPIPv4Alloc::NAS,
+            request_type: PDPRequestType::NewOrHandover,
+            pdp_pcscf_discovery_method: PDPPCSCF::Auto,
+            for_imcn: Bool::False,
+            nslpi: Bool::False,
+            secure_pco: Bool::False,
+            ipv4_mtu_discovery: Bool::False,
+            local_addr_ind: Bool::False,
+            non_ip_mtu_discovery: Bool::False,
+        },
+        b"AT+CGDCONT=1,\"IP\",\"iot.1nce.net\",\"\",0,0,0,0,0,0,0,0,0,0,0\r\n",
+    );
+    assert_at(
+        &DefinePDPContext {
+            cid: 3,
+            pdp_type: PDPType::IPv4V6,
+            apn: string("internet"),
+            pdp_addr: String::new(),
+            d_comp: PDPDComp::V42BIS,
+            h_comp: PDPHComp::RFC1144,
+            ipv4_alloc: PDPIPv4Alloc::DHCP,
+            request_type: PDPRequestType::Emergency,
+            pdp_pcscf_discovery_method: PDPPCSCF::NAS,
+            for_imcn: Bool::True,
+            nslpi: Bool::True,
+            secure_pco: Bool::False,
+            ipv4_mtu_discovery: Bool::True,
+            local_addr_ind: Bool::False,
+            non_ip_mtu_discovery: Bool::True,
+        },
+        b"AT+CGDCONT=3,\"IPV4V6\",\"internet\",\"\",2,2,1,1,1,1,1,0,1,0,1\r\n",
+    );
+    assert_at(&GetPDPAddress { cid: 1 }, b"AT+CGPADDR=1\r\n");
+    assert_at(
+        &SetPDPAuthentication {
+            cid: 1,
+            auth_prot: PDPAuthProtocol::PAP,
+            username: Some(string("user")),
+            password: Some(string("secret")),
+        },
+        b"AT+CGAUTH=1,1,\"user\",\"secret\"\r\n",
+    );
+    assert_at(
+        &SetPDPAuthentication {
+            cid: 2,
+            auth_prot: PDPAuthProtocol::None,
+            username: None,
+            password: None,
+        },
+        b"AT+CGAUTH=2,0\r\n",
+    );
+}
+
+#[test]
+fn test_sim() {
+    use sim::{types::*, *};
+
+    assert_at(
+        &EnterPin {
+            pin: string("1234"),
+            new_pin: None,
+        },
+        b"AT+CPIN=\"1234\"\r\n",
+    );
+    assert_at(
+        &EnterPin {
+            pin: string("12345678"),
+            new_pin: Some(string("4321")),
+        },
+        b"AT+CPIN=\"12345678\",\"4321\"\r\n",
+    );
+    assert_at(&GetPinStatus, b"AT+CPIN?\r\n");
+    assert_at(&GetIccid, b"AT+SQNCCID?\r\n");
+    assert_at(
+        &SetFacilityLock {
+            fac: Facility::Sim,
+            mode: FacilityLockMode::Lock,
+            passwd: Some(string("1234")),
+        },
+        b"AT+CLCK=\"SC\",1,\"1234\"\r\n",
+    );
+    assert_at(
+        &GetFacilityLock {
+            fac: Facility::Sim,
+            mode: FacilityLockMode::QueryStatus,
+        },
+        b"AT+CLCK=\"SC\",2\r\n",
+    );
+    assert_at(
+        &GenericSimAccess {
+            length: 10,
+            command: "00A4040000",
+        },
+        b"AT+CSIM=10,\"00A4040000\"\r\n",
+    );
+    assert_at(
+        &RestrictedSimAccess {
+            command: RestrictedSimCommand::ReadBinary,
+            file_id: Some(0x6F07),
+            p1: Some(0),
+            p2: Some(0),
+            p3: Some(9),
+            data: None,
+            path_id: None,
+        },
+        b"AT+CRSM=176,28423,0,0,9\r\n",
+    );
+    assert_at(
+        &OpenLogicalChannel {
+            dfname: "A0000005591010FFFFFFFF8900000100",
+        },
+        b"AT+CCHO=\"A0000005591010FFFFFFFF8900000100\"\r\n",
+    );
+    assert_at(&CloseLogicalChannel { session_id: 1 }, b"AT+CCHC=1\r\n");
+    assert_at(
+        &LogicalChannelAccess {
+            session_id: 1,
+            length: 10,
+            command: "80CA9F7F00",
+        },
+        b"AT+CGLA=1,10,\"80CA9F7F00\"\r\n",
+    );
+}
+
+#[test]
+fn test_ssl_tls() {
+    use ssl_tls::{types::*, *};
+
+    // The unset certificates stay as empty arguments, the following ones keep their position.
+    assert_at(
+        &Configure {
+            sp_id: 1,
+            version: SslTlsVersion::Tls13,
+            cipher_specs: String::new(),
+            cert_valid_level: 0b111,
+            ca_cert_id: Nullable::Some(1),
+            client_cert_id: Nullable::None,
+            client_private_key_id: Nullable::None,
+            psk: String::new(),
+            psk_identity: String::new(),
+            storage_id: StorageId::NVM,
+            resume: Resume::Disabled,
+            lifetime: 0,
+        },
+        b"AT+SQNSPCFG=1,3,\"\",7,1,,,\"\",\"\",0,0,0\r\n",
+    );
+}
+
+#[cfg(feature = "socket")]
+#[test]
+fn test_socket() {
+    use socket::{types::*, *};
+
+    assert_at(
+        &ConfigureExtended {
+            conn_id: 1,
+            ring_mode: RingMode::DataAmount,
+            recv_data_mode: DataMode::Hex,
+            keepalive: 0,
+            listen_auto_rsp: Some(Bool::False),
+            send_data_mode: Some(DataMode::Hex),
+        },
+        b"AT+SQNSCFGEXT=1,1,1,0,0,1\r\n",
+    );
+    assert_at(
+        &Receive {
+            conn_id: 1,
+            max_bytes: 256,
+        },
+        b"AT+SQNSRECV=1,256\r\n",
+    );
+    assert_at(&GetSocketInfo { conn_id: 2 }, b"AT+SQNSI=2\r\n");
+}
+
+#[test]
+fn test_system_features() {
+    use system_features::{types::*, *};
+
+    assert_at(
+        &ConfigureCMEErrorReports {
+            typ: CMEErrorReports::Verbose,
+        },
+        b"AT+CMEE=2\r\n",
+    );
+    assert_at(
+        &ConfigureCEREGReports {
+            typ: CEREGReports::EnabledUePsmWithLocationEmmCause,
+        },
+        b"AT+CEREG=5\r\n",
+    );
+    assert_at(
+        &ConfigurePacketDomainEventReports {
+            mode: PacketDomainEventReports::Buffered,
+        },
+        b"AT+CGEREP=2\r\n",
+    );
+    assert_at(
+        &ConfigureRingIndicator {
+            mode: RingIndicatorMode::Urcs,
+            duration: Some(100),
+        },
+        b"AT+SQNRICFG=1,100\r\n",
+    );
+}
+
+#[test]
+fn test_nvm() {
+    use nvm::{types::DataType, *};
+
+    assert_at(
+        &PrepareWrite {
+            data_type: DataType::Certificate,
+            index: 1,
+            size: 1024,
+        },
+        b"AT+SQNSNVW=\"certificate\",1,1024\r\n",
+    );
+    // The data follows the prompt as is, without prefix nor termination.
+    assert_at(
+        &Write {
+            data: Bytes::new(b"-----BEGIN CERTIFICATE-----\n"),
+        },
+        b"-----BEGIN CERTIFICATE-----\n",
+    );
+    assert_at(
+        &WriteChunk {
+            data: b"MIIBszCCAVmgAwIBAgIU\n",
+        },
+        b"MIIBszCCAVmgAwIBAgIU\n",
+    );
+    assert_at(
+        &WriteLastChunk {
+            data: b"-----END CERTIFICATE-----\n",
+        },
+        b"-----END CERTIFICATE-----\n",
+    );
+}
+
+#[test]
+fn test_manufacturing() {
+    use manufacturing::{types::KeyType, *};
+
+    assert_at(
+        &BurnPublicKey {
+            size: 91,
+            typ: KeyType::Ecdsa256,
+        },
+        b"AT+SMNPK=91,\"ECDSA 256\"\r\n",
+    );
+    assert_at(
+        &ContinuousWaveTransmit {
+            enabled: Bool::True,
+            earfcn: Some(18300),
+            power: Some(-10),
+        },
+        b"AT+SMCWTX=1,18300,-10\r\n",
+    );
+    assert_at(
+        &ContinuousWaveTransmit {
+            enabled: Bool::False,
+            earfcn: None,
+            power: None,
+        },
+        b"AT+SMCWTX=0\r\n",
+    );
+    assert_at(&MeasureReceivedPower { earfcn: 300 }, b"AT+SMCWRX=300\r\n");
+}
+
+#[cfg(feature = "mqtt")]
+#[test]
+fn test_mqtt() {
+    use mqtt::{types::Qos, *};
+
+    assert_at(
+        &Configure {
+            id: 0,
+            client_id: "device-1",
+            username: "user",
+            password: "secret",
+            sp_id: Some(1),
+        },
+        b"AT+SQNSMQTTCFG=0,\"device-1\",\"user\",\"secret\",1\r\n",
+    );
+    assert_at(
+        &Connect {
+            id: 0,
+            host: "broker.example.com",
+            port: Some(8883),
+            keepalive: Some(60),
+        },
+        b"AT+SQNSMQTTCONNECT=0,\"broker.example.com\",8883,60\r\n",
+    );
+    assert_at(&Disconnect { id: 0 }, b"AT+SQNSMQTTDISCONNECT=0\r\n");
+
+    // The publish prompt is terminated by a lone carriage return, the payload is sent raw.
+    assert_at(
+        &PreparePublish {
+            id: 0,
+            topic: "sensors/temp",
+            qos: Some(Qos::AtLeastOnce),
+            length: 5,
+        },
+        b"AT+SQNSMQTTPUBLISH=0,\"sensors/temp\",1,5\r",
+    );
+    assert_at(&Publish { payload: b"21.5\n" }, b"21.5\n");
+    // A payload larger than the command buffer isn't sent.
+    assert_eq!(Publish { payload: &[0; 16] }.write(&mut [0; 8]), 0);
+
+    assert_at(
+        &Receive {
+            id: 0,
+            topic: "sensors/temp",
+            mid: Some(3),
+            max_length: Some(512),
+        },
+        b"AT+SQNSMQTTRCVMESSAGE=0,\"sensors/temp\",3,512\r\n",
+    );
+    assert_at(
+        &Subscribe {
+            id: 0,
+            topic: "sensors/#",
+            qos: Some(Qos::ExactlyOnce),
+        },
+        b"AT+SQNSMQTTSUBSCRIBE=0,\"sensors/#\",2\r\n",
+    );
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn test_http() {
+    use http::{types::*, *};
+
+    assert_at(
+        &Configure {
+            prof_id: 1,
+            host: "example.com",
+            port: 443,
+            auth_type: AuthType::None,
+            username: "",
+            password: "",
+            ssl_enabled: Bool::True,
+            timeout: 120,
+            cid: 1,
+            sp_id: Some(2),
+        },
+        b"AT+SQNHTTPCFG=1,\"example.com\",443,0,\"\",\"\",1,120,1,2\r\n",
+    );
+    assert_at(
+        &Query {
+            prof_id: 1,
+            command: QueryCommand::Get,
+            resource: "/status?id=7",
+        },
+        b"AT+SQNHTTPQRY=1,0,\"/status?id=7\"\r\n",
+    );
+    assert_at(
+        &Receive {
+            prof_id: 1,
+            max_bytes: 256,
+        },
+        b"AT+SQNHTTPRCV=1,256\r\n",
+    );
+}
+
+#[cfg(feature = "lwm2m")]
+#[test]
+fn test_lwm2m() {
+    use lwm2m::*;
+
+    assert_at(
+        &Enable {
+            enabled: Bool::True,
+        },
+        b"AT+SQNLWM2MENABLE=1\r\n",
+    );
+    assert_at(&Bootstrap, b"AT+SQNLWM2MBOOTSTRAP\r\n");
+    assert_at(&Register, b"AT+SQNLWM2MREG\r\n");
+    assert_at(&UpdateRegistration, b"AT+SQNLWM2MUPDATE\r\n");
+    assert_at(&Deregister, b"AT+SQNLWM2MDEREG\r\n");
+    assert_at(
+        &SetResource {
+            object: 3303,
+            instance: 0,
+            resource: 5700,
+            value: "21.5",
+        },
+        b"AT+SQNLWM2MSET=3303,0,5700,\"21.5\"\r\n",
+    );
+    assert_at(
+        &GetResource {
+            object: 3303,
+            instance: 0,
+            resource: 5750,
+        },
+        b"AT+SQNLWM2MGET=3303,0,5750\r\n",
+    );
+}
+
+#[cfg(feature = "nidd")]
+#[test]
+fn test_nidd() {
+    use nidd::{types::*, *};
+
+    assert_at(
+        &SetCiotOptimization {
+            reporting: CiotOptimizationReporting::Disabled,
+            supported: Some(CiotOptimization::ControlPlane),
+            preferred: Some(PreferredCiotOptimization::ControlPlane),
+        },
+        b"AT+CCIOTOPT=0,1,1\r\n",
+    );
+    assert_at(&GetCiotOptimization, b"AT+CCIOTOPT?\r\n");
+    assert_at(
+        &SendData {
+            cid: 1,
+            length: 3,
+            data: string("0A0B0C"),
+            rai: None,
+            user_data_type: None,
+        },
+        b"AT+CSODCP=1,3,\"0A0B0C\"\r\n",
+    );
+    assert_at(
+        &SendData {
+            cid: 2,
+            length: 1,
+            data: string("FF"),
+            rai: Some(ReleaseAssistance::NoFurtherData),
+            user_data_type: Some(UserDataType::Exception),
+        },
+        b"AT+CSODCP=2,1,\"FF\",1,1\r\n",
+    );
+    assert_at(
+        &ConfigureDataReporting {
+            reporting: Bool::True,
+        },
+        b"AT+CRTDCP=1\r\n",
+    );
+    assert_at(&GetDataReporting, b"AT+CRTDCP?\r\n");
+}
+
+#[cfg(feature = "sms")]
+#[test]
+fn test_sms() {
+    use sms::*;
+
+    assert_at(
+        &ConfigureNewMessageIndications { mode: 2, mt: 1 },
+        b"AT+CNMI=2,1\r\n",
+    );
+}
+
+#[cfg(feature = "gnss")]
+#[test]
+fn test_gnss() {
+    use gnss::{types::*, *};
+
+    assert_at(&GetGnssConfig, b"AT+LPGNSSCFG?\r\n");
+    // The reserved argument is left empty.
+    assert_at(
+        &SetGnssConfig {
+            location_mode: LocationMode::OnDeviceLocation,
+            fix_sensitivity: FixSensitivity::Medium,
+            urc_settings: UrcNotificationSetting::Full,
+            reserved: Reserved,
+            metrics: Bool::True,
+            acquisition_mode: AcquisitionMode::ColdWarmStart,
+            early_abort: Bool::False,
+        },
+        b"AT+LPGNSSCFG=0,2,2,,1,0,0\r\n",
+    );
+    assert_at(
+        &UpdateGnssAssistance {
+            typ: GnssAssistanceType::RealTimeEphemeris,
+        },
+        b"AT+LPGNSSASSISTANCE=1\r\n",
+    );
+    assert_at(&GetGnssAssistance, b"AT+LPGNSSASSISTANCE?\r\n");
+    assert_at(
+        &PrepareUploadGnssAssistance {
+            typ: GnssAssistanceType::Almanac,
+            size: 2048,
+        },
+        b"AT+LPGNSSUPLOADASSISTANCE=0,2048\r\n",
+    );
+    assert_at(
+        &SetApproximatePositionAssistance {
+            lat: QuotedF32(48.5),
+            long: QuotedF32(2.25),
+            elev: None,
+        },
+        b"AT+LPGNSSAPPROXPOS=\"48.5\",\"2.25\"\r\n",
+    );
+    assert_at(
+        &ProgramGnss {
+            action: ProgramGnssAction::Single,
+        },
+        b"AT+LPGNSSFIXPROG=\"single\"\r\n",
+    );
+    assert_at(
+        &SetGnssCloudServerName {
+            hostname: "api.example.com",
+        },
+        b"AT+LPGNSSCLOUDSEL=\"api.example.com\"\r\n",
+    );
+    assert_at(&GetGnssCloudServerName, b"AT+LPGNSSCLOUDSEL?\r\n");
+    assert_at(
+        &SetGnssTimeout { timeout: 120 },
+        b"AT+LPGNSSTIMEOUT=120\r\n",
+    );
+    assert_at(&GetGnssTimeout, b"AT+LPGNSSTIMEOUT?\r\n");
+}
@@ -0,0 +1,130 @@
+//! HTTP client of the modem, requests sent on one of its profiles.
+//!
+//! A profile is configured with [`Configure`], then a request is sent with [`Query`]. The modem
+//! reports the response with a [`Ring`](urc::Ring) URC, its body is then read in slices of at
+//! most [`HTTP_RECV_LEN`] bytes with [`Receive`].
+
+use atat::{
+    InternalError,
+    atat_derive::AtatCmd,
+    serde_at::{SerializeOptions, to_slice},
+};
+use responses::HttpData;
+use serde::Serialize;
+use types::{AuthType, QueryCommand};
+
+use super::NoResponse;
+use crate::types::Bool;
+
+pub mod responses;
+pub mod types;
+pub mod urc;
+
+/// Largest number of bytes of a response body read at once with [`Receive`].
+pub const HTTP_RECV_LEN: usize = 256;
+
+/// Longest resource of a [`Query`], the path and query string of the URL.
+pub const HTTP_RESOURCE_LEN: usize = 256;
+
+/// Configures the server of an HTTP profile.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNHTTPCFG", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Configure<'a> {
+    /// Profile id, from 0 to 5.
+    #[at_arg(position = 0)]
+    pub prof_id: u8,
+
+    /// Host name or IP address of the server.
+    #[at_arg(position = 1, len = 253)]
+    pub host: &'a str,
+
+    /// Port of the server, 80 by default, 443 with TLS.
+    #[at_arg(position = 2)]
+    pub port: u16,
+
+    #[at_arg(position = 3)]
+    pub auth_type: AuthType,
+
+    /// User name of the basic authentication, empty without.
+    #[at_arg(position = 4, len = 64)]
+    pub username: &'a str,
+
+    /// Password of the basic authentication, empty without.
+    #[at_arg(position = 5, len = 64)]
+    pub password: &'a str,
+
+    #[at_arg(position = 6)]
+    pub ssl_enabled: Bool,
+
+    /// Time to wait for the server, in seconds.
+    #[at_arg(position = 7)]
+    pub timeout: u16,
+
+    /// PDP context used for the requests.
+    #[at_arg(position = 8)]
+    pub cid: u8,
+
+    /// The index of the secure profile previously set with the SSL / TLS Security Profile
+    /// Configuration, when TLS is enabled.
+    #[at_arg(position = 9)]
+    pub sp_id: Option<u8>,
+}
+
+/// Sends a request without body on a configured profile, e.g. a GET.
+///
+/// The modem reports the response with a [`Ring`](urc::Ring) URC.
+///
+/// Type: `asynchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNHTTPQRY", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Query<'a> {
+    /// Profile id, from 0 to 5.
+    #[at_arg(position = 0)]
+    pub prof_id: u8,
+
+    #[at_arg(position = 1)]
+    pub command: QueryCommand,
+
+    /// Path and query string of the URL, starting with `/`, up to [`HTTP_RESOURCE_LEN`]
+    /// characters.
+    #[at_arg(position = 2, len = 256)]
+    pub resource: &'a str,
+}
+
+/// Reads the body of the last response received on a profile, up to [`HTTP_RECV_LEN`] bytes.
+///
+/// The modem answers with the data after a `<<<` marker, see [`HttpData`].
+///
+/// Type: `synchronous`
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Receive {
+    /// Profile id, from 0 to 5.
+    pub prof_id: u8,
+
+    /// Number of bytes to read, up to [`HTTP_RECV_LEN`].
+    pub max_bytes: u16,
+}
+
+impl atat::AtatCmd for Receive {
+    type Response = HttpData;
+
+    const MAX_LEN: usize = "AT+SQNHTTPRCV=".len()
+        + 1 // profile id
+        + 6 // max bytes
+        + 2; // termination
+    const MAX_TIMEOUT_MS: u32 = 1000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Can't fail, both arguments are numbers.
+        to_slice(self, "+SQNHTTPRCV", buf, SerializeOptions::default()).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        HttpData::parse(resp?).ok_or(atat::Error::Parse)
+    }
+}
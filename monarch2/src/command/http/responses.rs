@@ -0,0 +1,43 @@
+use atat::AtatResp;
+use heapless::Vec;
+
+use super::HTTP_RECV_LEN;
+
+/// Response to [`Receive`](super::Receive), a slice of the body of the response.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct HttpData {
+    /// The data read, empty once the whole body was read.
+    pub data: Vec<u8, HTTP_RECV_LEN>,
+}
+
+impl AtatResp for HttpData {}
+
+impl HttpData {
+    /// Parses the data following the `<<<` marker of the response, as it is.
+    ///
+    /// Returns `None` without the marker, or if the data exceeds [`HTTP_RECV_LEN`] bytes.
+    pub fn parse(resp: &[u8]) -> Option<Self> {
+        let start = resp.windows(3).position(|w| w == b"<<<")? + 3;
+        let data = Vec::from_slice(&resp[start..]).ok()?;
+        Some(Self { data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_data_parse() {
+        let res = HttpData::parse(b"<<<{\"on\":true}").unwrap();
+        assert_eq!(res.data, b"{\"on\":true}");
+
+        // The whole body was read.
+        let res = HttpData::parse(b"<<<").unwrap();
+        assert!(res.data.is_empty());
+
+        assert_eq!(HttpData::parse(b"{\"on\":true}"), None);
+    }
+}
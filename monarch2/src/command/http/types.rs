@@ -0,0 +1,21 @@
+use atat::atat_derive::AtatEnum;
+
+/// Authentication of an HTTP profile, see [`Configure`](super::Configure).
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum AuthType {
+    None = 0,
+    /// HTTP basic authentication, with a user name and a password.
+    Basic = 1,
+}
+
+/// Method of a request without body, see [`Query`](super::Query).
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum QueryCommand {
+    Get = 0,
+    Head = 1,
+    Delete = 2,
+}
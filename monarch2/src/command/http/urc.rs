@@ -0,0 +1,40 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+/// The response to a request was received on a profile, its body is read with
+/// [`Receive`](super::Receive).
+#[derive(Debug, Clone, PartialEq, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Ring {
+    /// Profile id.
+    #[at_arg(position = 0)]
+    pub prof_id: u8,
+
+    /// HTTP status code of the response, e.g. 200.
+    #[at_arg(position = 1)]
+    pub status_code: u16,
+
+    /// Content type of the body, empty if the server didn't send one.
+    #[at_arg(position = 2)]
+    pub content_type: String<64>,
+
+    /// Length of the body, in bytes.
+    #[at_arg(position = 3)]
+    pub data_size: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_ring_parse() {
+        let res: Ring = from_str("+SQNHTTPRING: 1,200,\"application/json\",42").unwrap();
+        assert_eq!(res.prof_id, 1);
+        assert_eq!(res.status_code, 200);
+        assert_eq!(res.content_type, "application/json");
+        assert_eq!(res.data_size, 42);
+    }
+}
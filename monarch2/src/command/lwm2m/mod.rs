@@ -0,0 +1,111 @@
+use atat::atat_derive::AtatCmd;
+use responses::ResourceValue;
+
+use super::NoResponse;
+use crate::types::Bool;
+
+pub mod responses;
+pub mod types;
+pub mod urc;
+
+/// Longest resource value written with [`SetResource`] or reported by the server, as text.
+pub const LWM2M_VALUE_LEN: usize = 64;
+
+/// Enables or disables the LwM2M client of the modem.
+///
+/// Once enabled, the client bootstraps (if configured) then registers to the LwM2M server, the
+/// progress is reported by `+SQNLWM2MEVT` URCs, see [`urc::Event`]. Disabling it deregisters
+/// from the server first.
+///
+/// Type: `asynchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNLWM2MENABLE", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Enable {
+    #[at_arg(position = 0)]
+    pub enabled: Bool,
+}
+
+/// Starts a bootstrap with the bootstrap server, e.g. to renew the server credentials.
+///
+/// Type: `asynchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bootstrap;
+
+static_cmd!(Bootstrap, "+SQNLWM2MBOOTSTRAP", NoResponse, timeout = 300);
+
+/// Registers to the LwM2M server, the client must be enabled with [`Enable`].
+///
+/// Type: `asynchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Register;
+
+static_cmd!(Register, "+SQNLWM2MREG", NoResponse, timeout = 300);
+
+/// Sends a registration update to the LwM2M server, e.g. to tell it that the device is reachable
+/// again after a PSM sleep.
+///
+/// Type: `asynchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UpdateRegistration;
+
+static_cmd!(
+    UpdateRegistration,
+    "+SQNLWM2MUPDATE",
+    NoResponse,
+    timeout = 300
+);
+
+/// Deregisters from the LwM2M server, the client stays enabled.
+///
+/// Type: `asynchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Deregister;
+
+static_cmd!(Deregister, "+SQNLWM2MDEREG", NoResponse, timeout = 300);
+
+/// Sets the value of a resource exposed by the client.
+///
+/// The client answers the reads of the server from these values, and notifies the server when
+/// it observes the resource.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNLWM2MSET", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetResource<'a> {
+    /// Object id, e.g. 3303 for a temperature sensor.
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+
+    /// Value as text, up to [`LWM2M_VALUE_LEN`] characters.
+    #[at_arg(position = 3, len = 64)]
+    pub value: &'a str,
+}
+
+/// Reads the value of a resource, e.g. after the server wrote it.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNLWM2MGET", ResourceValue, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetResource {
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+}
@@ -0,0 +1,20 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+/// Value of a resource read with [`GetResource`](super::GetResource).
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResourceValue {
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+
+    /// Value as text.
+    #[at_arg(position = 3)]
+    pub value: String<64>,
+}
@@ -0,0 +1,34 @@
+use atat::atat_derive::AtatEnum;
+
+/// State change of the LwM2M client, reported by [`Event`](super::urc::Event).
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum ClientEvent {
+    /// The client was disabled.
+    Disabled = 0,
+    BootstrapStarted = 1,
+    /// The bootstrap server provisioned the server credentials.
+    BootstrapCompleted = 2,
+    BootstrapFailed = 3,
+    /// The client is registered, the server can access its resources.
+    Registered = 4,
+    /// The server rejected the registration or couldn't be reached.
+    RegistrationFailed = 5,
+    /// The registration was renewed with an update.
+    RegistrationUpdated = 6,
+    Deregistered = 7,
+}
+
+impl ClientEvent {
+    /// Whether the event ends a registration attempt, see
+    /// [`Modem::lwm2m_register`](crate::Modem::lwm2m_register).
+    pub fn ends_registration(&self) -> bool {
+        matches!(
+            self,
+            ClientEvent::Registered
+                | ClientEvent::RegistrationFailed
+                | ClientEvent::BootstrapFailed
+        )
+    }
+}
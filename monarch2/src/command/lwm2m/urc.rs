@@ -0,0 +1,110 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+use super::types::ClientEvent;
+use crate::types::Bool;
+
+/// State change of the LwM2M client, e.g. the completion of the registration.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Event {
+    #[at_arg(position = 0)]
+    pub event: ClientEvent,
+}
+
+/// The server wrote the value of a resource.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ResourceWritten {
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+
+    /// Written value as text.
+    #[at_arg(position = 3)]
+    pub value: String<64>,
+}
+
+/// The server executed a resource, e.g. 3/0/4 to reboot the device.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ResourceExecuted {
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+
+    /// Arguments of the execution, if any.
+    #[at_arg(position = 3)]
+    pub arguments: Option<String<32>>,
+}
+
+/// The server started or cancelled the observation of a resource.
+///
+/// The client notifies the observed resources when their value is set with
+/// [`SetResource`](super::SetResource).
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Observation {
+    #[at_arg(position = 0)]
+    pub object: u16,
+
+    #[at_arg(position = 1)]
+    pub instance: u16,
+
+    #[at_arg(position = 2)]
+    pub resource: u16,
+
+    /// Whether the resource is observed from now on.
+    #[at_arg(position = 3)]
+    pub observed: Bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_event_parse() {
+        let res: Event = from_str("+SQNLWM2MEVT: 4").unwrap();
+        assert_eq!(res.event, ClientEvent::Registered);
+        assert!(res.event.ends_registration());
+    }
+
+    #[test]
+    fn test_resource_written_parse() {
+        let res: ResourceWritten = from_str("+SQNLWM2MWRITE: 3303,0,5750,\"living room\"").unwrap();
+        assert_eq!(res.object, 3303);
+        assert_eq!(res.instance, 0);
+        assert_eq!(res.resource, 5750);
+        assert_eq!(res.value, "living room");
+    }
+
+    #[test]
+    fn test_resource_executed_parse() {
+        let res: ResourceExecuted = from_str("+SQNLWM2MEXEC: 3,0,4").unwrap();
+        assert_eq!(res.resource, 4);
+        assert_eq!(res.arguments, None);
+    }
+
+    #[test]
+    fn test_observation_parse() {
+        let res: Observation = from_str("+SQNLWM2MOBSERVE: 3303,0,5700,1").unwrap();
+        assert_eq!(res.resource, 5700);
+        assert_eq!(res.observed, Bool::True);
+    }
+}
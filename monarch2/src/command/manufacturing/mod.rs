@@ -1,9 +1,12 @@
 use atat::atat_derive::AtatCmd;
+use responses::ReceivedPower;
 use types::KeyType;
 
+pub mod responses;
 pub mod types;
 
 use super::NoResponse;
+use crate::types::Bool;
 
 /// This command allows to set the public key used to check the integrity of the upgrade packages.
 ///
@@ -19,6 +22,7 @@ use super::NoResponse;
 ///
 /// AT+CFUN=5, OTP unlocked and pubkey not already set.
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+SMNPK", NoResponse, timeout = 300)]
 pub struct BurnPublicKey {
     /// Size in bytes of PEM encoded public key.
@@ -29,3 +33,44 @@ pub struct BurnPublicKey {
     #[at_arg(position = 1)]
     pub typ: KeyType,
 }
+
+/// Starts or stops the transmission of an unmodulated carrier (continuous wave) for RF and
+/// regulatory testing.
+///
+/// # WARNING
+///
+/// This is a manufacturing mode command. You need to enter manufacturing mode with AT +CFUN=5 before using it.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SMCWTX", NoResponse, timeout = 1000)]
+pub struct ContinuousWaveTransmit {
+    #[at_arg(position = 0)]
+    pub enabled: Bool,
+
+    /// Uplink EARFCN of the carrier, required to start the transmission.
+    #[at_arg(position = 1)]
+    pub earfcn: Option<u32>,
+
+    /// Output power in dBm, required to start the transmission.
+    #[at_arg(position = 2)]
+    pub power: Option<i8>,
+}
+
+/// Measures the power received on a downlink channel, e.g. from a signal generator on the
+/// production line.
+///
+/// # WARNING
+///
+/// This is a manufacturing mode command. You need to enter manufacturing mode with AT +CFUN=5 before using it.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SMCWRX", ReceivedPower, timeout = 5000)]
+pub struct MeasureReceivedPower {
+    /// Downlink EARFCN of the channel.
+    #[at_arg(position = 0)]
+    pub earfcn: u32,
+}
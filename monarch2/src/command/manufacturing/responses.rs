@@ -0,0 +1,23 @@
+use atat::atat_derive::AtatResp;
+
+/// Response to [`MeasureReceivedPower`](super::MeasureReceivedPower).
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ReceivedPower {
+    /// Received power in dBm.
+    #[at_arg(position = 0)]
+    pub rssi: i16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_received_power_parse() {
+        let res: ReceivedPower = from_str("+SMCWRX: -67").unwrap();
+        assert_eq!(res.rssi, -67);
+    }
+}
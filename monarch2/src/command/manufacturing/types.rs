@@ -2,6 +2,7 @@ use atat::atat_derive::AtatEnum;
 
 /// Public key type.
 #[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum KeyType {
     /// ECDSA public key, 256 bits..
     #[at_enum("ECDSA 256")]
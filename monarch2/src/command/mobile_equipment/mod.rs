@@ -1,5 +1,5 @@
 use atat::atat_derive::AtatCmd;
-use responses::SignalQuality;
+use responses::{Functionality, SignalQuality};
 use types::{FunctionalMode, ResetFlag};
 
 use super::NoResponse;
@@ -21,10 +21,18 @@ pub struct SetFunctionality {
     pub rst: Option<ResetFlag>,
 }
 
+/// Reads the current functionality level of the device.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetFunctionality;
+
+static_cmd!(GetFunctionality, "+CFUN?", Functionality);
+
 /// This command returns received signal strength indication (rssi).
 ///
 /// See also Mobile Termination Error Result Code: +CME ERROR for error values.
-#[derive(Clone, Debug, AtatCmd)]
-#[at_cmd("+CSQ", SignalQuality)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GetSignalQuality;
+
+static_cmd!(GetSignalQuality, "+CSQ", SignalQuality);
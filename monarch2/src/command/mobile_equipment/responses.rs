@@ -1,7 +1,10 @@
 use atat::atat_derive::AtatResp;
 
+use super::types::FunctionalMode;
+
 #[derive(Clone, Debug, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct SignalQuality {
     /// The RSSI of the signal in dBm.
     #[at_arg(position = 0)]
@@ -11,3 +14,24 @@ pub struct SignalQuality {
     #[at_arg(position = 1)]
     pub ber: u8,
 }
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Functionality {
+    /// Current functionality level.
+    #[at_arg(position = 0)]
+    pub fun: FunctionalMode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_functionality_parse() {
+        let res: Functionality = from_str("+CFUN: 4").unwrap();
+        assert_eq!(res.fun, FunctionalMode::AirplaneMode);
+    }
+}
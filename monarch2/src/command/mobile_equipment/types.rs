@@ -11,6 +11,33 @@ pub enum FunctionalMode {
     Full = 1,
     /// Aurplane mode
     AirplaneMode = 4,
+    /// Manufacturing mode
+    Manufacturing = 5,
+}
+
+/// Power state of the modem as tracked by the driver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PowerState {
+    /// Radio off, CFUN=0.
+    Minimum,
+    /// Radio off with the SIM accessible, CFUN=4.
+    Airplane,
+    /// Fully operational, CFUN=1.
+    Full,
+    /// Manufacturing mode, CFUN=5.
+    Manufacturing,
+}
+
+impl From<FunctionalMode> for PowerState {
+    fn from(mode: FunctionalMode) -> Self {
+        match mode {
+            FunctionalMode::Minimum => PowerState::Minimum,
+            FunctionalMode::Full => PowerState::Full,
+            FunctionalMode::AirplaneMode => PowerState::Airplane,
+            FunctionalMode::Manufacturing => PowerState::Manufacturing,
+        }
+    }
 }
 
 /// Reset flag
@@ -1,23 +1,67 @@
 use atat::{
-    AtatLen,
-    atat_derive::{AtatCmd, AtatResp, AtatUrc},
+    AtatLen, InternalError,
+    atat_derive::{AtatResp, AtatUrc},
 };
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
+/// Implements [`atat::AtatCmd`] for a command without parameters, written from a static byte
+/// string instead of being serialized at runtime like the derived commands.
+///
+/// `static_cmd!(GetClock, "+CCLK?", Clock, timeout = 1000)` sends `AT+CCLK?\r\n`, the timeout
+/// in milliseconds is optional.
+macro_rules! static_cmd {
+    ($cmd:ty, $at:literal, $resp:ty $(, timeout = $timeout:literal)?) => {
+        impl atat::AtatCmd for $cmd {
+            type Response = $resp;
+
+            const MAX_LEN: usize = concat!("AT", $at, "\r\n").len();
+            $(const MAX_TIMEOUT_MS: u32 = $timeout;)?
+
+            fn write(&self, buf: &mut [u8]) -> usize {
+                let cmd = concat!("AT", $at, "\r\n").as_bytes();
+                buf[..cmd.len()].copy_from_slice(cmd);
+                cmd.len()
+            }
+
+            fn parse(
+                &self,
+                resp: Result<&[u8], atat::InternalError>,
+            ) -> Result<Self::Response, atat::Error> {
+                atat::serde_at::from_slice(resp?).map_err(|_| atat::Error::Parse)
+            }
+        }
+    };
+}
+
 pub mod types;
 
+#[cfg(feature = "coap")]
 pub mod coap;
 pub mod device;
-#[cfg(feature = "gm02sp")]
+pub mod diagnostics;
+pub mod dns;
+#[cfg(feature = "gnss")]
 pub mod gnss;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "lwm2m")]
+pub mod lwm2m;
 pub mod manufacturing;
 pub mod mobile_equipment;
+#[cfg(feature = "mqtt")]
 pub mod mqtt;
 pub mod network;
+#[cfg(feature = "nidd")]
+pub mod nidd;
 pub mod nvm;
 pub mod pdp;
+pub mod ping;
+pub mod raw;
 pub mod sim;
+#[cfg(feature = "sms")]
 pub mod sms;
+#[cfg(feature = "socket")]
+pub mod socket;
 pub mod ssl_tls;
 pub mod system_features;
 
@@ -25,31 +69,69 @@ pub mod system_features;
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct NoResponse;
 
-#[derive(Clone, AtatCmd)]
+#[derive(Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[at_cmd("", NoResponse)]
 pub struct AT;
 
+static_cmd!(AT, "", NoResponse);
+
+/// Aborts a pending prompt (e.g. MQTT publish, NVM write) by sending the ESC character.
+///
+/// The modem leaves the prompt mode without a final result code.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AbortPrompt;
+
+impl atat::AtatCmd for AbortPrompt {
+    type Response = NoResponse;
+
+    const MAX_LEN: usize = 1;
+    const EXPECTS_RESPONSE_CODE: bool = false;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        buf[0] = 0x1B;
+        1
+    }
+
+    fn parse(&self, _resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        Ok(NoResponse)
+    }
+}
+
 #[derive(Debug, Clone, AtatUrc)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 #[allow(clippy::large_enum_variant)]
 pub enum Urc {
-    #[cfg(feature = "gm02sp")]
+    #[cfg(feature = "gnss")]
     #[at_urc("+LPGNSSFIXREADY")]
     GnssFixReady(gnss::urc::GnssFixReady),
+    /// The GNSS fix in progress was stopped.
+    #[cfg(feature = "gnss")]
+    #[at_urc("+LPGNSSFIXSTOP")]
+    GnssFixStopped,
 
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTONCONNECT")]
     MqttConnected(mqtt::urc::Connected),
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTONDISCONNECT")]
     MqttDisconnected(mqtt::urc::Disconnected),
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTONPUBLISH")]
     MqttMessagePublished(mqtt::urc::PublishResponse),
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTONMESSAGE")]
     MqttMessageReceived(mqtt::urc::Received),
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTONSUBSCRIBE")]
     MqttSubscribed(mqtt::urc::Subscribed),
+    #[cfg(feature = "mqtt")]
     #[at_urc("+SQNSMQTTPUBLISH")]
     MqttPromptToPublish(mqtt::urc::PromptToPublish),
+    #[cfg(feature = "mqtt")]
+    #[at_urc("+SQNSMQTTMEMORYFULL")]
+    MqttMemoryFull(mqtt::urc::MemoryFull),
 
     /// The + SHUTDOWN URC indicates that the ME has completed the shutdown procedure and is about to restart.
     #[at_urc("+SHUTDOWN")]
@@ -61,9 +143,126 @@ pub enum Urc {
 
     #[at_urc("+CEREG")]
     NetworkRegistrationStatus(network::urc::NetworkRegistrationStatus),
+    #[at_urc("+CGEV")]
+    PacketDomainEvent(network::urc::PacketDomainEvent),
+    #[at_urc("+CEDRXP")]
+    EdrxParameters(network::urc::EdrxParameters),
+
+    #[at_urc("+SQNSIMST")]
+    SimStatus(sim::urc::SimStatus),
+
+    #[cfg(feature = "socket")]
+    #[at_urc("+SQNSRING")]
+    SocketRing(socket::urc::Ring),
+
+    #[cfg(feature = "http")]
+    #[at_urc("+SQNHTTPRING")]
+    HttpRing(http::urc::Ring),
+
+    #[cfg(feature = "sms")]
+    #[at_urc("+CMTI")]
+    SmsReceived(sms::urc::NewMessage),
+
+    #[cfg(feature = "nidd")]
+    #[at_urc("+CRTDCP")]
+    NiddDataReceived(nidd::urc::DataReceived),
+
+    #[at_urc("+SQNSUPGRADE")]
+    UpgradeStatus(device::urc::UpgradeStatus),
+    #[at_urc("+SQNNTP")]
+    NtpSynchronized(device::urc::NtpSynchronized),
 
+    #[cfg(feature = "coap")]
     #[at_urc("+SQNCOAPCONNECTED")]
     CoapConnected(coap::urc::Connected),
+    #[cfg(feature = "coap")]
+    #[at_urc("+SQNCOAPCLOSED")]
+    CoapClosed(coap::urc::Closed),
+    #[cfg(feature = "coap")]
+    #[at_urc("+SQNCOAPRING")]
+    CoapRing(coap::urc::Ring),
+
+    #[cfg(feature = "lwm2m")]
+    #[at_urc("+SQNLWM2MEVT")]
+    Lwm2mEvent(lwm2m::urc::Event),
+    #[cfg(feature = "lwm2m")]
+    #[at_urc("+SQNLWM2MWRITE")]
+    Lwm2mResourceWritten(lwm2m::urc::ResourceWritten),
+    #[cfg(feature = "lwm2m")]
+    #[at_urc("+SQNLWM2MEXEC")]
+    Lwm2mResourceExecuted(lwm2m::urc::ResourceExecuted),
+    #[cfg(feature = "lwm2m")]
+    #[at_urc("+SQNLWM2MOBSERVE")]
+    Lwm2mObservation(lwm2m::urc::Observation),
+
+    /// URC this enum doesn't know or fails to parse, e.g. added or changed by a newer firmware,
+    /// as its line truncated to [`UNKNOWN_URC_LEN`](crate::UNKNOWN_URC_LEN) bytes.
+    ///
+    /// Only produced by the [`UrcParser`], the derived parser never matches it.
+    #[cfg(feature = "unknown-urc")]
+    #[at_urc("+", parse = no_urc)]
+    Unknown(heapless::String<crate::capacity::UNKNOWN_URC_LEN>),
+}
+
+/// Digest parser of [`Urc::Unknown`], matching no line: the unknown URCs are the lines the
+/// [`UrcParser`] gets from the digester but [`Urc`] fails to parse.
+#[cfg(feature = "unknown-urc")]
+fn no_urc<'a, E: atat::nom::error::ParseError<&'a [u8]>>(
+    _code: &[u8],
+) -> impl Fn(&'a [u8]) -> atat::nom::IResult<&'a [u8], (&'a [u8], usize), E> {
+    |buf| {
+        Err(atat::nom::Err::Error(E::from_error_kind(
+            buf,
+            atat::nom::error::ErrorKind::Fail,
+        )))
+    }
+}
+
+/// Parser of the [`Urc`]s falling back to [`Urc::Unknown`] instead of dropping the line, the URC
+/// type of the `atat` ingress with the `unknown-urc` feature:
+///
+/// ```ignore
+/// type ModemIngress<'a> = Ingress<
+///     'a,
+///     CmeCodeDigester<'a, AtDigester<Urc>>,
+///     UrcParser,
+///     INGRESS_BUF_SIZE,
+///     URC_CAPACITY,
+///     URC_SUBSCRIBERS,
+/// >;
+/// ```
+///
+/// The `AtDigester` only passes the lines of the known URC prefixes, the
+/// [`CmeCodeDigester`](crate::CmeCodeDigester) passes the other `+<NAME>: ...` lines received
+/// while no command waits for its response. The [`UrcChannel`](atat::UrcChannel) still carries
+/// [`Urc`]s.
+#[cfg(feature = "unknown-urc")]
+pub struct UrcParser;
+
+/// Parser of the [`Urc`]s, the URC type of the `atat` ingress: [`Urc`] itself without the
+/// `unknown-urc` feature.
+#[cfg(not(feature = "unknown-urc"))]
+pub type UrcParser = Urc;
+
+#[cfg(feature = "unknown-urc")]
+impl atat::AtatUrc for UrcParser {
+    type Response = Urc;
+
+    fn parse(resp: &[u8]) -> Option<Urc> {
+        <Urc as atat::AtatUrc>::parse(resp).or_else(|| {
+            let line = match core::str::from_utf8(resp) {
+                Ok(line) => line,
+                Err(err) => core::str::from_utf8(&resp[..err.valid_up_to()]).ok()?,
+            };
+            let mut len = line.len().min(crate::capacity::UNKNOWN_URC_LEN);
+            while !line.is_char_boundary(len) {
+                len -= 1;
+            }
+            heapless::String::try_from(&line[..len])
+                .ok()
+                .map(Urc::Unknown)
+        })
+    }
 }
 
 /// Used for reserved fields that are currently ignored but can't be skipped
@@ -112,6 +311,11 @@ impl<'de> Deserialize<'de> for Reserved {
     }
 }
 
+#[cfg(test)]
+mod golden;
+#[cfg(test)]
+mod roundtrip;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +327,26 @@ mod tests {
         let x = Urc::parse(input);
         assert_eq!(708, x.unwrap().1);
     }
+
+    #[cfg(feature = "unknown-urc")]
+    #[test]
+    fn test_unknown_urc() {
+        use atat::AtatUrc;
+
+        assert!(matches!(UrcParser::parse(b"+SYSSTART"), Some(Urc::Start)));
+        match UrcParser::parse(b"+SQNSFOO: 1,\"bar\"") {
+            Some(Urc::Unknown(line)) => assert_eq!(line, "+SQNSFOO: 1,\"bar\""),
+            _ => panic!("unexpected URC"),
+        }
+
+        // Truncated to the capacity, on a character boundary.
+        let mut long = std::vec![b'+'; crate::capacity::UNKNOWN_URC_LEN - 1];
+        long.extend_from_slice("é".as_bytes());
+        match UrcParser::parse(&long) {
+            Some(Urc::Unknown(line)) => {
+                assert_eq!(line.len(), crate::capacity::UNKNOWN_URC_LEN - 1)
+            }
+            _ => panic!("unexpected URC"),
+        }
+    }
 }
@@ -1,5 +1,4 @@
-use atat::atat_derive::AtatCmd;
-use heapless::String;
+use atat::{InternalError, atat_derive::AtatCmd};
 use types::Qos;
 
 use super::NoResponse;
@@ -8,6 +7,9 @@ pub mod responses;
 pub mod types;
 pub mod urc;
 
+/// Largest payload of a [`Publish`].
+pub const MQTT_PAYLOAD_LEN: usize = 2048;
+
 /// This command disconnects from a broker. Connection must have been previously initiated with the Initiate MQTT.
 ///
 /// Type: `asynchronous`
@@ -37,12 +39,12 @@ pub struct Configure<'a> {
     pub client_id: &'a str,
 
     /// Username for broker authentication.
-    #[at_arg(position = 2)]
-    pub username: String<256>,
+    #[at_arg(position = 2, len = 256)]
+    pub username: &'a str,
 
     /// Password for broker authentication.
-    #[at_arg(position = 3)]
-    pub password: String<256>,
+    #[at_arg(position = 3, len = 256)]
+    pub password: &'a str,
 
     /// The index of the secure profile previously set with the SSL / TLS Security Profile Configuration.
     #[at_arg(position = 4)]
@@ -104,7 +106,7 @@ pub struct PreparePublish<'a> {
     pub id: u8,
 
     /// The topic the client wants to publish to.
-    #[at_arg(position = 1, len = 64)]
+    #[at_arg(position = 1, len = 256)]
     pub topic: &'a str,
 
     /// The quality of service level to request for the subscription.
@@ -116,22 +118,36 @@ pub struct PreparePublish<'a> {
     pub length: usize,
 }
 
-// NOTE: this can be nicer, we shouldn't need to have 2 separate commands but instead implement
-// [`atat::AtatCmd`] for  [`PreparePublish`] and handle the customization for payload there.
-#[derive(Clone, AtatCmd)]
-#[at_cmd(
-    "",
-    NoResponse,
-    cmd_prefix = "",
-    termination = "",
-    value_sep = false,
-    timeout = 300
-)]
+/// The payload following a [`PreparePublish`] prompt, copied as is from the caller's buffer.
+///
+/// The modem answers the prompt before accepting the payload, so both can't be a single command.
+#[derive(Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Publish<'a> {
-    /// The actual multi-line message to send.
-    #[at_arg(position = 0, len = 2048)]
-    pub payload: &'a atat::serde_bytes::Bytes,
+    /// The actual multi-line message to send, up to [`MQTT_PAYLOAD_LEN`] bytes.
+    pub payload: &'a [u8],
+}
+
+impl atat::AtatCmd for Publish<'_> {
+    type Response = NoResponse;
+
+    const MAX_LEN: usize = MQTT_PAYLOAD_LEN;
+    const MAX_TIMEOUT_MS: u32 = 300;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Nothing is sent if the payload doesn't fit the command buffer, the modem keeps waiting
+        // for it until the command times out.
+        let Some(dst) = buf.get_mut(..self.payload.len()) else {
+            return 0;
+        };
+        dst.copy_from_slice(self.payload);
+        self.payload.len()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        resp?;
+        Ok(NoResponse)
+    }
 }
 
 /// This command delivers a message selected by its id or the last received message if <qos>=0. The device must have been connected using the Initiate MQTT Connection to a Broker: AT+SQNSMQTTCONNECT (on page 148) command.
@@ -144,14 +160,14 @@ pub struct Publish<'a> {
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+SQNSMQTTRCVMESSAGE", NoResponse, timeout = 300)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Receive {
+pub struct Receive<'a> {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
     pub id: u8,
 
     /// The topic the client wants to publish to.
-    #[at_arg(position = 1)]
-    pub topic: String<256>,
+    #[at_arg(position = 1, len = 256)]
+    pub topic: &'a str,
 
     /// Id of the message to read. <mid> is generated by the broker.
     ///
@@ -176,14 +192,14 @@ pub struct Receive {
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+SQNSMQTTSUBSCRIBE", NoResponse, timeout = 300)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Subscribe {
+pub struct Subscribe<'a> {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
     pub id: u8,
 
     /// The topic the client wants to subscribe to.
-    #[at_arg(position = 1)]
-    pub topic: String<256>,
+    #[at_arg(position = 1, len = 256)]
+    pub topic: &'a str,
 
     /// The quality of service level to request for the subscription.
     #[at_arg(position = 2)]
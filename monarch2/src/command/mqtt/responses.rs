@@ -1,6 +1,8 @@
 use atat::atat_derive::AtatResp;
 
 #[derive(Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct PromptToPayload {
     #[at_arg(position = 0)]
     pub pmid: u16,
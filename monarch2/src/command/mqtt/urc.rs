@@ -1,10 +1,11 @@
+use crate::capacity::MqttTopic;
 use atat::atat_derive::AtatResp;
-use heapless::String;
 
 use super::types::{MQTTStatusCode, Qos};
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Connected {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
@@ -17,6 +18,7 @@ pub struct Connected {
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Disconnected {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
@@ -29,6 +31,7 @@ pub struct Disconnected {
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct PublishResponse {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
@@ -45,6 +48,7 @@ pub struct PublishResponse {
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Received {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
@@ -52,7 +56,7 @@ pub struct Received {
 
     /// The topic the client wants to publish to.
     #[at_arg(position = 1)]
-    pub topic: String<256>,
+    pub topic: MqttTopic,
 
     /// Size of the message payload.
     #[at_arg(position = 2)]
@@ -67,12 +71,13 @@ pub struct Received {
     /// A maximum of 100 messages are saved in the FIFO after +SQNSMQTTONMESSAGE is emitted. If the queue overflows, the URC +SQNSMQTTMEMORYFULL is sent and the oldest messages are lost.
     ///
     /// A message with <qos>=0 doesn't have a <mid›, as this type of message is overwritten every time a new message arrives. No <mid> value is to be given to read a message with <qos>=0.
-    #[at_arg(position = 2)]
+    #[at_arg(position = 4)]
     pub mid: Option<u16>,
 }
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Subscribed {
     /// Client ID. The only supported value is 0 - 1 client.
     #[at_arg(position = 0)]
@@ -80,7 +85,7 @@ pub struct Subscribed {
 
     /// The topic the client wants to publish to.
     #[at_arg(position = 1)]
-    pub topic: String<256>,
+    pub topic: MqttTopic,
 
     /// Subscription return code.
     #[at_arg(position = 2)]
@@ -89,7 +94,38 @@ pub struct Subscribed {
 
 #[derive(Debug, Clone, AtatResp)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct PromptToPublish {
     #[at_arg(position = 0)]
     pub pmid: u8,
 }
+
+/// The message queue of the modem overflowed, the oldest received messages were lost.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct MemoryFull {
+    /// Client ID. The only supported value is 0 - 1 client.
+    #[at_arg(position = 0)]
+    pub id: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_received_parse() {
+        let res: Received =
+            from_str("+SQNSMQTTONMESSAGE: 0,\"devices/42/commands\",64,1,7").unwrap();
+        assert_eq!(res.topic.as_str(), "devices/42/commands");
+        assert_eq!(res.msg_length, 64);
+        assert_eq!(res.qos, Qos::AtLeastOnce);
+        assert_eq!(res.mid, Some(7));
+
+        let res: Received = from_str("+SQNSMQTTONMESSAGE: 0,\"devices/42/commands\",64,0").unwrap();
+        assert_eq!(res.qos, Qos::AtMostOnce);
+        assert_eq!(res.mid, None);
+    }
+}
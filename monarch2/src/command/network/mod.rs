@@ -1,9 +1,10 @@
 use atat::atat_derive::AtatCmd;
-use heapless::String;
+use responses::OperatorSelection;
 use types::{NetworkSelectionMode, OperatorNameFormat};
 
 use super::NoResponse;
 
+pub mod responses;
 pub mod types;
 pub mod urc;
 
@@ -32,7 +33,7 @@ pub mod urc;
 #[derive(Clone, AtatCmd, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+COPS", NoResponse)]
-pub struct PLMNSelection {
+pub struct PLMNSelection<'a> {
     /// Network selection mode.
     #[at_arg(position = 0)]
     pub mode: NetworkSelectionMode,
@@ -48,6 +49,13 @@ pub struct PLMNSelection {
     /// plus a two BCD digit network code, which is administration specific; returned `oper` shall not be in BCD format,
     /// but in IRA characters converted from BCD; hence the number has structure:
     /// `(country code digit 3)(country code digit 2)(country code digit 1)(network code digit 3)(network code digit 2)(network code digit 1)`.
-    #[at_arg(position = 2)]
-    pub oper: Option<String<16>>,
+    #[at_arg(position = 2, len = 16)]
+    pub oper: Option<&'a str>,
 }
+
+/// Reads the current network selection mode and the selected operator, see [`PLMNSelection`].
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetPLMNSelection;
+
+static_cmd!(GetPLMNSelection, "+COPS?", OperatorSelection);
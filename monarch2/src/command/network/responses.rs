@@ -0,0 +1,43 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+use super::types::{NetworkSelectionMode, OperatorNameFormat};
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct OperatorSelection {
+    /// Network selection mode.
+    #[at_arg(position = 0)]
+    pub mode: NetworkSelectionMode,
+
+    /// Format of `oper`, omitted when no operator is selected.
+    #[at_arg(position = 1)]
+    pub format: Option<OperatorNameFormat>,
+
+    /// Selected network operator, omitted when no operator is selected.
+    #[at_arg(position = 2)]
+    pub oper: Option<String<16>>,
+
+    /// Access technology of the selected operator (7: E-UTRAN, 9: E-UTRAN NB-S1).
+    #[at_arg(position = 3)]
+    pub act: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_operator_selection_parse() {
+        let res: OperatorSelection = from_str("+COPS: 0,0,\"Telekom.de\",7").unwrap();
+        assert_eq!(res.mode, NetworkSelectionMode::Automatic);
+        assert_eq!(res.oper.as_deref(), Some("Telekom.de"));
+        assert_eq!(res.act, Some(7));
+
+        let res: OperatorSelection = from_str("+COPS: 2").unwrap();
+        assert_eq!(res.mode, NetworkSelectionMode::Unregister);
+        assert_eq!(res.oper, None);
+    }
+}
@@ -1,7 +1,7 @@
 use atat::atat_derive::AtatEnum;
 
 /// The supported network selection modes.
-#[derive(Clone, PartialEq, AtatEnum, Default)]
+#[derive(Clone, Debug, PartialEq, AtatEnum, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
 pub enum NetworkSelectionMode {
@@ -19,7 +19,7 @@ pub enum NetworkSelectionMode {
 }
 
 /// The supported network operator name formats.
-#[derive(Clone, PartialEq, AtatEnum, Default)]
+#[derive(Clone, Debug, PartialEq, AtatEnum, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
 pub enum OperatorNameFormat {
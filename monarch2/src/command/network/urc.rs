@@ -1,11 +1,139 @@
 use atat::atat_derive::AtatResp;
+use heapless::String;
 
 use super::types::NetworkRegistrationState;
 
 // 7.14 Network registration status +CEREG
-#[derive(Debug, Clone, AtatResp)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+///
+/// The fields after `stat` depend on the [reporting level](crate::system_features::types::CEREGReports)
+/// and are `None` when not reported, e.g. `+CEREG: 1` or
+/// `+CEREG: 5,"2F0A","01A2D001",7,,,"00100001","00000110"`.
+#[derive(Clone, AtatResp)]
+#[cfg_attr(any(test, feature = "verbose-fmt"), derive(Debug))]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct NetworkRegistrationStatus {
     #[at_arg(position = 0)]
     pub stat: NetworkRegistrationState,
+
+    /// Tracking area code, 2 bytes in hexadecimal format.
+    #[at_arg(position = 1)]
+    pub tac: Option<String<4>>,
+
+    /// E-UTRAN cell ID, 4 bytes in hexadecimal format.
+    #[at_arg(position = 2)]
+    pub ci: Option<String<8>>,
+
+    /// Access technology of the serving cell, 7 for E-UTRAN, 9 for E-UTRAN (NB-S1).
+    #[at_arg(position = 3)]
+    pub act: Option<u8>,
+
+    /// Type of the reject cause, 0 for an EMM cause.
+    #[at_arg(position = 4)]
+    pub cause_type: Option<u8>,
+
+    /// Cause of the failed registration.
+    #[at_arg(position = 5)]
+    pub reject_cause: Option<u8>,
+
+    /// PSM active time (T3324) allocated by the network, as a byte in 8 bit format.
+    #[at_arg(position = 6)]
+    pub active_time: Option<String<8>>,
+
+    /// PSM periodic TAU (T3412 extended) allocated by the network, as a byte in 8 bit format.
+    #[at_arg(position = 7)]
+    pub periodic_tau: Option<String<8>>,
+}
+
+compact_fmt!(NetworkRegistrationStatus);
+
+/// 10.1.19 Packet domain event reporting +CGEV, e.g. `+CGEV: ME PDN ACT 1`.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct PacketDomainEvent {
+    /// Event name followed by its first parameter, e.g. `NW DETACH` or `ME PDN DEACT 1`.
+    #[at_arg(position = 0)]
+    pub event: String<32>,
+
+    /// Second parameter of the event, e.g. the reason of `ME PDN ACT` or the change reason of
+    /// `NW MODIFY`.
+    #[at_arg(position = 1)]
+    pub reason: Option<u8>,
+
+    /// Third parameter of the event, e.g. the event type of `NW MODIFY`.
+    #[at_arg(position = 2)]
+    pub event_type: Option<u8>,
+}
+
+/// 7.41 eDRX parameters provided by the network +CEDRXP.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct EdrxParameters {
+    /// Access technology, 4 for E-UTRAN (WB-S1), 5 for E-UTRAN (NB-S1).
+    #[at_arg(position = 0)]
+    pub act_type: u8,
+
+    /// Requested eDRX value, as a half byte in 4 bit format, e.g. `"0101"`.
+    #[at_arg(position = 1)]
+    pub requested: Option<String<4>>,
+
+    /// eDRX value provided by the network, as a half byte in 4 bit format.
+    #[at_arg(position = 2)]
+    pub provided: Option<String<4>>,
+
+    /// Paging time window provided by the network, as a half byte in 4 bit format.
+    #[at_arg(position = 3)]
+    pub paging_time_window: Option<String<4>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_network_registration_status_parse() {
+        let res: NetworkRegistrationStatus = from_str("+CEREG: 2").unwrap();
+        assert_eq!(res.stat, NetworkRegistrationState::Searching);
+        assert_eq!(res.tac, None);
+
+        let res: NetworkRegistrationStatus = from_str("+CEREG: 1,\"2F0A\",\"01A2D001\",7").unwrap();
+        assert_eq!(res.stat, NetworkRegistrationState::RegisteredHome);
+        assert_eq!(res.tac.as_deref(), Some("2F0A"));
+        assert_eq!(res.ci.as_deref(), Some("01A2D001"));
+        assert_eq!(res.act, Some(7));
+
+        let res: NetworkRegistrationStatus =
+            from_str("+CEREG: 3,\"2F0A\",\"01A2D001\",7,0,15").unwrap();
+        assert_eq!(res.stat, NetworkRegistrationState::Denied);
+        assert_eq!(res.reject_cause, Some(15));
+
+        let res: NetworkRegistrationStatus =
+            from_str("+CEREG: 5,\"2F0A\",\"01A2D001\",7,,,\"00100001\",\"00000110\"").unwrap();
+        assert_eq!(res.stat, NetworkRegistrationState::RegisteredRoaming);
+        assert_eq!(res.cause_type, None);
+        assert_eq!(res.active_time.as_deref(), Some("00100001"));
+        assert_eq!(res.periodic_tau.as_deref(), Some("00000110"));
+    }
+
+    #[test]
+    fn test_packet_domain_event_parse() {
+        let res: PacketDomainEvent = from_str("+CGEV: ME PDN ACT 1,0").unwrap();
+        assert_eq!(res.event.as_str(), "ME PDN ACT 1");
+        assert_eq!(res.reason, Some(0));
+
+        let res: PacketDomainEvent = from_str("+CGEV: NW DETACH").unwrap();
+        assert_eq!(res.event.as_str(), "NW DETACH");
+        assert_eq!(res.reason, None);
+    }
+
+    #[test]
+    fn test_edrx_parameters_parse() {
+        let res: EdrxParameters = from_str("+CEDRXP: 4,\"0101\",\"0010\",\"0001\"").unwrap();
+        assert_eq!(res.act_type, 4);
+        assert_eq!(res.provided.as_deref(), Some("0010"));
+        assert_eq!(res.paging_time_window.as_deref(), Some("0001"));
+    }
 }
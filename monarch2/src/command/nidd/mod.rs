@@ -0,0 +1,119 @@
+//! Non-IP Data Delivery (NIDD): the payloads of a Non-IP PDN connection are carried in the NAS
+//! signalling of the control plane, without UDP/IP headers.
+//!
+//! The PDP context must be of the [`NonIP`](crate::pdp::types::PDPType::NonIP) type and the
+//! control plane CIoT optimization enabled with [`SetCiotOptimization`].
+//!
+//! [`SendData`] and [`urc::DataReceived`] also carry the IP packets of an IP context on networks
+//! that only offer the control plane optimization.
+
+use atat::atat_derive::AtatCmd;
+use heapless::String;
+use responses::{CiotOptimizationConfig, DataReportingConfig};
+use types::{
+    CiotOptimization, CiotOptimizationReporting, PreferredCiotOptimization, ReleaseAssistance,
+    UserDataType,
+};
+
+use super::NoResponse;
+use crate::types::Bool;
+
+pub mod responses;
+pub mod types;
+pub mod urc;
+
+/// Largest payload sent with [`SendData`] or received with [`urc::DataReceived`].
+pub const NIDD_PAYLOAD_LEN: usize = 256;
+
+/// Payload of [`SendData`] and [`urc::DataReceived`], hexadecimal encoded.
+pub type NiddHexData = String<{ 2 * NIDD_PAYLOAD_LEN }>;
+
+/// Selects the CIoT EPS optimizations supported and preferred by the device.
+///
+/// Takes effect at the next attach.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CCIOTOPT", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetCiotOptimization {
+    #[at_arg(position = 0)]
+    pub reporting: CiotOptimizationReporting,
+
+    #[at_arg(position = 1)]
+    pub supported: Option<CiotOptimization>,
+
+    #[at_arg(position = 2)]
+    pub preferred: Option<PreferredCiotOptimization>,
+}
+
+/// Reads the CIoT EPS optimizations supported and preferred by the device.
+///
+/// Type: `synchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetCiotOptimization;
+
+static_cmd!(
+    GetCiotOptimization,
+    "+CCIOTOPT?",
+    CiotOptimizationConfig,
+    timeout = 300
+);
+
+/// Sends data over the control plane to the network, e.g. the payload of a Non-IP PDN
+/// connection or an IP packet.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CSODCP", NoResponse, timeout = 5000)]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
+pub struct SendData {
+    /// Context Identifier (CID) of the PDN connection.
+    #[at_arg(position = 0)]
+    pub cid: u8,
+
+    /// Length of the data in bytes.
+    #[at_arg(position = 1)]
+    pub length: usize,
+
+    /// Data, hexadecimal encoded.
+    #[at_arg(position = 2)]
+    pub data: NiddHexData,
+
+    /// Release Assistance Indication, none by default.
+    #[at_arg(position = 3)]
+    pub rai: Option<ReleaseAssistance>,
+
+    /// Regular data by default.
+    #[at_arg(position = 4)]
+    pub user_data_type: Option<UserDataType>,
+}
+
+compact_fmt!(defmt SendData);
+
+/// Enables or disables the reporting of the data received over the control plane, with
+/// `+CRTDCP` URCs, see [`urc::DataReceived`].
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CRTDCP", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigureDataReporting {
+    #[at_arg(position = 0)]
+    pub reporting: Bool,
+}
+
+/// Reads whether the data received over the control plane is reported.
+///
+/// Type: `synchronous`
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetDataReporting;
+
+static_cmd!(
+    GetDataReporting,
+    "+CRTDCP?",
+    DataReportingConfig,
+    timeout = 300
+);
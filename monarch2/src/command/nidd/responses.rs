@@ -0,0 +1,29 @@
+use atat::atat_derive::AtatResp;
+
+use super::types::{CiotOptimization, PreferredCiotOptimization};
+use crate::types::Bool;
+
+/// CIoT EPS optimizations of the device, read with
+/// [`GetCiotOptimization`](super::GetCiotOptimization).
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CiotOptimizationConfig {
+    /// Reporting of the network optimizations, 0 or 1.
+    #[at_arg(position = 0)]
+    pub reporting: u8,
+
+    #[at_arg(position = 1)]
+    pub supported: CiotOptimization,
+
+    #[at_arg(position = 2)]
+    pub preferred: PreferredCiotOptimization,
+}
+
+/// Reporting of the data received over the control plane, read with
+/// [`GetDataReporting`](super::GetDataReporting).
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataReportingConfig {
+    #[at_arg(position = 0)]
+    pub reporting: Bool,
+}
@@ -0,0 +1,58 @@
+use atat::atat_derive::AtatEnum;
+
+/// CIoT EPS optimizations, see 3GPP TS 24.301.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum CiotOptimization {
+    None = 0,
+    /// Data carried in the NAS signalling, required by NIDD.
+    ControlPlane = 1,
+    /// Suspension and resumption of the connection, the data stays on the user plane.
+    UserPlane = 2,
+    Both = 3,
+}
+
+/// CIoT EPS optimization preferred by the device.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum PreferredCiotOptimization {
+    NoPreference = 0,
+    ControlPlane = 1,
+    UserPlane = 2,
+}
+
+/// Reporting of the CIoT EPS optimizations supported by the network with `+CCIOTOPTI` URCs.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum CiotOptimizationReporting {
+    Disabled = 0,
+    Enabled = 1,
+    /// Disables the reporting and restores the default optimizations of the device.
+    Reset = 3,
+}
+
+/// Release Assistance Indication (RAI) sent with the data, tells the network whether the
+/// connection can be released afterwards, see 3GPP TS 24.301.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum ReleaseAssistance {
+    NoInformation = 0,
+    /// No further uplink or downlink data is expected, the connection can be released.
+    NoFurtherData = 1,
+    /// A single downlink transmission, e.g. an acknowledgement, is expected after the data.
+    SingleDownlink = 2,
+}
+
+/// Type of the data sent over the control plane.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum UserDataType {
+    Regular = 0,
+    /// Exception data, sent even when the device is barred or out of its allowed rate.
+    Exception = 1,
+}
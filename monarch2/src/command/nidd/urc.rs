@@ -0,0 +1,56 @@
+use atat::atat_derive::AtatResp;
+use heapless::Vec;
+
+use super::{NIDD_PAYLOAD_LEN, NiddHexData};
+use crate::types::hex_decode;
+
+/// Data received over the control plane, reported once enabled with
+/// [`ConfigureDataReporting`](super::ConfigureDataReporting).
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct DataReceived {
+    /// Context Identifier (CID) of the PDN connection.
+    #[at_arg(position = 0)]
+    pub cid: u8,
+
+    /// Length of the data in bytes.
+    #[at_arg(position = 1)]
+    pub length: u16,
+
+    /// Data, hexadecimal encoded.
+    #[at_arg(position = 2)]
+    pub data: NiddHexData,
+}
+
+impl DataReceived {
+    /// Returns the decoded data, `None` if it isn't valid hexadecimal.
+    pub fn payload(&self) -> Option<Vec<u8, NIDD_PAYLOAD_LEN>> {
+        let mut payload = Vec::new();
+        // Can't fail, the capacity holds the decoded `NiddHexData`.
+        payload.resize_default(NIDD_PAYLOAD_LEN).ok()?;
+        let len = hex_decode(&self.data, &mut payload)?;
+        payload.truncate(len);
+        Some(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_data_received_parse() {
+        let res: DataReceived = from_str("+CRTDCP: 1,3,\"0A0B0C\"").unwrap();
+        assert_eq!(res.cid, 1);
+        assert_eq!(res.length, 3);
+        assert_eq!(res.payload().as_deref(), Some(&[0x0A, 0x0B, 0x0C][..]));
+    }
+
+    #[test]
+    fn test_data_received_invalid_hex() {
+        let res: DataReceived = from_str("+CRTDCP: 1,1,\"0G\"").unwrap();
+        assert_eq!(res.payload(), None);
+    }
+}
@@ -1,4 +1,4 @@
-use atat::atat_derive::AtatCmd;
+use atat::{InternalError, atat_derive::AtatCmd};
 
 pub mod types;
 
@@ -6,6 +6,18 @@ use crate::nvm::types::DataType;
 
 use super::NoResponse;
 
+/// Maximum size of the data written with [`Write`], the size of the largest certificates.
+pub const NVM_WRITE_LEN: usize = 8192;
+
+/// Size of the slices of data sent to a prompt with [`WriteChunk`] and [`WriteLastChunk`], the
+/// larger writes are split in slices of this size.
+pub const NVM_CHUNK_LEN: usize = 1024;
+
+/// Whether the index is reserved for Sequans's internal use: 0 to 4 and 7 to 10.
+pub(crate) fn is_reserved_index(index: u8) -> bool {
+    (0..=4).contains(&index) || (7..=10).contains(&index)
+}
+
 /// This command writes / deletes data (certificates, etc.) to / from the non-volatile (NV) memory. Data stored in nonvolatile memory is not affected by device reboots and software upgrades.
 ///
 /// Attention: A factory reset (see Device Reset to Factory State: AT+SQNSFACTORYRESET (on page 484)) deletes all data written in the NV memory.
@@ -33,6 +45,7 @@ use super::NoResponse;
 ///
 /// Note: The MQTT broker can provide certificates and private keys files with < CR> < LF> (Carriage Return and Line Feed) endings. The parameter ‹size>, however, must not take the < CR› characters into account.
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+SQNSNVW", NoResponse)]
 pub struct PrepareWrite {
     #[at_arg(position = 0)]
@@ -57,3 +70,63 @@ pub struct Write<'a> {
     #[at_arg(position = 0, len = 8192)]
     pub data: &'a atat::serde_bytes::Bytes,
 }
+
+/// A part of the data following a [`PrepareWrite`] prompt, all but the last one which is sent
+/// with [`WriteLastChunk`]: the modem only answers once it received the announced size.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WriteChunk<'a> {
+    pub data: &'a [u8],
+}
+
+impl atat::AtatCmd for WriteChunk<'_> {
+    type Response = NoResponse;
+
+    const MAX_LEN: usize = NVM_CHUNK_LEN;
+    const EXPECTS_RESPONSE_CODE: bool = false;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        write_data(self.data, buf)
+    }
+
+    fn parse(&self, _resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        Ok(NoResponse)
+    }
+}
+
+/// The last part of the data following a [`PrepareWrite`] prompt, answered once the modem stored
+/// the data.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WriteLastChunk<'a> {
+    pub data: &'a [u8],
+}
+
+impl atat::AtatCmd for WriteLastChunk<'_> {
+    type Response = NoResponse;
+
+    const MAX_LEN: usize = NVM_CHUNK_LEN;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        write_data(self.data, buf)
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        resp?;
+        Ok(NoResponse)
+    }
+}
+
+/// Copies `data` as is in the command buffer.
+fn write_data(data: &[u8], buf: &mut [u8]) -> usize {
+    // Like `mqtt::Publish`, data larger than the command buffer isn't sent.
+    let Some(dst) = buf.get_mut(..data.len()) else {
+        return 0;
+    };
+    dst.copy_from_slice(data);
+    data.len()
+}
+
+// The `len` of `Write::data` must follow `NVM_WRITE_LEN`, or the largest certificates overflow the
+// serialization.
+const _: () = assert!(<Write<'static> as atat::AtatCmd>::MAX_LEN >= NVM_WRITE_LEN);
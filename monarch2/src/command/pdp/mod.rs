@@ -1,10 +1,15 @@
 use atat::atat_derive::AtatCmd;
 use heapless::String;
-use types::{PDPDComp, PDPHComp, PDPIPv4Alloc, PDPPCSCF, PDPRequestType, PDPType};
+use responses::PDPAddress;
+use types::{PDPAuthProtocol, PDPDComp, PDPHComp, PDPIPv4Alloc, PDPPCSCF, PDPRequestType, PDPType};
 
+pub mod responses;
 pub mod types;
 
-use crate::types::Bool;
+use crate::{
+    capacity::{Apn, PdpCredential},
+    types::Bool,
+};
 
 use super::NoResponse;
 
@@ -16,6 +21,7 @@ use super::NoResponse;
 ///
 /// Reboot persistent, module must not be attached (+CEREG != 1 or 5).
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
 #[at_cmd("+CGDCONT", NoResponse)]
 pub struct DefinePDPContext {
     /// Context Identifier (CID): integer between 1–16.
@@ -28,7 +34,7 @@ pub struct DefinePDPContext {
 
     /// Cellular APN for SIM card. Leave empty to autodetect APN.
     #[at_arg(position = 2)]
-    pub apn: String<64>,
+    pub apn: Apn,
 
     /// Optional PDP address. Usually left empty for dynamic assignment.
     #[at_arg(position = 3)]
@@ -89,3 +95,39 @@ pub struct DefinePDPContext {
     #[at_arg(position = 14)]
     pub non_ip_mtu_discovery: Bool,
 }
+
+compact_fmt!(defmt DefinePDPContext);
+
+/// Sets the authentication of a PDP context, for the APNs requiring a username and password.
+///
+/// Reboot persistent, like the context definition it must be set before the module attaches.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CGAUTH", NoResponse)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetPDPAuthentication {
+    /// Context Identifier (CID): integer between 1–16.
+    #[at_arg(position = 0)]
+    pub cid: u8,
+
+    /// Authentication protocol, [`PDPAuthProtocol::None`] removes the credentials.
+    #[at_arg(position = 1)]
+    pub auth_prot: PDPAuthProtocol,
+
+    #[at_arg(position = 2)]
+    pub username: Option<PdpCredential>,
+
+    #[at_arg(position = 3)]
+    pub password: Option<PdpCredential>,
+}
+
+/// Reads the IP addresses assigned to a PDP context.
+///
+/// The addresses are omitted while the context is not activated.
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+CGPADDR", PDPAddress)]
+pub struct GetPDPAddress {
+    /// Context Identifier (CID): integer between 1–16.
+    #[at_arg(position = 0)]
+    pub cid: u8,
+}
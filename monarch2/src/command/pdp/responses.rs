@@ -0,0 +1,33 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct PDPAddress {
+    /// Context Identifier (CID).
+    #[at_arg(position = 0)]
+    pub cid: u8,
+
+    /// IPv4 address, or the IPv6 address of an IPv6 only context.
+    #[at_arg(position = 1)]
+    pub pdp_addr_1: Option<String<64>>,
+
+    /// IPv6 address of a dual stack context.
+    #[at_arg(position = 2)]
+    pub pdp_addr_2: Option<String<64>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_pdp_address_parse() {
+        let res: PDPAddress = from_str("+CGPADDR: 1,\"10.160.42.7\"").unwrap();
+        assert_eq!(res.cid, 1);
+        assert_eq!(res.pdp_addr_1.as_deref(), Some("10.160.42.7"));
+        assert_eq!(res.pdp_addr_2, None);
+    }
+}
@@ -65,8 +65,18 @@ pub enum PDPPCSCF {
     NAS = 1,
 }
 
+/// Authentication protocol of a PDP context.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[at_enum(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PDPAuthProtocol {
+    None = 0,
+    PAP = 1,
+    CHAP = 2,
+}
+
 /// The supported packet data protocol types.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PDPType {
     IP,
@@ -0,0 +1,60 @@
+use atat::{
+    InternalError,
+    serde_at::{SerializeOptions, to_slice},
+};
+use responses::PingReplies;
+use serde::Serialize;
+
+use crate::dns::HOSTNAME_LEN;
+
+pub mod responses;
+
+/// Sends ICMP echo requests to a host, the PDN must be active.
+///
+/// The modem answers once every request was answered or timed out, with one line per request, see
+/// [`PingReplies`]. The default timeout only fits a single request, send it with
+/// [`Modem::send_with_timeout`](crate::Modem::send_with_timeout) otherwise.
+///
+/// Type: `synchronous`
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ping<'a> {
+    /// IP address or host name to ping, up to [`HOSTNAME_LEN`] characters.
+    pub host: &'a str,
+
+    /// Number of echo requests, 1 to [`MAX_PING_COUNT`](responses::MAX_PING_COUNT).
+    pub count: Option<u8>,
+
+    /// Size of the data of the requests, in bytes.
+    pub size: Option<u16>,
+
+    /// Time between two requests, in seconds.
+    pub interval: Option<u8>,
+
+    /// Time waited for each reply, in seconds.
+    pub timeout: Option<u8>,
+}
+
+impl atat::AtatCmd for Ping<'_> {
+    type Response = PingReplies;
+
+    const MAX_LEN: usize = "AT+PING=".len()
+        + HOSTNAME_LEN + 2 // quoted host
+        + 3 // count
+        + 5 // size
+        + 3 // interval
+        + 3 // timeout
+        + 4 // separators
+        + 2; // termination
+
+    const MAX_TIMEOUT_MS: u32 = 15_000;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Can't fail as long as the host fits `HOSTNAME_LEN`, like the derived commands.
+        to_slice(self, "+PING", buf, SerializeOptions::default()).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        Ok(PingReplies::parse(resp?))
+    }
+}
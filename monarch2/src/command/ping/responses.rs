@@ -0,0 +1,112 @@
+use atat::{AtatResp, serde_at::from_slice};
+use embassy_time::Duration;
+use heapless::{String, Vec};
+
+/// Most echo requests sent by a [`Ping`](super::Ping).
+pub const MAX_PING_COUNT: usize = 16;
+
+/// Result of one echo request of a [`Ping`](super::Ping).
+#[derive(Clone, Debug, PartialEq, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct PingReply {
+    /// Number of the request, from 1.
+    #[at_arg(position = 0)]
+    pub reply_id: u8,
+
+    /// Address of the host that replied.
+    #[at_arg(position = 1)]
+    pub address: String<39>,
+
+    /// Round trip time in milliseconds, -1 if the request timed out.
+    #[at_arg(position = 2)]
+    pub rtt: i32,
+
+    /// Time to live of the reply, -1 if the request timed out.
+    #[at_arg(position = 3)]
+    pub ttl: i16,
+}
+
+impl PingReply {
+    /// Round trip time of the request, `None` if it timed out.
+    pub fn round_trip_time(&self) -> Option<Duration> {
+        u64::try_from(self.rtt).ok().map(Duration::from_millis)
+    }
+}
+
+/// Response to [`Ping`](super::Ping), one reply per echo request.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct PingReplies {
+    pub replies: Vec<PingReply, MAX_PING_COUNT>,
+}
+
+impl AtatResp for PingReplies {}
+
+impl PingReplies {
+    /// Parses the `+PING: <reply_id>,<address>,<rtt>,<ttl>` lines of the response.
+    ///
+    /// The lines that can't be parsed are skipped, the ones beyond [`MAX_PING_COUNT`] dropped.
+    pub fn parse(resp: &[u8]) -> Self {
+        let mut replies = Vec::new();
+        for line in resp.split(|&b| b == b'\n') {
+            let Ok(reply) = from_slice::<PingReply>(line.trim_ascii()) else {
+                continue;
+            };
+            if replies.push(reply).is_err() {
+                break;
+            }
+        }
+        Self { replies }
+    }
+
+    /// Number of requests answered.
+    pub fn received(&self) -> usize {
+        self.replies.iter().filter(|reply| reply.rtt >= 0).count()
+    }
+
+    /// Average round trip time of the answered requests, `None` if none was.
+    pub fn average_round_trip_time(&self) -> Option<Duration> {
+        let (total, count) = self
+            .replies
+            .iter()
+            .filter_map(PingReply::round_trip_time)
+            .fold((Duration::from_ticks(0), 0), |(total, count), rtt| {
+                (total + rtt, count + 1)
+            });
+        (count > 0).then(|| total / count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_replies_parse() {
+        let res = PingReplies::parse(
+            b"+PING: 1,\"8.8.8.8\",62,117\r\n+PING: 2,\"8.8.8.8\",-1,-1\r\n+PING: 3,\"8.8.8.8\",58,117",
+        );
+        assert_eq!(res.replies.len(), 3);
+        assert_eq!(res.replies[0].address, "8.8.8.8");
+        assert_eq!(
+            res.replies[0].round_trip_time(),
+            Some(Duration::from_millis(62))
+        );
+        assert_eq!(res.replies[1].round_trip_time(), None);
+        assert_eq!(res.received(), 2);
+        assert_eq!(
+            res.average_round_trip_time(),
+            Some(Duration::from_millis(60))
+        );
+    }
+
+    #[test]
+    fn test_ping_replies_parse_invalid() {
+        let res = PingReplies::parse(b"+PING: garbage\r\n\r\n+PING: 1,\"8.8.8.8\",-1,-1");
+        assert_eq!(res.replies.len(), 1);
+        assert_eq!(res.received(), 0);
+        assert_eq!(res.average_round_trip_time(), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Passthrough for AT commands and URCs not modelled by this crate.
+//!
+//! [`RawCommand`] sends a command given as a string, see [`Modem::send_raw`](crate::Modem::send_raw).
+//! [`RawUrcTap`] wraps the [`Digester`] of the `atat` ingress and publishes a copy of every URC
+//! line, including the ones the [`Urc`](super::Urc) enum doesn't know about.
+
+use atat::{
+    AtatCmd, AtatResp, InternalError,
+    digest::{DigestResult, Digester},
+};
+use embassy_sync::{blocking_mutex::raw::RawMutex, pubsub::PubSubChannel};
+use heapless::String;
+
+/// Maximum length of a raw command, including the termination.
+pub const MAX_RAW_CMD_LEN: usize = 256;
+
+/// Maximum length of a URC line published by the [`RawUrcTap`], longer lines are dropped.
+pub const MAX_RAW_URC_LEN: usize = 128;
+
+/// URC line as received from the modem.
+pub type RawUrc = String<MAX_RAW_URC_LEN>;
+
+/// AT command given as a string, e.g. `AT+CGSN=1`.
+///
+/// The command is sent as is, terminated with `\r\n`. The timeout is not limited by the command,
+/// it has to be enforced by the caller.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawCommand<'a, const N: usize> {
+    pub cmd: &'a str,
+}
+
+/// Response to a [`RawCommand`], the information text lines preceding the final result code.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawResponse<const N: usize>(pub String<N>);
+
+impl<const N: usize> AtatResp for RawResponse<N> {}
+
+impl<const N: usize> AtatCmd for RawCommand<'_, N> {
+    type Response = RawResponse<N>;
+
+    const MAX_LEN: usize = MAX_RAW_CMD_LEN;
+    const MAX_TIMEOUT_MS: u32 = u32::MAX;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        let cmd = self.cmd.as_bytes();
+        let len = cmd.len() + 2;
+        buf[..cmd.len()].copy_from_slice(cmd);
+        buf[cmd.len()..len].copy_from_slice(b"\r\n");
+        len
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        let resp = resp?;
+        let resp = core::str::from_utf8(resp).map_err(|_| atat::Error::Parse)?;
+        String::try_from(resp)
+            .map(RawResponse)
+            .map_err(|_| atat::Error::Parse)
+    }
+}
+
+/// [`Digester`] wrapper publishing every URC line to a [`PubSubChannel`].
+///
+/// ```ignore
+/// static RAW_URCS: PubSubChannel<CriticalSectionRawMutex, RawUrc, 4, 1, 0> = PubSubChannel::new();
+///
+/// let digester = RawUrcTap::new(AtDigester::<Urc>::new(), &RAW_URCS);
+/// let ingress = Ingress::new(digester, &mut res_buf, &RES_SLOT, &URC_CHANNEL);
+/// ```
+///
+/// Messages are published without waiting, the oldest ones are dropped when a subscriber lags.
+pub struct RawUrcTap<'a, D, M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> {
+    digester: D,
+    channel: &'a PubSubChannel<M, RawUrc, CAP, SUBS, PUBS>,
+}
+
+impl<'a, D, M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize>
+    RawUrcTap<'a, D, M, CAP, SUBS, PUBS>
+{
+    pub fn new(digester: D, channel: &'a PubSubChannel<M, RawUrc, CAP, SUBS, PUBS>) -> Self {
+        Self { digester, channel }
+    }
+}
+
+impl<D, M, const CAP: usize, const SUBS: usize, const PUBS: usize> Digester
+    for RawUrcTap<'_, D, M, CAP, SUBS, PUBS>
+where
+    D: Digester,
+    M: RawMutex,
+{
+    fn digest<'a>(&mut self, buf: &'a [u8]) -> (DigestResult<'a>, usize) {
+        let (result, len) = self.digester.digest(buf);
+        if let DigestResult::Urc(line) = result {
+            match core::str::from_utf8(line).map(RawUrc::try_from) {
+                Ok(Ok(urc)) => self.channel.immediate_publisher().publish_immediate(urc),
+                _ => warn!("Raw URC dropped, not UTF-8 or too long"),
+            }
+        }
+        (result, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_command_write() {
+        let mut buf = [0u8; MAX_RAW_CMD_LEN];
+        let len = RawCommand::<32> { cmd: "AT+CGSN=1" }.write(&mut buf);
+        assert_eq!(&buf[..len], b"AT+CGSN=1\r\n");
+    }
+
+    #[test]
+    fn test_raw_command_parse() {
+        let cmd = RawCommand::<32> { cmd: "AT+CGSN=1" };
+        assert_eq!(
+            cmd.parse(Ok(b"+CGSN: \"351457830026594\"".as_slice()))
+                .unwrap(),
+            RawResponse(String::try_from("+CGSN: \"351457830026594\"").unwrap())
+        );
+
+        let cmd = RawCommand::<8> { cmd: "AT+CGSN=1" };
+        assert_eq!(
+            cmd.parse(Ok(b"+CGSN: \"351457830026594\"".as_slice())),
+            Err(atat::Error::Parse)
+        );
+    }
+}
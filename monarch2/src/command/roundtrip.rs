@@ -0,0 +1,121 @@
+//! Property tests of the types with hand-written serde implementations: any value written as a
+//! command argument must be parsed back unchanged, without shifting the arguments around it.
+
+use std::format;
+
+use atat::serde_at::{SerializeOptions, from_slice, ser::to_slice};
+use heapless::String;
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+
+use super::*;
+use crate::{
+    network::urc::NetworkRegistrationStatus,
+    pdp::types::PDPType,
+    types::{Bool, Nullable},
+};
+
+/// `value` surrounded by other arguments.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Args<T> {
+    before: u32,
+    value: T,
+    after: u8,
+}
+
+/// Writes `args` as the arguments of a command, then parses them back.
+fn roundtrip<T: Serialize + DeserializeOwned>(args: &Args<T>) -> Args<T> {
+    let mut buf = [0; 256];
+    let len = to_slice(args, "+CMD", &mut buf, SerializeOptions::default()).unwrap();
+    let line = &buf[..len];
+    let line = line
+        .strip_prefix(b"AT+CMD=")
+        .and_then(|line| line.strip_suffix(b"\r\n"))
+        .unwrap();
+    from_slice(line).unwrap()
+}
+
+fn pdp_type() -> impl Strategy<Value = PDPType> {
+    prop_oneof![
+        Just(PDPType::IP),
+        Just(PDPType::IPv4V6),
+        Just(PDPType::IPv6),
+        Just(PDPType::NonIP),
+        Just(PDPType::OSPIH),
+        Just(PDPType::PPP),
+        Just(PDPType::X25),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_nullable_number(
+        before in any::<u32>(),
+        value in any::<Option<u16>>(),
+        after in any::<u8>(),
+    ) {
+        let args = Args { before, value: Nullable::from(value), after };
+        prop_assert_eq!(roundtrip(&args), args);
+    }
+
+    #[test]
+    fn test_nullable_string(
+        before in any::<u32>(),
+        value in proptest::option::of("[a-zA-Z0-9.-]{1,12}"),
+        after in any::<u8>(),
+    ) {
+        let value: Option<String<12>> = value.map(|s| String::try_from(s.as_str()).unwrap());
+        let args = Args { before, value: Nullable::from(value), after };
+        prop_assert_eq!(roundtrip(&args), args);
+    }
+
+    #[test]
+    fn test_nullable_bool(
+        before in any::<u32>(),
+        value in any::<Option<bool>>(),
+        after in any::<u8>(),
+    ) {
+        let args = Args { before, value: Nullable::from(value.map(Bool::from)), after };
+        prop_assert_eq!(roundtrip(&args), args);
+    }
+
+    #[test]
+    fn test_reserved(before in any::<u32>(), after in any::<u8>()) {
+        let args = Args { before, value: Reserved, after };
+        let parsed = roundtrip(&args);
+        prop_assert_eq!((parsed.before, parsed.after), (before, after));
+    }
+
+    #[test]
+    fn test_pdp_type(before in any::<u32>(), value in pdp_type(), after in any::<u8>()) {
+        let args = Args { before, value, after };
+        prop_assert_eq!(roundtrip(&args), args);
+    }
+
+    /// The PSM timers are reported in the 8 bit format of 3GPP TS 24.008 and kept as is.
+    #[test]
+    fn test_psm_timers(active_time in "[01]{8}", periodic_tau in "[01]{8}") {
+        let line = format!(
+            "+CEREG: 1,\"2F0A\",\"01A2D001\",7,,,\"{active_time}\",\"{periodic_tau}\""
+        );
+        let res: NetworkRegistrationStatus = atat::serde_at::from_str(&line).unwrap();
+        prop_assert_eq!(res.active_time.as_deref(), Some(active_time.as_str()));
+        prop_assert_eq!(res.periodic_tau.as_deref(), Some(periodic_tau.as_str()));
+    }
+}
+
+#[cfg(feature = "gnss")]
+proptest! {
+    /// The coordinates and speeds of the GNSS fixes, with the 6 decimals reported by the modem.
+    #[test]
+    fn test_quoted_f32(
+        before in any::<u32>(),
+        micros in -180_000_000i32..=180_000_000,
+        after in any::<u8>(),
+    ) {
+        use crate::gnss::types::QuotedF32;
+
+        let args = Args { before, value: QuotedF32(micros as f32 / 1e6), after };
+        prop_assert_eq!(roundtrip(&args), args);
+    }
+}
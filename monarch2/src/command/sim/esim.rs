@@ -0,0 +1,383 @@
+//! eUICC (eSIM) profile management.
+//!
+//! The profiles of a soldered eSIM are managed by the ISD-R application of the eUICC using the
+//! ES10c functions defined by GSMA SGP.22. The functions are transported as STORE DATA APDUs
+//! over a logical channel opened with [`OpenLogicalChannel`](super::OpenLogicalChannel) and
+//! exchanged with [`LogicalChannelAccess`](super::LogicalChannelAccess).
+//!
+//! This module builds the hex encoded APDUs and parses the responses.
+
+use heapless::{String, Vec};
+
+use super::responses::SIM_RESPONSE_LEN;
+use crate::types::{hex_decode, hex_encode};
+
+/// AID of the ISD-R application on the eUICC.
+pub const ISD_R_AID: &str = "A0000005591010FFFFFFFF8900000100";
+
+/// Maximum number of profiles returned by [`parse_profiles`].
+pub const MAX_PROFILES: usize = 8;
+
+/// Length of a profile in the GetProfilesInfo response, with only the requested tags: the ICCID,
+/// the state and the class.
+const PROFILE_INFO_LEN: usize = 2 // profile info tag and length
+    + 2 + 10 // ICCID
+    + 3 + 1 // state
+    + 2 + 1; // class
+
+/// Length of the GetProfilesInfo response listing [`MAX_PROFILES`] profiles.
+const PROFILES_RESPONSE_LEN: usize = 4 // list tag and length
+    + 3 // list ok tag and length
+    + MAX_PROFILES * PROFILE_INFO_LEN
+    + 2; // status word
+
+// The hexadecimal response must fit the SIM access responses.
+const _: () = assert!(2 * PROFILES_RESPONSE_LEN <= SIM_RESPONSE_LEN);
+
+/// Hex encoded command APDU.
+pub type Apdu = String<64>;
+
+/// Summary of a profile installed on the eUICC.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ProfileInfo {
+    /// ICCID of the profile.
+    pub iccid: String<20>,
+    /// Whether the profile is currently enabled.
+    pub enabled: bool,
+    /// Class of the profile, if reported by the eUICC.
+    pub class: Option<ProfileClass>,
+}
+
+/// Class of a profile, see [`ProfileInfo`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub enum ProfileClass {
+    Test,
+    Provisioning,
+    Operational,
+}
+
+impl ProfileClass {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Test),
+            1 => Some(Self::Provisioning),
+            2 => Some(Self::Operational),
+            _ => None,
+        }
+    }
+}
+
+/// Result of the enable/disable profile functions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ProfileOperationResult {
+    Ok,
+    IccidOrAidNotFound,
+    /// The profile is already enabled (when enabling) or disabled (when disabling).
+    ProfileNotInExpectedState,
+    DisallowedByPolicy,
+    WrongProfileReenabling,
+    CatBusy,
+    Undefined(u8),
+}
+
+impl From<u8> for ProfileOperationResult {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Ok,
+            1 => Self::IccidOrAidNotFound,
+            2 => Self::ProfileNotInExpectedState,
+            3 => Self::DisallowedByPolicy,
+            4 => Self::WrongProfileReenabling,
+            5 => Self::CatBusy,
+            other => Self::Undefined(other),
+        }
+    }
+}
+
+const TAG_PROFILE_INFO_LIST: u16 = 0xBF2D;
+const TAG_ENABLE_PROFILE: u16 = 0xBF31;
+const TAG_DISABLE_PROFILE: u16 = 0xBF32;
+const TAG_LIST_OK: u16 = 0xA0;
+const TAG_PROFILE_INFO: u16 = 0xE3;
+const TAG_ICCID: u16 = 0x5A;
+const TAG_PROFILE_STATE: u16 = 0x9F70;
+const TAG_PROFILE_CLASS: u16 = 0x91;
+const TAG_RESULT: u16 = 0x80;
+
+/// Builds the GetProfilesInfo APDU listing all the installed profiles.
+///
+/// Only the ICCID, state and class of the profiles are requested, the names and icons would
+/// overflow the response.
+pub fn get_profiles_info(session_id: u32) -> Option<Apdu> {
+    // searchCriteria omitted, tagList [APPLICATION 28] { iccid, profileState, profileClass }
+    store_data(
+        session_id,
+        &[0xBF, 0x2D, 0x06, 0x5C, 0x04, 0x5A, 0x9F, 0x70, 0x91],
+    )
+}
+
+/// Builds the EnableProfile APDU for the profile identified by `iccid`.
+pub fn enable_profile(session_id: u32, iccid: &str) -> Option<Apdu> {
+    profile_request(session_id, TAG_ENABLE_PROFILE, iccid)
+}
+
+/// Builds the DisableProfile APDU for the profile identified by `iccid`.
+pub fn disable_profile(session_id: u32, iccid: &str) -> Option<Apdu> {
+    profile_request(session_id, TAG_DISABLE_PROFILE, iccid)
+}
+
+/// Parses the response to the GetProfilesInfo APDU.
+///
+/// Returns `None` if the response is malformed or reports an error status word.
+pub fn parse_profiles(response: &str) -> Option<Vec<ProfileInfo, MAX_PROFILES>> {
+    let mut buf = [0u8; SIM_RESPONSE_LEN / 2];
+    let data = response_data(response, &mut buf)?;
+
+    let (tag, content, _) = read_tlv(data)?;
+    if tag != TAG_PROFILE_INFO_LIST {
+        return None;
+    }
+
+    let (tag, mut list, _) = read_tlv(content)?;
+    if tag != TAG_LIST_OK {
+        return None;
+    }
+
+    let mut profiles = Vec::new();
+    while !list.is_empty() {
+        let (tag, mut fields, rest) = read_tlv(list)?;
+        list = rest;
+        if tag != TAG_PROFILE_INFO {
+            continue;
+        }
+
+        let mut profile = ProfileInfo {
+            iccid: String::new(),
+            enabled: false,
+            class: None,
+        };
+        while !fields.is_empty() {
+            let (tag, value, rest) = read_tlv(fields)?;
+            fields = rest;
+            match tag {
+                TAG_ICCID => profile.iccid = decode_iccid(value)?,
+                TAG_PROFILE_STATE => profile.enabled = value.first() == Some(&1),
+                TAG_PROFILE_CLASS => {
+                    profile.class = value.first().copied().and_then(ProfileClass::from_code)
+                }
+                _ => {}
+            }
+        }
+
+        profiles.push(profile).ok()?;
+    }
+
+    Some(profiles)
+}
+
+/// Parses the response to the EnableProfile/DisableProfile APDUs.
+pub fn parse_operation_result(response: &str) -> Option<ProfileOperationResult> {
+    let mut buf = [0u8; 16];
+    let data = response_data(response, &mut buf)?;
+
+    let (tag, content, _) = read_tlv(data)?;
+    if tag != TAG_ENABLE_PROFILE && tag != TAG_DISABLE_PROFILE {
+        return None;
+    }
+
+    let (tag, value, _) = read_tlv(content)?;
+    if tag != TAG_RESULT {
+        return None;
+    }
+
+    value.first().map(|&code| code.into())
+}
+
+fn profile_request(session_id: u32, tag: u16, iccid: &str) -> Option<Apdu> {
+    let iccid = encode_iccid(iccid)?;
+
+    let mut data = [0u8; 20];
+    data[..2].copy_from_slice(&tag.to_be_bytes());
+    // profileIdentifier [0] { iccid }, refreshFlag TRUE
+    data[2..7].copy_from_slice(&[17, 0xA0, 12, TAG_ICCID as u8, 10]);
+    data[7..17].copy_from_slice(&iccid);
+    data[17..].copy_from_slice(&[0x81, 0x01, 0xFF]);
+
+    store_data(session_id, &data)
+}
+
+/// Wraps `data` into a STORE DATA command APDU for the given logical channel.
+fn store_data(session_id: u32, data: &[u8]) -> Option<Apdu> {
+    let cla = match session_id {
+        1..=3 => 0x80 | session_id as u8,
+        4..=19 => 0xC0 | (session_id - 4) as u8,
+        _ => return None,
+    };
+
+    let mut apdu = Apdu::new();
+    hex_encode(&[cla, 0xE2, 0x91, 0x00, data.len() as u8], &mut apdu).ok()?;
+    hex_encode(data, &mut apdu).ok()?;
+    hex_encode(&[0x00], &mut apdu).ok()?;
+    Some(apdu)
+}
+
+/// Decodes the response APDU and strips the status word, which must report success.
+fn response_data<'a>(response: &str, buf: &'a mut [u8]) -> Option<&'a [u8]> {
+    let len = hex_decode(response, buf)?;
+    let (data, sw) = buf[..len].split_at_checked(len.checked_sub(2)?)?;
+    (sw == &[0x90, 0x00]).then_some(data)
+}
+
+/// Reads one BER-TLV object, returning its tag, value and the bytes following it.
+fn read_tlv(data: &[u8]) -> Option<(u16, &[u8], &[u8])> {
+    let (&first, mut rest) = data.split_first()?;
+    let mut tag = first as u16;
+    if first & 0x1F == 0x1F {
+        let (&second, r) = rest.split_first()?;
+        tag = (tag << 8) | second as u16;
+        rest = r;
+    }
+
+    let (&len, r) = rest.split_first()?;
+    rest = r;
+    let len = match len {
+        0..=0x7F => len as usize,
+        0x81 => {
+            let (&len, r) = rest.split_first()?;
+            rest = r;
+            len as usize
+        }
+        0x82 => {
+            let (len, r) = rest.split_first_chunk::<2>()?;
+            rest = r;
+            u16::from_be_bytes(*len) as usize
+        }
+        _ => return None,
+    };
+
+    let (value, rest) = rest.split_at_checked(len)?;
+    Some((tag, value, rest))
+}
+
+/// Encodes an ICCID into its 10 byte, nibble swapped BCD representation.
+fn encode_iccid(iccid: &str) -> Option<[u8; 10]> {
+    let digits = iccid.as_bytes();
+    if !(18..=20).contains(&digits.len()) {
+        return None;
+    }
+
+    let mut out = [0xFFu8; 10];
+    for (i, digit) in digits.iter().enumerate() {
+        let nibble = (*digit as char).to_digit(10)? as u8;
+        let byte = &mut out[i / 2];
+        *byte = if i % 2 == 0 {
+            (*byte & 0xF0) | nibble
+        } else {
+            (*byte & 0x0F) | (nibble << 4)
+        };
+    }
+
+    Some(out)
+}
+
+fn decode_iccid(bcd: &[u8]) -> Option<String<20>> {
+    let mut iccid = String::new();
+    for byte in bcd {
+        for nibble in [byte & 0x0F, byte >> 4] {
+            if nibble < 10 {
+                iccid.push((b'0' + nibble) as char).ok()?;
+            }
+        }
+    }
+    Some(iccid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_profiles_info_apdu() {
+        assert_eq!(
+            get_profiles_info(1).unwrap(),
+            "81E2910009BF2D065C045A9F709100"
+        );
+        assert_eq!(
+            get_profiles_info(5).unwrap(),
+            "C1E2910009BF2D065C045A9F709100"
+        );
+        assert_eq!(get_profiles_info(0), None);
+    }
+
+    #[test]
+    fn test_enable_profile_apdu() {
+        let apdu = enable_profile(1, "8944500601200000015").unwrap();
+        assert_eq!(apdu, "81E2910014BF3111A00C5A0A984405601002000010F58101FF00");
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let response = concat!(
+            "BF2D26A024",
+            "E3105A0A984405601002000010F59F700101",
+            "E3105A0A984405601002000020F59F700100",
+            "9000"
+        );
+        let profiles = parse_profiles(response).unwrap();
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].iccid, "8944500601200000015");
+        assert!(profiles[0].enabled);
+        assert_eq!(profiles[1].iccid, "8944500601200000025");
+        assert!(!profiles[1].enabled);
+        assert_eq!(profiles[1].class, None);
+    }
+
+    #[test]
+    fn test_parse_max_profiles() {
+        use std::{fmt::Write, string::String};
+
+        let mut list = String::new();
+        for i in 0..MAX_PROFILES {
+            let iccid = encode_iccid(&std::format!("894450060120000{i:03}5")).unwrap();
+            list.push_str("E3135A0A");
+            for byte in iccid {
+                write!(list, "{byte:02X}").unwrap();
+            }
+            // The first profile is enabled, all are operational.
+            write!(list, "9F7001{:02X}910102", u8::from(i == 0)).unwrap();
+        }
+        let response = std::format!("BF2D81ABA081A8{list}9000");
+        assert!(response.len() <= SIM_RESPONSE_LEN);
+
+        let profiles = parse_profiles(&response).unwrap();
+        assert_eq!(profiles.len(), MAX_PROFILES);
+        assert_eq!(profiles[0].iccid, "8944500601200000005");
+        assert!(profiles[0].enabled);
+        assert_eq!(profiles[7].iccid, "8944500601200000075");
+        assert!(!profiles[7].enabled);
+        assert!(
+            profiles
+                .iter()
+                .all(|p| p.class == Some(ProfileClass::Operational))
+        );
+    }
+
+    #[test]
+    fn test_parse_operation_result() {
+        assert_eq!(
+            parse_operation_result("BF31038001009000"),
+            Some(ProfileOperationResult::Ok)
+        );
+        assert_eq!(
+            parse_operation_result("BF32038001029000"),
+            Some(ProfileOperationResult::ProfileNotInExpectedState)
+        );
+        assert_eq!(parse_operation_result("6A88"), None);
+    }
+}
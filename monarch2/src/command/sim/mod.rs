@@ -1,9 +1,16 @@
 use atat::atat_derive::AtatCmd;
 use heapless::String;
+use responses::{
+    FacilityLock, GenericSimAccessResponse, Iccid, PinStatus, RestrictedSimAccessResponse,
+};
+use types::{Facility, FacilityLockMode, RestrictedSimCommand};
 
 use super::NoResponse;
 
+pub mod esim;
+pub mod responses;
 pub mod types;
+pub mod urc;
 
 /// This command sends to the MT a password which is necessary before it can be operated
 /// (SIM PIN, SIM PUK, PH SIM PIN, etc.). If the PIN is to be entered twice,
@@ -22,11 +29,191 @@ pub mod types;
 #[at_cmd("+CPIN", NoResponse, timeout = 300)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct EnterPin {
-    /// PIN code.
+    /// PIN code, or the PUK code when the SIM is waiting for the SIM PUK.
     #[at_arg(position = 0)]
-    pub pin: String<6>,
+    pub pin: String<8>,
 
-    /// New PIN code.
+    /// New PIN code, required when `pin` is a PUK code.
     #[at_arg(position = 1)]
-    pub new_pin: Option<String<6>>,
+    pub new_pin: Option<String<8>>,
+}
+
+/// Returns an alphanumeric string indicating whether some password is required or not.
+///
+/// See also Mobile Termination Error Result Code: +CME ERROR (on page 282) for <err > values.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetPinStatus;
+
+static_cmd!(GetPinStatus, "+CPIN?", PinStatus, timeout = 300);
+
+/// Reads the Integrated Circuit Card Identifier (ICCID) of the SIM card.
+///
+/// Fails with a +CME ERROR when no SIM card is inserted.
+#[derive(Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetIccid;
+
+static_cmd!(GetIccid, "+SQNCCID?", Iccid, timeout = 300);
+
+/// This command is used to lock, unlock or interrogate a MT or a network facility <fac>.
+///
+/// A password is normally needed to do such actions. When querying the status of a network
+/// service (<mode>=2) use [`GetFacilityLock`] instead.
+///
+/// Disabling the SIM lock (facility "SC") allows the device to boot unattended without entering
+/// the SIM PIN.
+///
+/// See also Mobile Termination Error Result Code: +CME ERROR (on page 282) for <err > values.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CLCK", NoResponse, timeout = 5000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetFacilityLock {
+    /// Facility to lock or unlock.
+    #[at_arg(position = 0)]
+    pub fac: Facility,
+
+    /// Whether to lock or unlock the facility.
+    #[at_arg(position = 1)]
+    pub mode: FacilityLockMode,
+
+    /// Password, shall be the same as the password specified for the facility from the MT user
+    /// interface or with the change password command.
+    #[at_arg(position = 2)]
+    pub passwd: Option<String<8>>,
+}
+
+/// Queries the lock status of a MT or a network facility <fac>.
+///
+/// This is the <mode>=2 form of the facility lock command, `mode` should always be
+/// [`FacilityLockMode::QueryStatus`].
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CLCK", FacilityLock, timeout = 5000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetFacilityLock {
+    /// Facility to query.
+    #[at_arg(position = 0)]
+    pub fac: Facility,
+
+    /// Query mode.
+    #[at_arg(position = 1)]
+    pub mode: FacilityLockMode,
+}
+
+/// Generic SIM access.
+///
+/// This command allows a direct control of the SIM by a distant application on the TE. The TE
+/// shall then take care of processing SIM information within the frame specified by GSM/UMTS.
+///
+/// Compared to the restricted SIM access command the definition of this command allows TE to
+/// take more control over the SIM-MT interface, e.g. to talk to SIM applets.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CSIM", GenericSimAccessResponse, timeout = 5000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GenericSimAccess<'a> {
+    /// Length of the characters that are sent to ME in `command` (two times the actual length of
+    /// the command).
+    #[at_arg(position = 0)]
+    pub length: usize,
+
+    /// Command passed on by the MT to the SIM in hexadecimal format (APDU).
+    #[at_arg(position = 1, len = 512)]
+    pub command: &'a str,
+}
+
+/// Restricted SIM access.
+///
+/// By using this command instead of Generic SIM Access AT+CSIM ([`GenericSimAccess`]), TE
+/// application has easier but more limited access to the SIM database, e.g. to read EF files
+/// such as the forbidden PLMN list. Set command transmits to the MT the SIM `command` and its
+/// required parameters.
+///
+/// As response to the command, MT sends the actual SIM information parameters and response data.
+/// MT error result code +CME ERROR may be returned when the command cannot be passed to the
+/// SIM, but failure in the execution of the command in the SIM is reported in `sw1` and `sw2`
+/// parameters.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CRSM", RestrictedSimAccessResponse, timeout = 5000)]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
+pub struct RestrictedSimAccess<'a> {
+    /// Command passed on by the MT to the SIM.
+    #[at_arg(position = 0)]
+    pub command: RestrictedSimCommand,
+
+    /// Identifier of an elementary datafile on SIM. Mandatory for every command except STATUS.
+    #[at_arg(position = 1)]
+    pub file_id: Option<u16>,
+
+    /// Parameter passed on by the MT to the SIM. Mandatory for every command except GET RESPONSE
+    /// and STATUS.
+    #[at_arg(position = 2)]
+    pub p1: Option<u8>,
+
+    /// Parameter passed on by the MT to the SIM.
+    #[at_arg(position = 3)]
+    pub p2: Option<u8>,
+
+    /// Parameter passed on by the MT to the SIM.
+    #[at_arg(position = 4)]
+    pub p3: Option<u8>,
+
+    /// Information which shall be written to the SIM in hexadecimal format.
+    #[at_arg(position = 5, len = 512)]
+    pub data: Option<&'a str>,
+
+    /// Path of an elementary file on the SIM/UICC in hexadecimal format.
+    #[at_arg(position = 6, len = 16)]
+    pub path_id: Option<&'a str>,
+}
+
+compact_fmt!(defmt RestrictedSimAccess<'a>);
+
+/// Open logical channel.
+///
+/// Causes the MT to return `session_id` to allow the TE to identify a channel that is being
+/// allocated by the currently selected UICC, which is attached to ME. The currently selected UICC
+/// will open a new logical channel, select the application identified by the `dfname` received
+/// with this command and return a session Id as the response.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CCHO", LogicalChannel, timeout = 5000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OpenLogicalChannel<'a> {
+    /// All selectable applications in the UICC are referenced by a DF name coded on 1 to 16
+    /// bytes, in hexadecimal format.
+    #[at_arg(position = 0, len = 32)]
+    pub dfname: &'a str,
+}
+
+/// Close logical channel.
+///
+/// Asks the ME to close a communication session with the active UICC.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CCHC", NoResponse, timeout = 5000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CloseLogicalChannel {
+    /// Session Id returned by the open logical channel command.
+    #[at_arg(position = 0)]
+    pub session_id: u32,
+}
+
+/// Generic UICC logical channel access.
+///
+/// Transmits to the MT the `command` that shall be sent as it is to the selected UICC. In the
+/// same manner the UICC `response` shall be sent back by the MT to the TA as it is.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CGLA", LogicalChannelAccessResponse, timeout = 10000)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogicalChannelAccess<'a> {
+    /// Session Id returned by the open logical channel command.
+    #[at_arg(position = 0)]
+    pub session_id: u32,
+
+    /// Length of the characters that are sent to MT in `command` (two times the actual length of
+    /// the command).
+    #[at_arg(position = 1)]
+    pub length: usize,
+
+    /// Command passed on by the MT to the UICC in hexadecimal format.
+    #[at_arg(position = 2, len = 512)]
+    pub command: &'a str,
 }
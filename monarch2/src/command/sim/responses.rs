@@ -0,0 +1,131 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+use super::types::{FacilityLockStatus, SIMState};
+
+/// Capacity of the hexadecimal data of the SIM access responses.
+pub const SIM_RESPONSE_LEN: usize = 512;
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct PinStatus {
+    /// Whether some password is required or not.
+    #[at_arg(position = 0)]
+    pub code: SIMState,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Iccid {
+    /// ICCID of the SIM card.
+    #[at_arg(position = 0)]
+    pub iccid: String<20>,
+
+    /// Operator name stored on the SIM card, usually empty.
+    #[at_arg(position = 1)]
+    pub operator: String<64>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct FacilityLock {
+    /// Whether the facility lock is active.
+    #[at_arg(position = 0)]
+    pub status: FacilityLockStatus,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct GenericSimAccessResponse {
+    /// Length of the characters that are sent to TE in `response` (two times the actual length
+    /// of the response).
+    #[at_arg(position = 0)]
+    pub length: usize,
+
+    /// Response to the command passed on by the SIM to the MT in hexadecimal format.
+    #[at_arg(position = 1)]
+    pub response: String<SIM_RESPONSE_LEN>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct RestrictedSimAccessResponse {
+    /// Information from the SIM about the execution of the actual command (status word 1).
+    #[at_arg(position = 0)]
+    pub sw1: u8,
+
+    /// Information from the SIM about the execution of the actual command (status word 2).
+    #[at_arg(position = 1)]
+    pub sw2: u8,
+
+    /// Response of a successful completion of the command previously issued in hexadecimal
+    /// format. Not returned after a successful update command.
+    #[at_arg(position = 2)]
+    pub response: Option<String<SIM_RESPONSE_LEN>>,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct LogicalChannel {
+    /// A session Id to be used in order to target a specific application on the smart card
+    /// using logical channels mechanism.
+    #[at_arg(position = 0)]
+    pub session_id: u32,
+}
+
+#[derive(Clone, Debug, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct LogicalChannelAccessResponse {
+    /// Length of the characters that are sent to TE in `response` (two times the actual length
+    /// of the response).
+    #[at_arg(position = 0)]
+    pub length: usize,
+
+    /// Response to the command passed on by the UICC to the MT in hexadecimal format.
+    #[at_arg(position = 1)]
+    pub response: String<SIM_RESPONSE_LEN>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_pin_status_parsing() {
+        let status: PinStatus = from_str("+CPIN: READY").unwrap();
+        assert_eq!(status.code, SIMState::Ready);
+
+        let status: PinStatus = from_str("+CPIN: SIM PIN").unwrap();
+        assert_eq!(status.code, SIMState::PinRequired);
+    }
+
+    #[test]
+    fn test_iccid_parsing() {
+        let res: Iccid = from_str("+SQNCCID: \"89882280666027595366\",\"\"").unwrap();
+        assert_eq!(res.iccid, "89882280666027595366");
+        assert_eq!(res.operator, "");
+    }
+
+    #[test]
+    fn test_facility_lock_parsing() {
+        let lock: FacilityLock = from_str("+CLCK: 1").unwrap();
+        assert_eq!(lock.status, FacilityLockStatus::Active);
+    }
+
+    #[test]
+    fn test_restricted_sim_access_parsing() {
+        let res: RestrictedSimAccessResponse =
+            from_str("+CRSM: 144,0,\"32F4103254F6FFFFFFFFFFFF\"").unwrap();
+        assert_eq!(res.sw1, 144);
+        assert_eq!(res.sw2, 0);
+        assert_eq!(res.response.as_deref(), Some("32F4103254F6FFFFFFFFFFFF"));
+    }
+}
@@ -1,40 +1,108 @@
 use atat::atat_derive::AtatEnum;
 
 /// The possible states that the SIM card can be in.
-#[derive(Clone, PartialEq, AtatEnum)]
-#[at_enum(u8)]
+///
+/// The modem reports the state as an unquoted string, e.g. `+CPIN: SIM PIN`.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SIMState {
     /// MT is not waiting for any password.
-    Ready = 1,
+    #[at_enum("READY")]
+    Ready,
     /// MT is waiting for the SIM PIN to be given.
-    PinRequired = 2,
+    #[at_enum("SIM PIN")]
+    PinRequired,
     /// MT is waiting for the SIM PUK to be given.
-    PukRequired = 3,
+    #[at_enum("SIM PUK")]
+    PukRequired,
     /// MT is waiting for the phone to SIM card password to be given.
-    PhoneToSimPinRequired = 4,
+    #[at_enum("PH-SIM PIN")]
+    PhoneToSimPinRequired,
     /// MT is waiting for the phone-to-very first SIM card password to be given.
-    PhoneToFirstSimPinRequired = 5,
+    #[at_enum("PH-FSIM PIN")]
+    PhoneToFirstSimPinRequired,
     /// MT is waiting for the phone-to-very first SIM card unblocking password to be given.
-    PhoneToFirstSimPukRequired = 6,
+    #[at_enum("PH-FSIM PUK")]
+    PhoneToFirstSimPukRequired,
     /// MT is waiting for theSIM PIN2 to be given (this <code> is recommended to be returned only when the last executed command resulted in PIN2 authentication failure (i.e. +CME ERROR: 17); if PIN2 is not entered right after the failure, it is recommended that MT does not block its operation).
-    Pin2Required = 7,
+    #[at_enum("SIM PIN2")]
+    Pin2Required,
     /// MT is waiting for the SIM PUK2 to be given (this < code> is recommended to be returned only when the last executed command resulted in PUK2 authentication failure (i.e. +CME ERROR: 18); if PUK2 and new PIN2 are not entered right after the failure, it is recommended that MT does not block its operation).
-    Puk2Required = 8,
+    #[at_enum("SIM PUK2")]
+    Puk2Required,
     /// MT is waiting for the network personalisation password to be given.
-    NetworkPinRequired = 9,
+    #[at_enum("PH-NET PIN")]
+    NetworkPinRequired,
     /// MT is waiting for the network personalisation unblocking password to be given.
-    NetworkPukRequired = 10,
+    #[at_enum("PH-NET PUK")]
+    NetworkPukRequired,
     /// MT is waiting for the network subset personalization password to be given.
-    NetworkSubsetPinRequired = 11,
+    #[at_enum("PH-NETSUB PIN")]
+    NetworkSubsetPinRequired,
     /// MT is waiting for the network subset personalization unblocking password to be given.
-    NetworkSubsetPukRequired = 12,
+    #[at_enum("PH-NETSUB PUK")]
+    NetworkSubsetPukRequired,
     /// MT is waiting for the service provider personalization password to be given.
-    ServiceProviderPinRequired = 13,
+    #[at_enum("PH-SP PIN")]
+    ServiceProviderPinRequired,
     /// MT is waiting for service provider personalisation unblocking password to be given.
-    ServiceProviderPukRequired = 14,
+    #[at_enum("PH-SP PUK")]
+    ServiceProviderPukRequired,
     /// MT is waiting for the corporate personalisation password to be given.
-    CorporateSimRequired = 15,
+    #[at_enum("PH-CORP PIN")]
+    CorporateSimRequired,
     /// MT is waiting for the corporate personalisation unblocking password to be given.
-    CorporatePukRequired = 16,
+    #[at_enum("PH-CORP PUK")]
+    CorporatePukRequired,
+}
+
+/// Facilities that can be locked with the facility lock command.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Facility {
+    /// SIM (lock SIM/UICC card installed in the currently selected card slot). The SIM/UICC
+    /// asks the password in MT power-up and when this lock command issued.
+    #[at_enum("SC")]
+    Sim,
+    /// SIM fixed dialling memory feature (if PIN2 authentication has not been done during the
+    /// current session, PIN2 is required as <passwd>).
+    #[at_enum("FD")]
+    FixedDialling,
+    /// Network Personalization (refer 3GPP TS 22.022).
+    #[at_enum("PN")]
+    NetworkPersonalisation,
+}
+
+/// Facility lock command mode.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[at_enum(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FacilityLockMode {
+    Unlock = 0,
+    Lock = 1,
+    QueryStatus = 2,
+}
+
+/// Facility lock status.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[at_enum(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FacilityLockStatus {
+    NotActive = 0,
+    Active = 1,
+}
+
+/// Commands passed on by the MT to the SIM with the restricted SIM access command.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[at_enum(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RestrictedSimCommand {
+    ReadBinary = 176,
+    ReadRecord = 178,
+    GetResponse = 192,
+    UpdateBinary = 214,
+    UpdateRecord = 220,
+    Status = 242,
+    RetrieveData = 203,
+    SetData = 219,
 }
@@ -0,0 +1,11 @@
+use atat::atat_derive::AtatResp;
+
+/// SIM card insertion status, reported when enabled with AT+SQNSIMST=1.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct SimStatus {
+    /// 0 when the SIM card was removed, 1 when it was inserted.
+    #[at_arg(position = 0)]
+    pub state: u8,
+}
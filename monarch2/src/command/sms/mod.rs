@@ -1 +1,22 @@
+use atat::atat_derive::AtatCmd;
+
+use super::NoResponse;
+
+pub mod urc;
+
 pub struct Placeholder;
+
+/// 3GPP TS 27.005 3.4.1 Configures the new message indications (+CMTI).
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+CNMI", NoResponse)]
+pub struct ConfigureNewMessageIndications {
+    /// 0 buffers the indications in the modem, 1 discards them while the serial link is
+    /// reserved, 2 buffers them while reserved and flushes them afterwards.
+    #[at_arg(position = 0)]
+    pub mode: u8,
+
+    /// 0 doesn't indicate the received messages, 1 indicates them with +CMTI.
+    #[at_arg(position = 1)]
+    pub mt: u8,
+}
@@ -0,0 +1,29 @@
+use atat::atat_derive::AtatResp;
+use heapless::String;
+
+/// 3GPP TS 27.005 3.4.1 New message indication +CMTI, a new SMS was stored.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct NewMessage {
+    /// Memory storage the message was stored to, e.g. `"SM"`.
+    #[at_arg(position = 0)]
+    pub mem: String<4>,
+
+    /// Location of the message in the memory storage.
+    #[at_arg(position = 1)]
+    pub index: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_new_message_parse() {
+        let res: NewMessage = from_str("+CMTI: \"SM\",3").unwrap();
+        assert_eq!(res.mem.as_str(), "SM");
+        assert_eq!(res.index, 3);
+    }
+}
@@ -0,0 +1,109 @@
+//! Sockets opened by the application, e.g. with [`raw`](crate::raw) commands.
+//!
+//! In the hexadecimal data mode, see [`ConfigureExtended`], the data is exchanged as hexadecimal
+//! strings instead of a binary prompt, which keeps every byte of the payload on the AT lines.
+
+use atat::{
+    InternalError,
+    atat_derive::AtatCmd,
+    serde_at::{SerializeOptions, to_slice},
+};
+use responses::{ReceivedData, SocketInfo};
+use serde::Serialize;
+use types::{DataMode, RingMode};
+
+use super::NoResponse;
+use crate::types::Bool;
+
+pub mod responses;
+pub mod types;
+pub mod urc;
+
+/// Largest number of bytes read at once with [`Receive`].
+pub const SOCKET_RECV_LEN: usize = 256;
+
+/// Number of sockets of the modem, identified from 1.
+pub const SOCKET_COUNT: u8 = 6;
+
+/// Configures the extended options of a socket, notably the representation of its data.
+///
+/// Must be sent while the socket is closed.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNSCFGEXT", NoResponse, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigureExtended {
+    /// Socket connection identifier, from 1 to 6.
+    #[at_arg(position = 0)]
+    pub conn_id: u8,
+
+    #[at_arg(position = 1)]
+    pub ring_mode: RingMode,
+
+    /// Representation of the data read with [`Receive`].
+    #[at_arg(position = 2)]
+    pub recv_data_mode: DataMode,
+
+    /// Unused, must be 0.
+    #[at_arg(position = 3)]
+    pub keepalive: u8,
+
+    /// Whether the incoming connections of a listening socket are accepted automatically.
+    #[at_arg(position = 4)]
+    pub listen_auto_rsp: Option<Bool>,
+
+    /// Representation of the data written to the socket.
+    #[at_arg(position = 5)]
+    pub send_data_mode: Option<DataMode>,
+}
+
+/// Reads the counters of a socket, see [`SocketInfo`].
+///
+/// The counters restart from 0 every time the socket is opened.
+///
+/// Type: `synchronous`
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SQNSI", SocketInfo, timeout = 300)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GetSocketInfo {
+    /// Socket connection identifier, from 1 to [`SOCKET_COUNT`].
+    #[at_arg(position = 0)]
+    pub conn_id: u8,
+}
+
+/// Reads the data received on a socket in the [`DataMode::Hex`] receive mode, up to
+/// [`SOCKET_RECV_LEN`] bytes.
+///
+/// The modem answers with the number of bytes read, then the data on its own line, see
+/// [`ReceivedData`].
+///
+/// Type: `synchronous`
+#[derive(Clone, Serialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Receive {
+    /// Socket connection identifier, from 1 to 6.
+    pub conn_id: u8,
+
+    /// Number of bytes to read, up to [`SOCKET_RECV_LEN`].
+    pub max_bytes: u16,
+}
+
+impl atat::AtatCmd for Receive {
+    type Response = ReceivedData;
+
+    const MAX_LEN: usize = "AT+SQNSRECV=".len()
+        + 1 // connection id
+        + 6 // max bytes
+        + 2; // termination
+    const MAX_TIMEOUT_MS: u32 = 300;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        // Can't fail, both arguments are numbers.
+        to_slice(self, "+SQNSRECV", buf, SerializeOptions::default()).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, atat::Error> {
+        ReceivedData::parse(resp?).ok_or(atat::Error::Parse)
+    }
+}
@@ -0,0 +1,92 @@
+use atat::{AtatResp, atat_derive::AtatResp};
+use heapless::Vec;
+
+use super::SOCKET_RECV_LEN;
+use crate::types::hex_decode;
+
+/// Response to [`GetSocketInfo`](super::GetSocketInfo), the byte counters of a socket since it
+/// was opened.
+#[derive(Clone, Debug, PartialEq, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct SocketInfo {
+    #[at_arg(position = 0)]
+    pub conn_id: u8,
+
+    /// Bytes sent.
+    #[at_arg(position = 1)]
+    pub sent: u32,
+
+    /// Bytes received, read or not.
+    #[at_arg(position = 2)]
+    pub received: u32,
+
+    /// Bytes received and not read yet.
+    #[at_arg(position = 3)]
+    pub buffered: u32,
+
+    /// Bytes sent and not acknowledged by the peer yet, TCP only.
+    #[at_arg(position = 4)]
+    pub ack_waiting: u32,
+}
+
+/// Response to [`Receive`](super::Receive), the data read from the socket, decoded.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ReceivedData {
+    /// Socket connection identifier, from 1 to 6.
+    pub conn_id: u8,
+
+    pub data: Vec<u8, SOCKET_RECV_LEN>,
+}
+
+impl AtatResp for ReceivedData {}
+
+impl ReceivedData {
+    /// Parses the `+SQNSRECV: <connId>,<count>` line of the response and the hexadecimal data
+    /// following it.
+    ///
+    /// Returns `None` without the line, or if the data isn't `count` bytes of valid
+    /// hexadecimal, e.g. because the socket is in the [`Text`](super::types::DataMode::Text)
+    /// receive mode.
+    pub fn parse(resp: &[u8]) -> Option<Self> {
+        let mut lines = resp.split(|&b| b == b'\n').map(<[u8]>::trim_ascii);
+        let header = lines.find_map(|line| line.strip_prefix(b"+SQNSRECV:"))?;
+        let (conn_id, count) = core::str::from_utf8(header).ok()?.split_once(',')?;
+        let conn_id = conn_id.trim().parse().ok()?;
+        let count: usize = count.trim().parse().ok()?;
+
+        let mut data = Vec::new();
+        if count > 0 {
+            let hex = core::str::from_utf8(lines.next()?).ok()?;
+            data.resize_default(SOCKET_RECV_LEN).ok()?;
+            let len = hex_decode(hex, &mut data)?;
+            if len != count {
+                return None;
+            }
+            data.truncate(len);
+        }
+        Some(Self { conn_id, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_received_data_parse() {
+        let res = ReceivedData::parse(b"+SQNSRECV: 1,4\r\nDEADBEEF").unwrap();
+        assert_eq!(res.conn_id, 1);
+        assert_eq!(res.data, [0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let res = ReceivedData::parse(b"+SQNSRECV: 2,0").unwrap();
+        assert!(res.data.is_empty());
+
+        // Binary data of the text mode.
+        assert_eq!(ReceivedData::parse(b"+SQNSRECV: 1,4\r\nab\x00\xff"), None);
+        // Count not matching the data.
+        assert_eq!(ReceivedData::parse(b"+SQNSRECV: 1,3\r\nDEADBEEF"), None);
+    }
+}
@@ -0,0 +1,42 @@
+use atat::atat_derive::AtatEnum;
+
+/// Content of the [`Ring`](super::urc::Ring) URCs reporting the data received on a socket.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum RingMode {
+    /// Only the connection identifier.
+    Normal = 0,
+    /// The connection identifier and the number of bytes available to read.
+    DataAmount = 1,
+}
+
+/// Bytes sent and received, see [`Modem::data_usage`](crate::Modem::data_usage).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct DataUsage {
+    pub sent: u64,
+    pub received: u64,
+}
+
+impl DataUsage {
+    /// Returns the usage of both, e.g. to add up the usage of the sockets.
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self {
+            sent: self.sent.saturating_add(other.sent),
+            received: self.received.saturating_add(other.received),
+        }
+    }
+}
+
+/// Representation of the data of a socket.
+#[derive(Debug, Clone, Copy, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum DataMode {
+    /// Bytes as they are, after a prompt or on their own line.
+    Text = 0,
+    /// Hexadecimal string, two characters per byte.
+    Hex = 1,
+}
@@ -0,0 +1,31 @@
+use atat::atat_derive::AtatResp;
+
+/// Data was received on a socket, or the incoming connection of a listening socket was accepted.
+#[derive(Debug, Clone, AtatResp)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct Ring {
+    /// Socket connection identifier, from 1 to 6.
+    #[at_arg(position = 0)]
+    pub conn_id: u8,
+
+    /// Number of bytes available to read, only reported in the data amount ring mode.
+    #[at_arg(position = 1)]
+    pub rec_data: Option<u16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atat::serde_at::from_str;
+
+    #[test]
+    fn test_ring_parse() {
+        let res: Ring = from_str("+SQNSRING: 1,16").unwrap();
+        assert_eq!(res.conn_id, 1);
+        assert_eq!(res.rec_data, Some(16));
+
+        let res: Ring = from_str("+SQNSRING: 2").unwrap();
+        assert_eq!(res.rec_data, None);
+    }
+}
@@ -13,7 +13,7 @@ pub mod types;
 /// A security profile is identified by a unique ID <spld>. Up to 6 security profiles can be configured. Each security profile cover the following SSL/LS connections properties:
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+SQNSPCFG", Configuration, timeout = 1000)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
 pub struct Configure {
     /// Security profile identifier.
     #[at_arg(position = 0)]
@@ -104,3 +104,5 @@ pub struct Configure {
     #[at_arg(position = 11)]
     pub lifetime: u32,
 }
+
+compact_fmt!(defmt Configure);
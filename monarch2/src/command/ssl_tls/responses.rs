@@ -6,6 +6,7 @@ use crate::types::Nullable;
 use super::types::{Resume, SslTlsVersion, StorageId};
 
 #[derive(Clone, AtatResp)]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
 pub struct Configuration {
     /// Security profile identifier.
     #[at_arg(position = 0)]
@@ -90,3 +91,26 @@ pub struct Configuration {
     #[at_arg(position = 11)]
     pub lifetime: u32,
 }
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Configuration {
+    fn format(&self, f: defmt::Formatter) {
+        // The pre-shared key is a secret, only report whether one is set.
+        defmt::write!(
+            f,
+            "Configuration {{ sp_id: {}, version: {}, cipher_specs: {}, cert_valid_level: {}, ca_cert_id: {}, client_cert_id: {}, client_private_key_id: {}, psk: {}, psk_identity: {}, storage_id: {}, resume: {}, lifetime: {} }}",
+            self.sp_id,
+            self.version,
+            self.cipher_specs,
+            self.cert_valid_level,
+            self.ca_cert_id,
+            self.client_cert_id,
+            self.client_private_key_id,
+            self.psk.as_ref().map(|_| "<redacted>"),
+            self.psk_identity,
+            self.storage_id,
+            self.resume,
+            self.lifetime,
+        );
+    }
+}
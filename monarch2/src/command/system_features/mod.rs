@@ -1,12 +1,13 @@
 /// https://quickspot.io/docs/file/gm02s_at_commands.pdf
 use atat::atat_derive::AtatCmd;
-use types::{CEREGReports, CMEErrorReports};
+use types::{CEREGReports, CMEErrorReports, PacketDomainEventReports, RingIndicatorMode};
 
 use super::NoResponse;
 
 pub mod types;
 
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+CMEE", NoResponse, timeout = 300)]
 pub struct ConfigureCMEErrorReports {
     #[at_arg(position = 0)]
@@ -14,8 +15,34 @@ pub struct ConfigureCMEErrorReports {
 }
 
 #[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_cmd("+CEREG", NoResponse)]
 pub struct ConfigureCEREGReports {
     #[at_arg(position = 0)]
     pub typ: CEREGReports,
 }
+
+/// Configures the packet domain event (+CGEV) reporting.
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+CGEREP", NoResponse)]
+pub struct ConfigurePacketDomainEventReports {
+    #[at_arg(position = 0)]
+    pub mode: PacketDomainEventReports,
+}
+
+/// Configures when the modem asserts its ring indicator (RI) line.
+///
+/// The line is pulsed for `duration` milliseconds, so a sleeping host can wake up before
+/// reading the URC or the data from the UART.
+#[derive(Clone, AtatCmd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_cmd("+SQNRICFG", NoResponse)]
+pub struct ConfigureRingIndicator {
+    #[at_arg(position = 0)]
+    pub mode: RingIndicatorMode,
+
+    /// Length of the pulse in milliseconds, the default of the modem if not set.
+    #[at_arg(position = 1)]
+    pub duration: Option<u16>,
+}
@@ -2,6 +2,7 @@ use atat::atat_derive::AtatEnum;
 
 /// The CME error reporting methods.
 #[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
 pub enum CMEErrorReports {
     Off = 0,
@@ -11,6 +12,7 @@ pub enum CMEErrorReports {
 
 /// The CEREG unsolicited reporting methods.
 #[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[at_enum(u8)]
 pub enum CEREGReports {
     Off = 0,
@@ -20,3 +22,32 @@ pub enum CEREGReports {
     EnabledUePsmWithLocation = 4,
     EnabledUePsmWithLocationEmmCause = 5,
 }
+
+/// The CGEV unsolicited reporting methods.
+#[derive(Clone, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum PacketDomainEventReports {
+    /// The events are buffered in the modem, the oldest are discarded when the buffer is full.
+    Off = 0,
+    /// The events are forwarded, discarded while the serial link is reserved (e.g. during a
+    /// prompt).
+    Enabled = 1,
+    /// The events are forwarded, buffered while the serial link is reserved and flushed
+    /// afterwards.
+    Buffered = 2,
+}
+
+/// Events asserting the ring indicator (RI) line of the modem, e.g. to wake the host from sleep.
+#[derive(Clone, Copy, Debug, PartialEq, AtatEnum)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[at_enum(u8)]
+pub enum RingIndicatorMode {
+    Disabled = 0,
+    /// Every URC sent to the host.
+    Urcs = 1,
+    /// The data received on a socket.
+    SocketData = 2,
+    /// Both the URCs and the socket data.
+    All = 3,
+}
@@ -155,6 +155,39 @@ impl<T: AtatLen> From<Nullable<T>> for Option<T> {
     }
 }
 
+/// Encodes `data` as upper case hexadecimal characters and appends them to `out`.
+pub(crate) fn hex_encode<const N: usize>(
+    data: &[u8],
+    out: &mut heapless::String<N>,
+) -> Result<(), ()> {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+    for byte in data {
+        out.push(DIGITS[(byte >> 4) as usize] as char)?;
+        out.push(DIGITS[(byte & 0x0F) as usize] as char)?;
+    }
+
+    Ok(())
+}
+
+/// Decodes the hexadecimal string `hex` into `out`, returning the number of decoded bytes.
+///
+/// Returns `None` if `hex` is not valid hexadecimal or doesn't fit into `out`.
+pub(crate) fn hex_decode(hex: &str, out: &mut [u8]) -> Option<usize> {
+    let hex = hex.as_bytes();
+    if hex.len() % 2 != 0 || hex.len() / 2 > out.len() {
+        return None;
+    }
+
+    for (byte, pair) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        let hi = (pair[0] as char).to_digit(16)?;
+        let lo = (pair[1] as char).to_digit(16)?;
+        *byte = ((hi << 4) | lo) as u8;
+    }
+
+    Some(hex.len() / 2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +224,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hex_roundtrip() {
+        let mut encoded = heapless::String::<16>::new();
+        hex_encode(&[0x00, 0xAB, 0x7F], &mut encoded).unwrap();
+        assert_eq!(encoded, "00AB7F");
+
+        let mut decoded = [0u8; 4];
+        assert_eq!(hex_decode("00ab7F", &mut decoded), Some(3));
+        assert_eq!(&decoded[..3], &[0x00, 0xAB, 0x7F]);
+
+        assert_eq!(hex_decode("0", &mut decoded), None);
+        assert_eq!(hex_decode("zz", &mut decoded), None);
+        assert_eq!(hex_decode("0011223344", &mut decoded), None);
+    }
+
     #[test]
     fn de_nullable() {
         #[derive(Debug, PartialEq, Serialize, AtatResp)]
@@ -0,0 +1,504 @@
+use embassy_time::Duration;
+use heapless::String;
+
+#[cfg(feature = "gnss")]
+use crate::gnss::types::UrcNotificationSetting;
+use crate::{
+    capacity::{Apn, PdpCredential},
+    pdp::types::{PDPAuthProtocol, PDPType},
+    system_features::types::{
+        CEREGReports, CMEErrorReports, PacketDomainEventReports, RingIndicatorMode,
+    },
+};
+
+/// Configuration of the modem driver passed to [`Modem::new`](crate::Modem::new).
+///
+/// Use [`ModemConfig::default`] and the `with_*` methods to override individual settings:
+///
+/// ```
+/// use embassy_time::Duration;
+/// use monarch2::ModemConfig;
+///
+/// let config = ModemConfig::default()
+///     .with_sim_pin(heapless::String::try_from("1234").unwrap())
+///     .with_mqtt_connect_timeout(Duration::from_secs(60));
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct ModemConfig {
+    /// SIM PIN submitted automatically by [`Modem::begin`](crate::Modem::begin) when the SIM
+    /// waits for it.
+    pub sim_pin: Option<String<8>>,
+
+    /// CME error reporting configured by [`Modem::begin`](crate::Modem::begin).
+    ///
+    /// Typed error handling relies on numeric reports.
+    pub cme_error_reports: CMEErrorReports,
+
+    /// URCs reported by the modem, configured by [`Modem::begin`](crate::Modem::begin).
+    pub urc: UrcPolicy,
+
+    /// Whether [`Modem::begin`](crate::Modem::begin) enables the automatic update of the clock and
+    /// time zone from the network (NITZ).
+    pub automatic_time_zone_update: bool,
+
+    /// Whether [`Modem::begin`](crate::Modem::begin) lets the modem attach on its own after it
+    /// starts, `None` keeps the setting of the modem.
+    ///
+    /// Disabled, the network is only attached by [`Modem::lte_connect`](crate::Modem::lte_connect).
+    pub autoconnect: Option<bool>,
+
+    /// PDP context defined by [`Modem::define_pdp_context`](crate::Modem::define_pdp_context).
+    pub pdp: PdpConfig,
+
+    /// How long [`Modem::lte_connect`](crate::Modem::lte_connect) waits for the network
+    /// registration, and [`Modem::lte_disconnect`](crate::Modem::lte_disconnect) for the
+    /// deregistration.
+    pub registration_timeout: Duration,
+
+    /// How long to wait for the broker to accept an MQTT connection.
+    pub mqtt_connect_timeout: Duration,
+
+    /// How long to wait for an MQTT publication or subscription to complete.
+    pub mqtt_operation_timeout: Duration,
+
+    /// How long to wait for the LwM2M client to bootstrap and register.
+    pub lwm2m_registration_timeout: Duration,
+
+    /// How long to wait for a GNSS fix.
+    pub gnss_fix_timeout: Duration,
+
+    /// How long the modem waits for the server to respond to an HTTP request, set on the
+    /// profile by [`Modem::http_configure`](crate::Modem::http_configure).
+    pub http_timeout: Duration,
+
+    /// How long to wait for an NTP server to synchronize the modem clock.
+    pub ntp_timeout: Duration,
+
+    /// How long to wait for the modem to start again after [`Modem::reboot`](crate::Modem::reboot).
+    pub reboot_timeout: Duration,
+
+    /// How long a command waits for the modem to wake up from a sleep state, see
+    /// [`ModemState::set_asleep`](crate::ModemState::set_asleep).
+    pub wake_timeout: Duration,
+
+    /// How often to poll the modem clock while waiting for the network to synchronize it.
+    pub clock_sync_retry: RetryPolicy,
+
+    /// How often to poll the GNSS assistance data while waiting for it to be downloaded.
+    pub gnss_assistance_retry: RetryPolicy,
+
+    /// How commands rejected with a transient `+CME ERROR` (e.g. SIM busy, network timeout, see
+    /// [`Error::class`](crate::Error::class)) are retried by [`Modem::send`](crate::Modem::send). A
+    /// single attempt disables the retries.
+    pub command_retry: BackoffPolicy,
+}
+
+compact_fmt!(ModemConfig);
+
+#[cfg(any(test, feature = "verbose-fmt"))]
+impl core::fmt::Debug for ModemConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never log the SIM PIN.
+        f.debug_struct("ModemConfig")
+            .field("sim_pin", &self.sim_pin.as_ref().map(|_| "<redacted>"))
+            .field("cme_error_reports", &self.cme_error_reports)
+            .field("urc", &self.urc)
+            .field(
+                "automatic_time_zone_update",
+                &self.automatic_time_zone_update,
+            )
+            .field("autoconnect", &self.autoconnect)
+            .field("pdp", &self.pdp)
+            .field("registration_timeout", &self.registration_timeout)
+            .field("mqtt_connect_timeout", &self.mqtt_connect_timeout)
+            .field("mqtt_operation_timeout", &self.mqtt_operation_timeout)
+            .field(
+                "lwm2m_registration_timeout",
+                &self.lwm2m_registration_timeout,
+            )
+            .field("gnss_fix_timeout", &self.gnss_fix_timeout)
+            .field("http_timeout", &self.http_timeout)
+            .field("ntp_timeout", &self.ntp_timeout)
+            .field("reboot_timeout", &self.reboot_timeout)
+            .field("wake_timeout", &self.wake_timeout)
+            .field("clock_sync_retry", &self.clock_sync_retry)
+            .field("gnss_assistance_retry", &self.gnss_assistance_retry)
+            .field("command_retry", &self.command_retry)
+            .finish()
+    }
+}
+
+#[cfg(all(feature = "defmt", feature = "verbose-fmt"))]
+impl defmt::Format for ModemConfig {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ModemConfig {{ sim_pin: {}, cme_error_reports: {}, urc: {}, automatic_time_zone_update: {}, autoconnect: {}, pdp: {}, registration_timeout: {}, mqtt_connect_timeout: {}, mqtt_operation_timeout: {}, lwm2m_registration_timeout: {}, gnss_fix_timeout: {}, http_timeout: {}, ntp_timeout: {}, reboot_timeout: {}, wake_timeout: {}, clock_sync_retry: {}, gnss_assistance_retry: {}, command_retry: {} }}",
+            self.sim_pin.as_ref().map(|_| "<redacted>"),
+            self.cme_error_reports,
+            self.urc,
+            self.automatic_time_zone_update,
+            self.autoconnect,
+            self.pdp,
+            self.registration_timeout,
+            self.mqtt_connect_timeout,
+            self.mqtt_operation_timeout,
+            self.lwm2m_registration_timeout,
+            self.gnss_fix_timeout,
+            self.http_timeout,
+            self.ntp_timeout,
+            self.reboot_timeout,
+            self.wake_timeout,
+            self.clock_sync_retry,
+            self.gnss_assistance_retry,
+            self.command_retry,
+        );
+    }
+}
+
+impl Default for ModemConfig {
+    fn default() -> Self {
+        Self {
+            sim_pin: None,
+            cme_error_reports: CMEErrorReports::Numeric,
+            urc: UrcPolicy::default(),
+            automatic_time_zone_update: true,
+            autoconnect: None,
+            pdp: PdpConfig::default(),
+            registration_timeout: Duration::from_secs(180),
+            mqtt_connect_timeout: Duration::from_secs(30),
+            mqtt_operation_timeout: Duration::from_secs(30),
+            lwm2m_registration_timeout: Duration::from_secs(60),
+            gnss_fix_timeout: Duration::from_secs(180),
+            http_timeout: Duration::from_secs(120),
+            ntp_timeout: Duration::from_secs(30),
+            reboot_timeout: Duration::from_secs(10),
+            wake_timeout: Duration::from_secs(5),
+            clock_sync_retry: RetryPolicy {
+                attempts: 5,
+                delay: Duration::from_millis(500),
+            },
+            gnss_assistance_retry: RetryPolicy {
+                attempts: 10,
+                delay: Duration::from_secs(10),
+            },
+            command_retry: BackoffPolicy {
+                attempts: 3,
+                initial_delay: Duration::from_millis(200),
+                max_delay: Duration::from_secs(2),
+            },
+        }
+    }
+}
+
+impl ModemConfig {
+    pub fn with_sim_pin(mut self, pin: String<8>) -> Self {
+        self.sim_pin = Some(pin);
+        self
+    }
+
+    pub fn with_cme_error_reports(mut self, reports: CMEErrorReports) -> Self {
+        self.cme_error_reports = reports;
+        self
+    }
+
+    pub fn with_cereg_reports(mut self, reports: CEREGReports) -> Self {
+        self.urc.cereg = reports;
+        self
+    }
+
+    pub fn with_urc_policy(mut self, policy: UrcPolicy) -> Self {
+        self.urc = policy;
+        self
+    }
+
+    pub fn with_automatic_time_zone_update(mut self, enabled: bool) -> Self {
+        self.automatic_time_zone_update = enabled;
+        self
+    }
+
+    pub fn with_autoconnect(mut self, enabled: bool) -> Self {
+        self.autoconnect = Some(enabled);
+        self
+    }
+
+    pub fn with_pdp(mut self, pdp: PdpConfig) -> Self {
+        self.pdp = pdp;
+        self
+    }
+
+    pub fn with_registration_timeout(mut self, timeout: Duration) -> Self {
+        self.registration_timeout = timeout;
+        self
+    }
+
+    pub fn with_mqtt_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.mqtt_connect_timeout = timeout;
+        self
+    }
+
+    pub fn with_mqtt_operation_timeout(mut self, timeout: Duration) -> Self {
+        self.mqtt_operation_timeout = timeout;
+        self
+    }
+
+    pub fn with_lwm2m_registration_timeout(mut self, timeout: Duration) -> Self {
+        self.lwm2m_registration_timeout = timeout;
+        self
+    }
+
+    pub fn with_gnss_fix_timeout(mut self, timeout: Duration) -> Self {
+        self.gnss_fix_timeout = timeout;
+        self
+    }
+
+    pub fn with_http_timeout(mut self, timeout: Duration) -> Self {
+        self.http_timeout = timeout;
+        self
+    }
+
+    pub fn with_ntp_timeout(mut self, timeout: Duration) -> Self {
+        self.ntp_timeout = timeout;
+        self
+    }
+
+    pub fn with_reboot_timeout(mut self, timeout: Duration) -> Self {
+        self.reboot_timeout = timeout;
+        self
+    }
+
+    pub fn with_wake_timeout(mut self, timeout: Duration) -> Self {
+        self.wake_timeout = timeout;
+        self
+    }
+
+    pub fn with_clock_sync_retry(mut self, retry: RetryPolicy) -> Self {
+        self.clock_sync_retry = retry;
+        self
+    }
+
+    pub fn with_gnss_assistance_retry(mut self, retry: RetryPolicy) -> Self {
+        self.gnss_assistance_retry = retry;
+        self
+    }
+
+    pub fn with_command_retry(mut self, retry: BackoffPolicy) -> Self {
+        self.command_retry = retry;
+        self
+    }
+}
+
+/// Which URCs the modem reports.
+///
+/// Turning off the indications the application doesn't consume saves UART wakeups. The driver
+/// relies on some of them: without +CEREG reports [`Modem::lte_connect`](crate::Modem::lte_connect)
+/// never sees the registration, without GNSS notifications
+/// [`Modem::get_gnss_fix`](crate::Modem::get_gnss_fix) times out.
+///
+/// The MQTT and CoAP URCs belong to their sessions and can't be turned off.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UrcPolicy {
+    /// Network registration (+CEREG) reporting level.
+    pub cereg: CEREGReports,
+
+    /// Packet domain event (+CGEV) reporting.
+    pub packet_domain_events: PacketDomainEventReports,
+
+    /// Events asserting the ring indicator line, `None` keeps the setting of the modem.
+    pub ring_indicator: Option<RingIndicatorMode>,
+
+    /// Whether received SMS are indicated with +CMTI.
+    #[cfg(feature = "sms")]
+    pub sms_indications: bool,
+
+    /// Content of the GNSS fix notifications, applied by
+    /// [`Modem::set_gnss_config`](crate::Modem::set_gnss_config).
+    #[cfg(feature = "gnss")]
+    pub gnss: UrcNotificationSetting,
+}
+
+impl Default for UrcPolicy {
+    fn default() -> Self {
+        Self {
+            cereg: CEREGReports::Enabled,
+            packet_domain_events: PacketDomainEventReports::Off,
+            ring_indicator: None,
+            #[cfg(feature = "sms")]
+            sms_indications: false,
+            #[cfg(feature = "gnss")]
+            gnss: UrcNotificationSetting::Full,
+        }
+    }
+}
+
+/// Default PDP context parameters.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PdpConfig {
+    /// Context Identifier (CID): integer between 1–16.
+    pub cid: u8,
+
+    /// PDP Type.
+    pub pdp_type: PDPType,
+
+    /// Cellular APN for SIM card. Leave empty to autodetect APN.
+    pub apn: Apn,
+
+    /// Credentials of the APN, if it requires any.
+    pub auth: Option<PdpAuth>,
+}
+
+impl Default for PdpConfig {
+    fn default() -> Self {
+        Self {
+            cid: 1,
+            pdp_type: PDPType::IP,
+            apn: String::new(),
+            auth: None,
+        }
+    }
+}
+
+/// Credentials of a PDP context, set by
+/// [`Modem::define_pdp_context`](crate::Modem::define_pdp_context).
+#[derive(Clone, PartialEq)]
+pub struct PdpAuth {
+    pub protocol: PDPAuthProtocol,
+    pub username: PdpCredential,
+    pub password: PdpCredential,
+}
+
+impl core::fmt::Debug for PdpAuth {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never log the password.
+        f.debug_struct("PdpAuth")
+            .field("protocol", &self.protocol)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for PdpAuth {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PdpAuth {{ protocol: {}, username: {}, password: <redacted> }}",
+            self.protocol,
+            self.username
+        );
+    }
+}
+
+/// How many times and how often an operation waiting for the modem is repeated.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryPolicy {
+    /// Maximum number of attempts.
+    pub attempts: u8,
+
+    /// Delay between two attempts.
+    pub delay: Duration,
+}
+
+/// Whether [`Modem::get_time`](crate::Modem::get_time) may attach to the LTE network to
+/// synchronize the modem clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockSyncPolicy {
+    /// Never waits for the network, fails with [`Error::ClockInvalid`](crate::Error::ClockInvalid)
+    /// if the clock is not synchronized.
+    NeverConnect,
+    /// Waits for the network to synchronize the clock, attaching to it if the modem is not
+    /// registered yet and detaching again afterwards.
+    #[default]
+    ConnectIfNeeded,
+    /// Waits for the network to synchronize the clock if the modem is registered, fails with
+    /// [`Error::ClockInvalid`](crate::Error::ClockInvalid) otherwise.
+    UseExisting,
+}
+
+/// Echo requests sent by [`Modem::icmp_ping`](crate::Modem::icmp_ping).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PingOptions {
+    /// Number of requests, capped at [`MAX_PING_COUNT`](crate::ping::responses::MAX_PING_COUNT).
+    pub count: u8,
+
+    /// Size of the data of the requests, in bytes.
+    pub size: u16,
+
+    /// Time waited for each reply, in whole seconds.
+    pub timeout: Duration,
+}
+
+impl Default for PingOptions {
+    fn default() -> Self {
+        Self {
+            count: 4,
+            size: 32,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Retries with an exponentially growing delay.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BackoffPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub attempts: u8,
+
+    /// Delay after the first failed attempt, doubled after every further one.
+    pub initial_delay: Duration,
+
+    /// Upper bound of the delay.
+    pub max_delay: Duration,
+}
+
+impl BackoffPolicy {
+    /// Returns the delay after the given failed attempt, counted from 0.
+    pub fn delay(&self, attempt: u8) -> Duration {
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        let delay = self.initial_delay.as_ticks().saturating_mul(factor as u64);
+        Duration::from_ticks(delay).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = BackoffPolicy {
+            attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+        assert_eq!(policy.delay(3), Duration::from_millis(500));
+        assert_eq!(policy.delay(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut config = ModemConfig::default().with_sim_pin(String::try_from("1234").unwrap());
+        config.pdp.auth = Some(PdpAuth {
+            protocol: PDPAuthProtocol::PAP,
+            username: PdpCredential::try_from("user").unwrap(),
+            password: PdpCredential::try_from("secret").unwrap(),
+        });
+
+        let debug = format!("{config:?}");
+        assert!(debug.contains("sim_pin: Some(\"<redacted>\")"));
+        assert!(debug.contains("username: \"user\""));
+        assert!(!debug.contains("1234"));
+        assert!(!debug.contains("secret"));
+    }
+}
@@ -0,0 +1,62 @@
+use core::future::Future;
+
+use embassy_futures::select::{Either, select};
+use embassy_time::{Duration, TimeoutError};
+use embedded_hal_async::delay::DelayNs;
+
+/// Delay provider of the [`Modem`](crate::Modem) when none is given, backed by the embassy-time
+/// driver.
+///
+/// Use [`Modem::new_with_delay`](crate::Modem::new_with_delay) to run the driver on executors
+/// without an embassy-time driver, e.g. RTIC with a monotonic implementing [`DelayNs`].
+pub type DefaultDelay = embassy_time::Delay;
+
+/// Waits for `duration` on the delay provider, with millisecond resolution.
+pub(crate) async fn sleep<D: DelayNs>(delay: &mut D, duration: Duration) {
+    let ms = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+    delay.delay_ms(ms).await
+}
+
+/// Runs `fut` until it completes, or fails with [`TimeoutError`] once `timeout` elapsed on the
+/// delay provider.
+pub(crate) async fn with_timeout<D: DelayNs, F: Future>(
+    delay: &mut D,
+    timeout: Duration,
+    fut: F,
+) -> Result<F::Output, TimeoutError> {
+    match select(fut, sleep(delay, timeout)).await {
+        Either::First(output) => Ok(output),
+        Either::Second(()) => Err(TimeoutError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::pending;
+
+    use embassy_futures::block_on;
+
+    use super::*;
+
+    /// Delay elapsing immediately.
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        let res = block_on(with_timeout(&mut NoDelay, Duration::from_secs(1), async {
+            42
+        }));
+        assert_eq!(res, Ok(42));
+
+        let res = block_on(with_timeout(
+            &mut NoDelay,
+            Duration::from_secs(1),
+            pending::<()>(),
+        ));
+        assert_eq!(res, Err(TimeoutError));
+    }
+}
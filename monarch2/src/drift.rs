@@ -0,0 +1,154 @@
+use embassy_time::{Duration, Instant};
+
+use crate::device::responses::Clock;
+
+/// Measures the drift of the modem clock against a monotonic clock of the host, e.g. to decide
+/// when to resynchronize the modem clock (network time, NTP, GNSS) after long PSM periods.
+///
+/// The first valid [`Clock`] sets the reference, the following ones report the drift since then:
+///
+/// ```ignore
+/// let mut monitor = ClockDriftMonitor::new();
+///
+/// loop {
+///     let clock = modem.get_time(ClockSyncPolicy::NeverConnect).await?;
+///     if let Some(drift) = monitor.update(&clock, Instant::now()) {
+///         if drift.exceeds(Duration::from_secs(2)) {
+///             modem.sync_time_ntp(&attached, "pool.ntp.org").await?;
+///             monitor.reset();
+///         }
+///     }
+///     Timer::after_secs(3600).await;
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockDriftMonitor {
+    /// Modem clock, in seconds since the Unix epoch, and host time of the reference sample.
+    reference: Option<(i64, Instant)>,
+}
+
+/// Drift of the modem clock reported by the [`ClockDriftMonitor`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockDrift {
+    /// Host time elapsed since the reference sample.
+    pub elapsed: Duration,
+
+    /// How far the modem clock is ahead (positive) or behind (negative) the host, in
+    /// milliseconds. The modem clock has a resolution of one second.
+    pub drift_ms: i64,
+}
+
+impl ClockDrift {
+    /// Drift rate in parts per million of the elapsed time, 0 if no time elapsed.
+    pub fn ppm(&self) -> i64 {
+        let elapsed_ms = self.elapsed.as_millis() as i64;
+        if elapsed_ms == 0 {
+            return 0;
+        }
+        self.drift_ms * 1_000_000 / elapsed_ms
+    }
+
+    /// Whether the modem clock drifted by more than `max` in either direction.
+    pub fn exceeds(&self, max: Duration) -> bool {
+        self.drift_ms.unsigned_abs() > max.as_millis()
+    }
+}
+
+impl ClockDriftMonitor {
+    pub const fn new() -> Self {
+        Self { reference: None }
+    }
+
+    /// Records the modem `clock` read at the host time `now`.
+    ///
+    /// Returns the drift since the reference sample, `None` for the reference sample itself or
+    /// when the clock is not synchronized.
+    pub fn update(&mut self, clock: &Clock, now: Instant) -> Option<ClockDrift> {
+        let modem = clock.unix_seconds()?;
+        let Some((reference_modem, reference_host)) = self.reference else {
+            self.reference = Some((modem, now));
+            return None;
+        };
+
+        let elapsed = now.saturating_duration_since(reference_host);
+        let modem_elapsed_ms = (modem - reference_modem) * 1000;
+        Some(ClockDrift {
+            elapsed,
+            drift_ms: modem_elapsed_ms - elapsed.as_millis() as i64,
+        })
+    }
+
+    /// Drops the reference sample, e.g. after the modem clock was resynchronized.
+    pub fn reset(&mut self) {
+        self.reference = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::device::responses::Time;
+
+    fn clock(time: &str) -> Clock {
+        Clock {
+            time: Time::from_str(time).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        let mut monitor = ClockDriftMonitor::new();
+        let start = Instant::from_secs(100);
+
+        assert_eq!(monitor.update(&clock("25/01/01,00:00:00+00"), start), None);
+
+        let drift = monitor
+            .update(
+                &clock("25/01/01,01:00:05+00"),
+                start + Duration::from_secs(3600),
+            )
+            .unwrap();
+        assert_eq!(drift.elapsed, Duration::from_secs(3600));
+        assert_eq!(drift.drift_ms, 5000);
+        assert_eq!(drift.ppm(), 1388);
+        assert!(drift.exceeds(Duration::from_secs(2)));
+        assert!(!drift.exceeds(Duration::from_secs(5)));
+
+        let drift = monitor
+            .update(
+                &clock("25/01/01,00:59:58+00"),
+                start + Duration::from_secs(3600),
+            )
+            .unwrap();
+        assert_eq!(drift.drift_ms, -2000);
+    }
+
+    #[test]
+    fn test_invalid_clock_ignored() {
+        let mut monitor = ClockDriftMonitor::new();
+
+        assert_eq!(
+            monitor.update(&clock("70/01/01,00:00:00+00"), Instant::from_secs(0)),
+            None
+        );
+        assert_eq!(
+            monitor.update(&clock("25/01/01,00:00:00+00"), Instant::from_secs(0)),
+            None
+        );
+        assert!(
+            monitor
+                .update(&clock("25/01/01,00:00:10+00"), Instant::from_secs(10))
+                .is_some()
+        );
+
+        monitor.reset();
+        assert_eq!(
+            monitor.update(&clock("25/01/01,00:00:20+00"), Instant::from_secs(20)),
+            None
+        );
+    }
+}
@@ -1,23 +1,668 @@
+use core::fmt;
+
+use atat::{
+    AtatCmd,
+    digest::{DigestResult, Digester},
+};
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex};
+
+#[cfg(feature = "coap")]
+use crate::coap::types::ResponseCode;
+#[cfg(feature = "gnss")]
+use crate::gnss::types::GnssError;
+#[cfg(feature = "lwm2m")]
+use crate::lwm2m::types::ClientEvent;
+#[cfg(feature = "mqtt")]
 use crate::mqtt::types::MQTTStatusCode;
+#[cfg(feature = "mqtt")]
+use crate::{aws::AwsIotError, azure::AzureIotError};
+use crate::{
+    mobile_equipment::types::PowerState,
+    modem::ModemState,
+    sim::{esim::ProfileOperationResult, types::SIMState},
+};
 
 #[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
-    AT(atat::Error),
-    Timeout(embassy_time::TimeoutError),
+    /// The command failed, e.g. its response timed out or couldn't be parsed.
+    AT(atat::Error, CommandName),
+    /// The modem rejected the command with a `+CME ERROR`.
+    Cme(CmeError, CommandName),
+    /// The modem rejected the SMS command with a `+CMS ERROR`.
+    Cms(CmsError, CommandName),
+    /// The operation didn't complete in time.
+    Timeout(Operation),
+    /// The modem clock couldn't be synchronized with the network or an NTP server.
     ClockSynchronization,
-    MQTT(MQTTStatusCode),
+    /// The modem clock is not synchronized and the [`ClockSyncPolicy`](crate::ClockSyncPolicy)
+    /// doesn't permit waiting for the network.
+    ClockInvalid,
+    /// The broker refused the MQTT connection.
+    #[cfg(feature = "mqtt")]
+    MqttConnect(MQTTStatusCode),
+    /// The MQTT message `pmid` (the id given by the modem) couldn't be published.
+    #[cfg(feature = "mqtt")]
+    MqttPublish { pmid: u16, rc: MQTTStatusCode },
+    /// The subscription to an MQTT topic was rejected.
+    #[cfg(feature = "mqtt")]
+    MqttSubscribe(MQTTStatusCode),
+    /// The [`AwsIotConfig`](crate::AwsIotConfig) doesn't meet the requirements of AWS IoT.
+    #[cfg(feature = "mqtt")]
+    AwsIot(AwsIotError),
+    /// The [`AzureIotConfig`](crate::AzureIotConfig) or its credentials don't meet the
+    /// requirements of Azure IoT Hub.
+    #[cfg(feature = "mqtt")]
+    AzureIot(AzureIotError),
+    /// The CoAP server answered with an error response code.
+    #[cfg(feature = "coap")]
+    Coap(ResponseCode),
+    /// The LwM2M client couldn't register, the event tells whether the bootstrap or the
+    /// registration failed.
+    #[cfg(feature = "lwm2m")]
+    Lwm2mRegistration(ClientEvent),
+    /// The modem couldn't program the GNSS fix.
+    #[cfg(feature = "gnss")]
+    Gnss(GnssError),
+    /// MQTT message notifications were dropped because the [`MqttInbox`](crate::MqttInbox) was
+    /// full.
+    #[cfg(feature = "mqtt")]
+    MqttInboxOverflow,
+    /// The SIM card is locked and waits for the given password to be entered.
+    SimPinRequired(SIMState),
+    /// The SIM card is in a different state than the operation requires.
+    UnexpectedSimState(SIMState),
+    /// The entered SIM password (PIN/PUK) is incorrect.
+    SimIncorrectPassword,
+    /// The SIM card is permanently blocked, no PUK attempts remain.
+    SimBlocked,
+    /// An eSIM profile operation was rejected by the eUICC.
+    Esim(ProfileOperationResult),
+    /// The raw command doesn't fit into [`MAX_RAW_CMD_LEN`](crate::raw::MAX_RAW_CMD_LEN), the
+    /// host name to resolve exceeds [`HOSTNAME_LEN`](crate::dns::HOSTNAME_LEN), or the host or the
+    /// resource of an HTTP request is too long.
+    CommandTooLong,
+    /// The data written exceeds the size announced to the modem, see
+    /// [`PromptWriter`](crate::PromptWriter), the MQTT payload exceeds
+    /// [`MQTT_PAYLOAD_LEN`](crate::mqtt::MQTT_PAYLOAD_LEN), the LwM2M resource value exceeds
+    /// [`LWM2M_VALUE_LEN`](crate::lwm2m::LWM2M_VALUE_LEN), or the NIDD payload exceeds
+    /// [`NIDD_PAYLOAD_LEN`](crate::nidd::NIDD_PAYLOAD_LEN).
+    PayloadTooLong,
+    /// The NVM index is reserved for the internal use of the modem.
+    ReservedNvmIndex(u8),
+    /// The modem restarted since it was initialized, [`Modem::begin`](crate::Modem::begin) must be
+    /// called again.
+    ModemRebooted,
+    /// The operation requires the modem to be in a different power state.
+    UnexpectedPowerState {
+        expected: PowerState,
+        actual: PowerState,
+    },
 }
 
-impl From<atat::Error> for Error {
-    fn from(err: atat::Error) -> Self {
-        Error::AT(err)
+impl Error {
+    /// Wraps the error of the `Cmd` command.
+    pub(crate) fn command<Cmd: AtatCmd>(err: atat::Error) -> Self {
+        let command = CommandName::of::<Cmd>();
+        match err {
+            atat::Error::CmeError(err) => Error::Cme(CmeError::from(err as u16), command),
+            atat::Error::CmsError(err) => Error::Cms(CmsError::from(err as u16), command),
+            err => Error::AT(err, command),
+        }
+    }
+
+    /// Restores the `+CME ERROR` code recorded by the [`CmeCodeDigester`], `atat` only keeps the
+    /// codes it knows.
+    pub(crate) fn with_cme_code(self, code: Option<u16>) -> Self {
+        match (self, code) {
+            (Error::Cme(_, command), Some(code)) => Error::Cme(CmeError::from(code), command),
+            (err, _) => err,
+        }
+    }
+
+    /// Returns the command that failed, if the error was reported for a command.
+    pub fn failed_command(&self) -> Option<CommandName> {
+        match self {
+            Error::AT(_, command) | Error::Cme(_, command) | Error::Cms(_, command) => {
+                Some(*command)
+            }
+            _ => None,
+        }
     }
+
+    /// Classifies the error, see [`ErrorClass`].
+    pub fn class(&self) -> ErrorClass {
+        // Without network service, only a new registration helps, not repeating the command.
+        if matches!(
+            self,
+            Error::Cme(CmeError::NoNetworkService, _) | Error::Cms(CmsError::NoNetworkService, _)
+        ) {
+            return ErrorClass::StateDependent;
+        }
+
+        let transient = match self {
+            Error::AT(err, _) => matches!(
+                err,
+                atat::Error::Timeout
+                    | atat::Error::Read
+                    | atat::Error::Write
+                    | atat::Error::Aborted
+            ),
+            Error::Cme(err, _) => matches!(
+                err,
+                CmeError::SimBusy
+                    | CmeError::NetworkTimeout
+                    | CmeError::ServiceOptionTemporarilyOutOfOrder
+            ),
+            Error::Cms(err, _) => matches!(err, CmsError::SimBusy | CmsError::NetworkTimeout),
+            Error::Timeout(_) | Error::ClockSynchronization => true,
+            #[cfg(feature = "mqtt")]
+            Error::MqttConnect(rc) | Error::MqttPublish { rc, .. } | Error::MqttSubscribe(rc) => {
+                matches!(
+                    rc,
+                    MQTTStatusCode::NoMem
+                        | MQTTStatusCode::NoConn
+                        | MQTTStatusCode::ConnLost
+                        | MQTTStatusCode::Eai
+                        | MQTTStatusCode::Unavailable
+                )
+            }
+            #[cfg(feature = "coap")]
+            Error::Coap(code) => {
+                *code == ResponseCode::SERVICE_UNAVAILABLE || *code == ResponseCode::GATEWAY_TIMEOUT
+            }
+            #[cfg(feature = "gnss")]
+            Error::Gnss(err) => *err == GnssError::FixInProgress,
+            // Only the dropped notifications are lost, the next receive succeeds.
+            #[cfg(feature = "mqtt")]
+            Error::MqttInboxOverflow => true,
+            _ => false,
+        };
+
+        if transient {
+            ErrorClass::Transient
+        } else {
+            ErrorClass::Permanent
+        }
+    }
+
+    /// Whether the operation may succeed if repeated later, see [`ErrorClass::Transient`] and
+    /// [`ErrorClass::StateDependent`].
+    pub fn is_transient(&self) -> bool {
+        self.class() != ErrorClass::Permanent
+    }
+
+    /// Whether the modem rejected the command only temporarily and it can be repeated as is: a
+    /// `+CME ERROR` of the [`ErrorClass::Transient`] class.
+    pub(crate) fn is_transient_cme(&self) -> bool {
+        matches!(self, Error::Cme(..)) && self.class() == ErrorClass::Transient
+    }
+}
+
+/// Whether an [`Error`] is worth retrying, e.g. to write generic retry loops:
+///
+/// ```ignore
+/// let attached = loop {
+///     let detached = modem.lte_force_disconnect(None).await?;
+///     match modem.lte_connect(detached).await {
+///         Err(err) if err.is_transient() => Timer::after_secs(5).await,
+///         res => break res?,
+///     }
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorClass {
+    /// A temporary condition, e.g. a timeout, a busy SIM or a network failure. Repeating the
+    /// operation later may succeed.
+    Transient,
+    /// The operation fails in the current state of the modem, e.g. without network service, and
+    /// succeeds once the state changes, e.g. after a new registration to the network.
+    StateDependent,
+    /// The operation fails until the cause is fixed, e.g. invalid parameters, a blocked SIM or a
+    /// modem that must be initialized again.
+    Permanent,
 }
 
-impl From<embassy_time::TimeoutError> for Error {
-    fn from(err: embassy_time::TimeoutError) -> Self {
-        Error::Timeout(err)
+/// Operation that timed out, see [`Error::Timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Operation {
+    /// The response to a command sent with a timeout, e.g. with
+    /// [`Modem::send_with_timeout`](crate::Modem::send_with_timeout).
+    Command(CommandName),
+    /// A URC awaited with [`Modem::wait_for`](crate::Modem::wait_for) or
+    /// [`Modem::send_and_wait_for`](crate::Modem::send_and_wait_for).
+    Urc,
+    /// The registration to the LTE network, or the deregistration.
+    NetworkRegistration,
+    /// The start notification of the modem after a reset.
+    Startup,
+    /// The wake-up of the modem from a sleep state, see
+    /// [`ModemState::set_asleep`](crate::ModemState::set_asleep).
+    Wake,
+    /// The synchronization of the modem clock with an NTP server.
+    NtpSync,
+    /// The acceptance of the MQTT connection by the broker.
+    #[cfg(feature = "mqtt")]
+    MqttConnect,
+    /// The completion of an MQTT publication.
+    #[cfg(feature = "mqtt")]
+    MqttPublish,
+    /// The acceptance of an MQTT subscription by the broker.
+    #[cfg(feature = "mqtt")]
+    MqttSubscribe,
+    /// The response to an HTTP request.
+    #[cfg(feature = "http")]
+    HttpResponse,
+    /// The registration of the LwM2M client to its server.
+    #[cfg(feature = "lwm2m")]
+    Lwm2mRegistration,
+    /// A GNSS fix.
+    #[cfg(feature = "gnss")]
+    GnssFix,
+    /// A payload received on a Non-IP context.
+    #[cfg(feature = "nidd")]
+    NiddReceive,
+}
+
+/// AT command that failed, identified by the name of its type, e.g. `EnterPin`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CommandName(&'static str);
+
+impl CommandName {
+    pub(crate) fn of<Cmd: AtatCmd>() -> Self {
+        let path = core::any::type_name::<Cmd>();
+        // Strip the module path and the generic parameters.
+        let path = path.split('<').next().unwrap_or(path);
+        CommandName(path.rsplit("::").next().unwrap_or(path))
+    }
+
+    /// Returns the name of the command type.
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Debug for CommandName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CommandName {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.0)
+    }
+}
+
+/// Defines an error code enum, keeping the unlisted codes as `Other`.
+macro_rules! error_codes {
+    (
+        $(#[$meta:meta])*
+        pub enum $enum:ident {
+            $($(#[$doc:meta])* $code:literal => $name:ident,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        #[non_exhaustive]
+        pub enum $enum {
+            $($(#[$doc])* $name,)*
+            /// Any other code.
+            Other(u16),
+        }
+
+        impl $enum {
+            /// Returns the numeric code of the error.
+            pub fn code(&self) -> u16 {
+                match self {
+                    $($enum::$name => $code,)*
+                    $enum::Other(code) => *code,
+                }
+            }
+        }
+
+        impl From<u16> for $enum {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => $enum::$name,)*
+                    code => $enum::Other(code),
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Error reported by the modem with `+CME ERROR: <code>`.
+    ///
+    /// Covers the 3GPP TS 27.007 codes used by the Monarch 2 platform and the Sequans
+    /// extensions, the other codes are kept as [`CmeError::Other`]. The codes are only
+    /// reported with the default
+    /// [`CMEErrorReports::Numeric`](crate::system_features::types::CMEErrorReports::Numeric)
+    /// configuration.
+    ///
+    /// `atat` reports the codes outside of 3GPP TS 27.007 as [`CmeError::Unknown`], the ingress
+    /// needs a [`CmeCodeDigester`] to tell e.g. [`CmeError::DeviceActive`].
+    pub enum CmeError {
+        0 => PhoneFailure,
+        3 => OperationNotAllowed,
+        4 => OperationNotSupported,
+        /// PH-SIM PIN required.
+        5 => PhSimPinRequired,
+        10 => SimNotInserted,
+        11 => SimPinRequired,
+        12 => SimPukRequired,
+        /// SIM failure, also reported once all the PUK attempts are used up.
+        13 => SimFailure,
+        /// The SIM is busy, the command can be repeated.
+        14 => SimBusy,
+        15 => SimWrong,
+        /// The entered password (PIN/PUK) is incorrect.
+        16 => IncorrectPassword,
+        17 => SimPin2Required,
+        18 => SimPuk2Required,
+        20 => MemoryFull,
+        21 => InvalidIndex,
+        22 => NotFound,
+        23 => MemoryFailure,
+        24 => TextStringTooLong,
+        25 => InvalidCharactersInTextString,
+        30 => NoNetworkService,
+        31 => NetworkTimeout,
+        32 => NetworkNotAllowed,
+        50 => IncorrectParameters,
+        100 => Unknown,
+        103 => IllegalMs,
+        106 => IllegalMe,
+        107 => GprsServicesNotAllowed,
+        111 => PlmnNotAllowed,
+        112 => LocationAreaNotAllowed,
+        113 => RoamingNotAllowed,
+        132 => ServiceOptionNotSupported,
+        133 => ServiceOptionNotSubscribed,
+        134 => ServiceOptionTemporarilyOutOfOrder,
+        148 => UnspecifiedGprsError,
+        149 => PdpAuthenticationFailure,
+        150 => InvalidMobileClass,
+        /// Sequans: the dual mode (LTE-M / NB-IoT) is not configured.
+        589 => DualModeNotConfigured,
+        /// Sequans: the operation is not possible while the device is active, e.g. attached.
+        591 => DeviceActive,
+    }
+}
+
+error_codes! {
+    /// Error reported by the modem with `+CMS ERROR: <code>` for the SMS operations.
+    ///
+    /// Covers the 3GPP TS 27.005 codes, the other codes (e.g. the network failure causes below
+    /// 300) are kept as [`CmsError::Other`].
+    pub enum CmsError {
+        300 => MeFailure,
+        301 => SmsServiceReserved,
+        302 => OperationNotAllowed,
+        303 => OperationNotSupported,
+        /// The PDU is invalid.
+        304 => InvalidPduModeParameter,
+        305 => InvalidTextModeParameter,
+        310 => SimNotInserted,
+        311 => SimPinRequired,
+        312 => PhSimPinRequired,
+        313 => SimFailure,
+        /// The SIM is busy, the command can be repeated.
+        314 => SimBusy,
+        315 => SimWrong,
+        316 => SimPukRequired,
+        317 => SimPin2Required,
+        318 => SimPuk2Required,
+        320 => MemoryFailure,
+        321 => InvalidMemoryIndex,
+        /// The message storage is full.
+        322 => MemoryFull,
+        330 => SmscAddressUnknown,
+        /// The modem is not registered to a network providing the SMS service.
+        331 => NoNetworkService,
+        332 => NetworkTimeout,
+        340 => NoCnmaAcknowledgementExpected,
+        500 => Unknown,
+    }
+}
+
+/// [`Digester`] wrapper recording the code of the `+CME ERROR` responses in the [`ModemState`],
+/// so that the commands fail with the [`CmeError`] reported by the modem.
+///
+/// `atat` collapses the codes it doesn't know (e.g. the Sequans extensions) into
+/// [`CmeError::Unknown`], the code is lost without this wrapper.
+///
+/// With the `unknown-urc` feature, it also passes the `+<NAME>: ...` lines the wrapped digester
+/// doesn't know as URCs while no command waits for its response, see
+/// [`UrcParser`](crate::UrcParser).
+///
+/// ```ignore
+/// let digester = CmeCodeDigester::new(AtDigester::<Urc>::new(), &STATE);
+/// let ingress = Ingress::new(digester, &mut res_buf, &RES_SLOT, &URC_CHANNEL);
+/// ```
+pub struct CmeCodeDigester<'a, D, M: RawMutex = CriticalSectionRawMutex> {
+    digester: D,
+    state: &'a ModemState<M>,
+}
+
+impl<'a, D, M: RawMutex> CmeCodeDigester<'a, D, M> {
+    pub fn new(digester: D, state: &'a ModemState<M>) -> Self {
+        Self { digester, state }
+    }
+}
+
+impl<D: Digester, M: RawMutex> Digester for CmeCodeDigester<'_, D, M> {
+    fn digest<'a>(&mut self, buf: &'a [u8]) -> (DigestResult<'a>, usize) {
+        let (result, len) = self.digester.digest(buf);
+        if let DigestResult::Response(Err(_)) = result {
+            if let Some(code) = parse_cme_code(&buf[..len]) {
+                self.state.set_cme_code(code);
+            }
+        }
+        // The digester only matches the known URC prefixes, an unknown URC would stay in the
+        // buffer and be taken for the information text of the next response.
+        #[cfg(feature = "unknown-urc")]
+        if matches!(result, DigestResult::None) && !self.state.is_command_pending() {
+            if let Some((line, len)) = unknown_urc(buf) {
+                return (DigestResult::Urc(line), len);
+            }
+        }
+        (result, len)
+    }
+}
+
+/// Matches a complete `+<NAME>: ...` line at the start of `buf`, returning it without the line
+/// breaks and the length it takes in `buf`.
+///
+/// Only called while no command waits for its response, the information text of a response
+/// looks the same.
+#[cfg(feature = "unknown-urc")]
+fn unknown_urc(buf: &[u8]) -> Option<(&[u8], usize)> {
+    let line = buf.trim_ascii_start();
+    let start = buf.len() - line.len();
+    let end = line.windows(2).position(|w| w == b"\r\n")?;
+    let line = &line[..end];
+
+    let name = line.strip_prefix(b"+")?;
+    let name_len = name
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric())
+        .count();
+    (name_len > 0 && name.get(name_len) == Some(&b':')).then_some((line, start + end + 2))
+}
+
+/// Parses the code of a `+CME ERROR: <code>` response.
+fn parse_cme_code(response: &[u8]) -> Option<u16> {
+    const PREFIX: &[u8] = b"+CME ERROR:";
+    let start = response.windows(PREFIX.len()).position(|w| w == PREFIX)? + PREFIX.len();
+    let code = response[start..].trim_ascii_start();
+    let len = code.iter().take_while(|b| b.is_ascii_digit()).count();
+    core::str::from_utf8(&code[..len]).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use atat::AtDigester;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+    use super::*;
+    use crate::command::Urc;
+
+    #[test]
+    fn test_cme_error_codes() {
+        assert_eq!(CmeError::from(14), CmeError::SimBusy);
+        assert_eq!(CmeError::from(589), CmeError::DualModeNotConfigured);
+        assert_eq!(CmeError::from(591).code(), 591);
+        assert_eq!(CmeError::from(9999), CmeError::Other(9999));
+        assert_eq!(CmeError::Other(9999).code(), 9999);
+    }
+
+    #[test]
+    fn test_cms_error_codes() {
+        assert_eq!(CmsError::from(322), CmsError::MemoryFull);
+        assert_eq!(CmsError::NoNetworkService.code(), 331);
+        assert_eq!(CmsError::from(27), CmsError::Other(27));
+    }
+
+    #[test]
+    fn test_error_class() {
+        let command = CommandName::of::<crate::sim::EnterPin>();
+        assert!(Error::Cme(CmeError::SimBusy, command).is_transient());
+        assert!(Error::AT(atat::Error::Timeout, command).is_transient());
+        assert_eq!(
+            Error::Cme(CmeError::IncorrectPassword, command).class(),
+            ErrorClass::Permanent
+        );
+        assert_eq!(Error::SimBlocked.class(), ErrorClass::Permanent);
+        assert_eq!(Error::ModemRebooted.class(), ErrorClass::Permanent);
+        assert_eq!(
+            Error::Cme(CmeError::NoNetworkService, command).class(),
+            ErrorClass::StateDependent
+        );
+        assert!(Error::Cms(CmsError::NoNetworkService, command).is_transient());
+    }
+
+    #[test]
+    fn test_transient_cme() {
+        let command = CommandName::of::<crate::sim::EnterPin>();
+        assert!(Error::Cme(CmeError::SimBusy, command).is_transient_cme());
+        assert!(Error::Cme(CmeError::NetworkTimeout, command).is_transient_cme());
+        assert!(!Error::Cme(CmeError::IncorrectPassword, command).is_transient_cme());
+        // Repeating the command doesn't help without network service.
+        assert!(!Error::Cme(CmeError::NoNetworkService, command).is_transient_cme());
+        // Transient, but the command isn't repeated for other errors than `+CME ERROR`.
+        assert!(!Error::AT(atat::Error::Timeout, command).is_transient_cme());
+        assert!(!Error::Cms(CmsError::SimBusy, command).is_transient_cme());
+    }
+
+    #[test]
+    fn test_parse_cme_code() {
+        assert_eq!(parse_cme_code(b"\r\n+CME ERROR: 589\r\n"), Some(589));
+        assert_eq!(
+            parse_cme_code(b"AT+CFUN=5\r\r\n+CME ERROR:14\r\n"),
+            Some(14)
+        );
+        assert_eq!(parse_cme_code(b"\r\n+CME ERROR: SIM busy\r\n"), None);
+        assert_eq!(parse_cme_code(b"\r\nERROR\r\n"), None);
+    }
+
+    #[test]
+    fn test_cme_code_digester() {
+        let state = ModemState::<NoopRawMutex>::new();
+        let mut digester = CmeCodeDigester::new(AtDigester::<Urc>::new(), &state);
+
+        let (result, _) = digester.digest(b"\r\n+CME ERROR: 591\r\n");
+        assert!(matches!(result, DigestResult::Response(Err(_))));
+        assert_eq!(state.take_cme_code(), Some(591));
+
+        digester.digest(b"\r\nOK\r\n");
+        assert_eq!(state.take_cme_code(), None);
+    }
+
+    #[cfg(feature = "unknown-urc")]
+    #[test]
+    fn test_unknown_urc_ingress() {
+        use atat::{AtatIngress, Ingress, ResponseSlot};
+
+        use crate::{
+            channel::{DefaultUrcChannel, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS},
+            command::UrcParser,
+        };
+
+        let state = ModemState::<NoopRawMutex>::new();
+        let urc_chan = DefaultUrcChannel::new();
+        let res_slot = ResponseSlot::<INGRESS_BUF_SIZE>::new();
+        let mut buf = [0; INGRESS_BUF_SIZE];
+        let mut ingress: Ingress<_, UrcParser, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS> =
+            Ingress::new(
+                CmeCodeDigester::new(AtDigester::<Urc>::new(), &state),
+                &mut buf,
+                &res_slot,
+                &urc_chan,
+            );
+        let mut urcs = urc_chan.subscribe().unwrap();
+
+        // Split across two writes, like bytes arriving from the UART.
+        ingress.try_write(b"\r\n+SQNSFOO: 1,").unwrap();
+        assert!(urcs.try_next_message_pure().is_none());
+        ingress.try_write(b"\"bar\"\r\n\r\n+SYSSTART\r\n").unwrap();
+        match urcs.try_next_message_pure() {
+            Some(Urc::Unknown(line)) => assert_eq!(line, "+SQNSFOO: 1,\"bar\""),
+            _ => panic!("unexpected URC"),
+        }
+        assert!(matches!(urcs.try_next_message_pure(), Some(Urc::Start)));
+
+        // The information text of a pending command is left to its response.
+        state.set_command_pending(true);
+        ingress.try_write(b"\r\n+SQNSFOO: 2\r\n").unwrap();
+        assert!(urcs.try_next_message_pure().is_none());
+        ingress.try_write(b"\r\nOK\r\n").unwrap();
+        assert!(urcs.try_next_message_pure().is_none());
+        assert!(res_slot.try_get().is_some());
+    }
+
+    #[cfg(feature = "unknown-urc")]
+    #[test]
+    fn test_unknown_urc_line() {
+        assert_eq!(
+            unknown_urc(b"\r\n+SQNSFOO: 1\r\nOK"),
+            Some((b"+SQNSFOO: 1".as_slice(), 15))
+        );
+        assert_eq!(unknown_urc(b"\r\n+SQNSFOO: 1"), None);
+        assert_eq!(unknown_urc(b"\r\nOK\r\n"), None);
+        assert_eq!(unknown_urc(b"\r\n+: 1\r\n"), None);
+        assert_eq!(unknown_urc(b"\r\n+SQNSFOO\r\n"), None);
+    }
+
+    #[test]
+    fn test_with_cme_code() {
+        let command = CommandName::of::<crate::sim::EnterPin>();
+        assert_eq!(
+            Error::Cme(CmeError::Unknown, command).with_cme_code(Some(589)),
+            Error::Cme(CmeError::DualModeNotConfigured, command)
+        );
+        assert_eq!(
+            Error::Cme(CmeError::SimBusy, command).with_cme_code(None),
+            Error::Cme(CmeError::SimBusy, command)
+        );
+        assert_eq!(
+            Error::AT(atat::Error::Timeout, command).with_cme_code(Some(14)),
+            Error::AT(atat::Error::Timeout, command)
+        );
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(CommandName::of::<crate::sim::EnterPin>().name(), "EnterPin");
+        assert_eq!(
+            CommandName::of::<crate::raw::RawCommand<'_, 16>>().name(),
+            "RawCommand"
+        );
     }
 }
@@ -0,0 +1,63 @@
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, pubsub::Subscriber};
+
+#[cfg(feature = "gnss")]
+use crate::gnss::urc::GnssFixReady;
+#[cfg(feature = "lwm2m")]
+use crate::lwm2m::{self, types::ClientEvent};
+#[cfg(feature = "mqtt")]
+use crate::mqtt::{self, types::MQTTStatusCode};
+use crate::{network::types::NetworkRegistrationState, sim::types::SIMState};
+
+/// Number of events buffered for each subscriber, the oldest are dropped when a subscriber lags.
+pub const EVENT_CAPACITY: usize = 8;
+
+/// Maximum number of concurrent [`Modem::events`](crate::Modem::events) subscribers.
+pub const EVENT_SUBSCRIBERS: usize = 2;
+
+/// Stream of [`ModemEvent`]s returned by [`Modem::events`](crate::Modem::events).
+pub type ModemEvents<'a, M = CriticalSectionRawMutex> =
+    Subscriber<'a, M, ModemEvent, EVENT_CAPACITY, EVENT_SUBSCRIBERS, 0>;
+
+/// Event reported by the modem, published by the [`UrcHandler`](crate::UrcHandler).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+#[allow(clippy::large_enum_variant)]
+pub enum ModemEvent {
+    /// The network registration state changed.
+    RegistrationChanged(NetworkRegistrationState),
+    /// The MQTT connection was established or refused.
+    #[cfg(feature = "mqtt")]
+    MqttConnected(MQTTStatusCode),
+    /// The MQTT connection was closed.
+    #[cfg(feature = "mqtt")]
+    MqttDisconnected(MQTTStatusCode),
+    /// A message was received on a subscribed MQTT topic.
+    #[cfg(feature = "mqtt")]
+    MqttMessageReceived(mqtt::urc::Received),
+    /// The state of the LwM2M client changed, e.g. it registered to its server.
+    #[cfg(feature = "lwm2m")]
+    Lwm2m(ClientEvent),
+    /// The LwM2M server wrote a resource.
+    #[cfg(feature = "lwm2m")]
+    Lwm2mResourceWritten(lwm2m::urc::ResourceWritten),
+    /// The LwM2M server executed a resource, e.g. a reboot.
+    #[cfg(feature = "lwm2m")]
+    Lwm2mResourceExecuted(lwm2m::urc::ResourceExecuted),
+    /// A GNSS fix is available.
+    #[cfg(feature = "gnss")]
+    GnssFix(GnssFixReady),
+    /// The SIM state was read, e.g. by [`Modem::get_pin_status`](crate::Modem::get_pin_status).
+    SimState(SIMState),
+    /// The modem is about to shut down or restart.
+    Shutdown,
+    /// The modem (re)started and is ready to operate.
+    ///
+    /// The state cached by the driver (registration, MQTT and CoAP sessions) is reset, the next
+    /// command fails with [`Error::ModemRebooted`](crate::Error::ModemRebooted) if the driver was
+    /// initialized.
+    Started,
+    /// The given number of URCs were lost because the [`UrcHandler`](crate::UrcHandler) lagged,
+    /// see [`ModemState::dropped_urcs`](crate::ModemState::dropped_urcs).
+    UrcsDropped(u64),
+}
@@ -142,3 +142,27 @@ macro_rules! unwrap {
         }
     }
 }
+
+/// Implements `Debug` and `defmt::Format` printing only the type name, for the large types whose
+/// derived implementations are only kept with the `verbose-fmt` feature. `defmt` only implements
+/// `defmt::Format`, e.g. for the commands, which don't implement `Debug`.
+macro_rules! compact_fmt {
+    (defmt $ty:ident $(<$lt:lifetime>)?) => {
+        #[cfg(all(feature = "defmt", not(feature = "verbose-fmt")))]
+        impl $(<$lt>)? ::defmt::Format for $ty $(<$lt>)? {
+            fn format(&self, f: ::defmt::Formatter) {
+                ::defmt::write!(f, "{=str} {{ .. }}", ::core::stringify!($ty));
+            }
+        }
+    };
+    ($ty:ident $(<$lt:lifetime>)?) => {
+        #[cfg(not(any(test, feature = "verbose-fmt")))]
+        impl $(<$lt>)? ::core::fmt::Debug for $ty $(<$lt>)? {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(::core::stringify!($ty)).finish_non_exhaustive()
+            }
+        }
+
+        compact_fmt!(defmt $ty $(<$lt>)?);
+    };
+}
@@ -0,0 +1,127 @@
+//! Helpers to reuse the command and response definitions in host tools, e.g. provisioning
+//! scripts or log decoders.
+
+use std::string::String;
+use std::vec::{self, Vec};
+
+use atat::{
+    AtDigester, AtatCmd, AtatUrc,
+    digest::{DigestResult, Digester},
+};
+
+use crate::command::{Urc, UrcParser};
+
+/// Serializes a command into the line sent to the modem, including the termination.
+///
+/// Fails for commands carrying binary payloads that are not valid UTF-8.
+pub fn to_at_string<Cmd: AtatCmd>(cmd: &Cmd) -> Result<String, std::string::FromUtf8Error> {
+    let mut buf = vec![0; Cmd::MAX_LEN];
+    let len = cmd.write(&mut buf);
+    buf.truncate(len);
+    String::from_utf8(buf)
+}
+
+/// Parses the response to `cmd`, without the final result code, e.g. as found in a log.
+pub fn parse_response<Cmd: AtatCmd>(cmd: &Cmd, line: &str) -> Result<Cmd::Response, atat::Error> {
+    cmd.parse(Ok(line.as_bytes()))
+}
+
+/// Parses an unsolicited result code, e.g. as found in a log. An unknown one is returned as
+/// `Urc::Unknown` with the `unknown-urc` feature.
+pub fn parse_urc(line: &str) -> Option<Urc> {
+    UrcParser::parse(line.as_bytes())
+}
+
+/// Feeds a recorded modem byte stream through the digester of the ingress and returns the URCs
+/// parsed from it, e.g. to turn device logs into regression tests.
+///
+/// The `chunks` are fed one after the other like the UART delivers them, so lines may be split
+/// anywhere. The responses to commands interleaved with the URCs are skipped. A line recognized as
+/// a URC but failing to parse stops the replay, the line is returned as the error.
+///
+/// ```ignore
+/// let trace = include_bytes!("boot.log");
+/// let urcs = replay_urcs(trace.chunks(16))?;
+/// ```
+pub fn replay_urcs<'c>(chunks: impl IntoIterator<Item = &'c [u8]>) -> Result<Vec<Urc>, String> {
+    let mut digester = AtDigester::<Urc>::new();
+    let mut buf = Vec::new();
+    let mut urcs = Vec::new();
+
+    for chunk in chunks {
+        buf.extend_from_slice(chunk);
+        loop {
+            let (result, swallowed) = digester.digest(&buf);
+            if let DigestResult::Urc(line) = result {
+                let urc = UrcParser::parse(line)
+                    .ok_or_else(|| String::from_utf8_lossy(line).into_owned())?;
+                urcs.push(urc);
+            }
+            if swallowed == 0 {
+                break;
+            }
+            buf.drain(..swallowed);
+        }
+    }
+
+    Ok(urcs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::AT, mobile_equipment::GetSignalQuality, network::types::NetworkRegistrationState,
+        pdp::GetPDPAddress,
+    };
+
+    #[test]
+    fn test_to_at_string() {
+        assert_eq!(to_at_string(&AT).unwrap(), "AT\r\n");
+        assert_eq!(
+            to_at_string(&GetPDPAddress { cid: 1 }).unwrap(),
+            "AT+CGPADDR=1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let res = parse_response(&GetSignalQuality, "+CSQ: 17,99").unwrap();
+        assert_eq!(res.rssi, 17);
+    }
+
+    #[test]
+    fn test_parse_urc() {
+        match parse_urc("+CEREG: 5") {
+            Some(Urc::NetworkRegistrationStatus(status)) => {
+                assert_eq!(status.stat, NetworkRegistrationState::RegisteredRoaming);
+            }
+            _ => panic!("unexpected URC"),
+        }
+    }
+
+    #[test]
+    fn test_replay_urcs() {
+        let trace: &[u8] = b"\r\n+SYSSTART\r\n\
+            \r\n+CEREG: 2\r\n\
+            \r\n+CSQ: 17,99\r\n\r\nOK\r\n\
+            \r\n+CEREG: 1,\"2F0A\",\"01A2D001\",7\r\n";
+
+        // Whole stream, then fragmented down to single bytes.
+        for chunk_size in [trace.len(), 7, 1] {
+            let urcs = replay_urcs(trace.chunks(chunk_size)).unwrap();
+            assert_eq!(urcs.len(), 3, "chunk size {chunk_size}");
+            assert!(matches!(urcs[0], Urc::Start));
+            assert!(matches!(
+                &urcs[1],
+                Urc::NetworkRegistrationStatus(status)
+                    if status.stat == NetworkRegistrationState::Searching
+            ));
+            assert!(matches!(
+                &urcs[2],
+                Urc::NetworkRegistrationStatus(status)
+                    if status.stat == NetworkRegistrationState::RegisteredHome
+            ));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use core::cell::RefCell;
+
+use embassy_sync::{
+    blocking_mutex::{Mutex, raw::RawMutex},
+    signal::Signal,
+};
+use heapless::Deque;
+
+use crate::{error::Error, mqtt::urc::Received};
+
+/// Number of MQTT message notifications kept by the [`MqttInbox`].
+pub const MQTT_INBOX_CAPACITY: usize = 4;
+
+/// What the [`MqttInbox`] does with a message notification received while it is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InboxOverflow {
+    /// Drops the oldest notification to make room for the new one.
+    #[default]
+    DropOldest,
+    /// Drops the new notification.
+    DropNewest,
+    /// Drops the new notification and fails the next receive with [`Error::MqttInboxOverflow`].
+    Error,
+}
+
+/// Queue of the [`Received`] MQTT message notifications, filled by the
+/// [`UrcHandler`](crate::UrcHandler) and available from
+/// [`ModemState::mqtt_inbox`](crate::ModemState::mqtt_inbox).
+///
+/// The notifications only carry the topic and message id, the payload is read from the modem with
+/// [`Receive`](crate::mqtt::Receive).
+pub struct MqttInbox<M: RawMutex> {
+    inner: Mutex<M, RefCell<Inner>>,
+    received: Signal<M, ()>,
+}
+
+struct Inner {
+    messages: Deque<Received, MQTT_INBOX_CAPACITY>,
+    policy: InboxOverflow,
+    overflowed: bool,
+}
+
+impl<M: RawMutex> MqttInbox<M> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(Inner {
+                messages: Deque::new(),
+                policy: InboxOverflow::DropOldest,
+                overflowed: false,
+            })),
+            received: Signal::new(),
+        }
+    }
+
+    /// Sets what happens to the notifications received while the inbox is full,
+    /// [`InboxOverflow::DropOldest`] by default.
+    pub fn set_overflow_policy(&self, policy: InboxOverflow) {
+        self.inner.lock(|inner| inner.borrow_mut().policy = policy);
+    }
+
+    /// Returns the oldest notification, if any.
+    ///
+    /// Fails with [`Error::MqttInboxOverflow`] once if notifications were dropped with the
+    /// [`InboxOverflow::Error`] policy.
+    pub fn try_receive(&self) -> Result<Option<Received>, Error> {
+        self.inner.lock(|inner| {
+            let mut inner = inner.borrow_mut();
+            if core::mem::take(&mut inner.overflowed) {
+                return Err(Error::MqttInboxOverflow);
+            }
+            Ok(inner.messages.pop_front())
+        })
+    }
+
+    /// Waits for the oldest notification, see [`try_receive`](Self::try_receive).
+    pub async fn receive(&self) -> Result<Received, Error> {
+        loop {
+            if let Some(message) = self.try_receive()? {
+                return Ok(message);
+            }
+            self.received.wait().await;
+        }
+    }
+
+    /// Drops all the queued notifications, e.g. after reconnecting to the broker.
+    pub fn clear(&self) {
+        self.inner.lock(|inner| {
+            let mut inner = inner.borrow_mut();
+            inner.messages.clear();
+            inner.overflowed = false;
+        });
+    }
+
+    pub(crate) fn push(&self, message: Received) {
+        self.inner.lock(|inner| {
+            let mut inner = inner.borrow_mut();
+            if inner.messages.is_full() {
+                warn!("MQTT inbox full, applying {:?}", inner.policy);
+                match inner.policy {
+                    InboxOverflow::DropOldest => {
+                        inner.messages.pop_front();
+                    }
+                    InboxOverflow::DropNewest => return,
+                    InboxOverflow::Error => {
+                        inner.overflowed = true;
+                        return;
+                    }
+                }
+            }
+            // Can't fail, there is room for the message.
+            let _ = inner.messages.push_back(message);
+        });
+        self.received.signal(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+    use super::*;
+    use crate::mqtt::types::Qos;
+
+    fn message(mid: u16) -> Received {
+        Received {
+            id: 0,
+            topic: "topic".try_into().unwrap(),
+            msg_length: 4,
+            qos: Qos::AtLeastOnce,
+            mid: Some(mid),
+        }
+    }
+
+    fn fill(inbox: &MqttInbox<NoopRawMutex>) {
+        for mid in 0..=MQTT_INBOX_CAPACITY as u16 {
+            inbox.push(message(mid));
+        }
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let inbox = MqttInbox::<NoopRawMutex>::new();
+        fill(&inbox);
+
+        let first = inbox.try_receive().unwrap().unwrap();
+        assert_eq!(first.mid, Some(1));
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        let inbox = MqttInbox::<NoopRawMutex>::new();
+        inbox.set_overflow_policy(InboxOverflow::DropNewest);
+        fill(&inbox);
+
+        let first = inbox.try_receive().unwrap().unwrap();
+        assert_eq!(first.mid, Some(0));
+    }
+
+    #[test]
+    fn test_error() {
+        let inbox = MqttInbox::<NoopRawMutex>::new();
+        inbox.set_overflow_policy(InboxOverflow::Error);
+        fill(&inbox);
+
+        assert_eq!(inbox.try_receive().unwrap_err(), Error::MqttInboxOverflow);
+        let first = inbox.try_receive().unwrap().unwrap();
+        assert_eq!(first.mid, Some(0));
+    }
+}
@@ -0,0 +1,33 @@
+use heapless::String;
+
+use crate::{
+    mobile_equipment::responses::SignalQuality, network::types::NetworkRegistrationState,
+    sim::types::SIMState,
+};
+
+/// Diagnostic snapshot of the modem returned by [`Modem::info`](crate::Modem::info), e.g. for
+/// device health reports.
+///
+/// The details depending on the SIM card or the network are `None` when the modem can't provide
+/// them, e.g. without a SIM card or before the network registration.
+#[derive(Clone)]
+#[cfg_attr(any(test, feature = "verbose-fmt"), derive(Debug))]
+#[cfg_attr(all(feature = "defmt", feature = "verbose-fmt"), derive(defmt::Format))]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+pub struct ModemInfo {
+    pub manufacturer: String<32>,
+    pub model: String<32>,
+    pub imei: String<16>,
+    pub firmware_version: String<64>,
+    pub sim_state: Option<SIMState>,
+    pub iccid: Option<String<20>>,
+    pub registration_state: NetworkRegistrationState,
+    /// Selected network operator, in the format set with
+    /// [`PLMNSelection`](crate::network::PLMNSelection).
+    pub operator: Option<String<16>>,
+    pub signal_quality: Option<SignalQuality>,
+    /// IP address of the [configured PDP context](crate::PdpConfig).
+    pub ip_address: Option<String<64>>,
+}
+
+compact_fmt!(ModemInfo);
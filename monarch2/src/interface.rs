@@ -0,0 +1,802 @@
+use atat::asynch::AtatClient;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embedded_hal_async::delay::DelayNs;
+use heapless::{String, Vec};
+
+#[cfg(feature = "nidd")]
+use embassy_time::Duration;
+
+#[cfg(feature = "gnss")]
+use crate::gnss::{
+    types::{FixSensitivity, GnssAssistanceType},
+    urc::GnssFixReady,
+};
+#[cfg(feature = "http")]
+use crate::http;
+#[cfg(feature = "nidd")]
+use crate::nidd;
+#[cfg(feature = "socket")]
+use crate::socket;
+#[cfg(feature = "mqtt")]
+use crate::{
+    aws::AwsIotConfig,
+    azure::{AzureIotConfig, AzureSasToken},
+    modem::MqttAuth,
+    mqtt,
+};
+use crate::{
+    calendar::ClockTime,
+    config::{ClockSyncPolicy, PingOptions},
+    device, diagnostics, dns,
+    error::Error,
+    info::ModemInfo,
+    mobile_equipment::types::PowerState,
+    modem::{Attached, Detached, Manufacturing, Modem},
+    network::types::NetworkRegistrationState,
+    nvm, ping, sim,
+};
+
+/// The operations of the [`Modem`] as traits.
+///
+/// Application code written against these traits instead of the concrete [`Modem`] can be unit
+/// tested with a mock implementation, without any AT traffic. `ModemInterface` holds the general
+/// control of the modem and the LTE connection, the other operations are split by subsystem
+/// ([`SimInterface`], [`ManufacturingInterface`], [`TlsInterface`] and the ones gated by the
+/// feature of their subsystem, e.g. [`MqttInterface`]), so a mock only implements the traits the
+/// code under test uses. The state tokens needed by a mock are created with the hidden
+/// `Attached::new_unchecked`, `Detached::new_unchecked` and `Manufacturing::new_unchecked`
+/// constructors.
+///
+/// See the [`Modem`] methods of the same name for the documentation of each operation.
+#[allow(async_fn_in_trait)]
+pub trait ModemInterface {
+    async fn begin(&mut self) -> Result<(), Error>;
+
+    async fn ping(&mut self) -> Result<(), Error>;
+
+    async fn get_operation_mode(&mut self) -> Result<device::types::RAT, Error>;
+
+    async fn set_opeartion_mode(
+        &mut self,
+        detached: &Detached,
+        mode: device::types::RAT,
+    ) -> Result<(), Error>;
+
+    async fn get_autoconnect(&mut self) -> Result<bool, Error>;
+
+    async fn set_autoconnect(&mut self, enabled: bool) -> Result<(), Error>;
+
+    async fn reboot(&mut self) -> Result<(), Error>;
+
+    async fn get_carrier_profile(
+        &mut self,
+    ) -> Result<String<{ device::responses::CARRIER_PROFILE_LEN }>, Error>;
+
+    async fn set_carrier_profile(
+        &mut self,
+        detached: &Detached,
+        profile: &str,
+    ) -> Result<bool, Error>;
+
+    async fn define_pdp_context(&mut self) -> Result<(), Error>;
+
+    async fn power_state(&mut self) -> Result<PowerState, Error>;
+
+    async fn require_state(&mut self, expected: PowerState) -> Result<(), Error>;
+
+    fn get_network_registration_state(&self) -> NetworkRegistrationState;
+
+    async fn info(&mut self) -> Result<ModemInfo, Error>;
+
+    async fn lte_connect(&mut self, detached: Detached) -> Result<Attached, Error>;
+
+    async fn lte_disconnect(&mut self, attached: Attached) -> Result<Detached, Error>;
+
+    async fn lte_force_disconnect(&mut self, attached: Option<Attached>)
+    -> Result<Detached, Error>;
+
+    async fn get_time(
+        &mut self,
+        policy: ClockSyncPolicy,
+    ) -> Result<device::responses::Clock, Error>;
+
+    async fn set_time(&mut self, time: &ClockTime) -> Result<(), Error>;
+
+    async fn sync_time_ntp(
+        &mut self,
+        attached: &Attached,
+        server: &str,
+    ) -> Result<device::responses::Clock, Error>;
+
+    async fn resolve(
+        &mut self,
+        attached: &Attached,
+        hostname: &str,
+    ) -> Result<dns::responses::HostAddresses, Error>;
+
+    async fn icmp_ping(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        options: &PingOptions,
+    ) -> Result<ping::responses::PingReplies, Error>;
+
+    async fn read_diagnostic_log(
+        &mut self,
+        log: diagnostics::types::DiagnosticLog,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error>;
+}
+
+impl<AtCl, const N: usize, const L: usize, D, M> ModemInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn begin(&mut self) -> Result<(), Error> {
+        Modem::begin(self).await
+    }
+
+    async fn ping(&mut self) -> Result<(), Error> {
+        Modem::ping(self).await
+    }
+
+    async fn get_operation_mode(&mut self) -> Result<device::types::RAT, Error> {
+        Modem::get_operation_mode(self).await
+    }
+
+    async fn set_opeartion_mode(
+        &mut self,
+        detached: &Detached,
+        mode: device::types::RAT,
+    ) -> Result<(), Error> {
+        Modem::set_opeartion_mode(self, detached, mode).await
+    }
+
+    async fn get_autoconnect(&mut self) -> Result<bool, Error> {
+        Modem::get_autoconnect(self).await
+    }
+
+    async fn set_autoconnect(&mut self, enabled: bool) -> Result<(), Error> {
+        Modem::set_autoconnect(self, enabled).await
+    }
+
+    async fn reboot(&mut self) -> Result<(), Error> {
+        Modem::reboot(self).await
+    }
+
+    async fn get_carrier_profile(
+        &mut self,
+    ) -> Result<String<{ device::responses::CARRIER_PROFILE_LEN }>, Error> {
+        Modem::get_carrier_profile(self).await
+    }
+
+    async fn set_carrier_profile(
+        &mut self,
+        detached: &Detached,
+        profile: &str,
+    ) -> Result<bool, Error> {
+        Modem::set_carrier_profile(self, detached, profile).await
+    }
+
+    async fn define_pdp_context(&mut self) -> Result<(), Error> {
+        Modem::define_pdp_context(self).await
+    }
+
+    async fn power_state(&mut self) -> Result<PowerState, Error> {
+        Modem::power_state(self).await
+    }
+
+    async fn require_state(&mut self, expected: PowerState) -> Result<(), Error> {
+        Modem::require_state(self, expected).await
+    }
+
+    fn get_network_registration_state(&self) -> NetworkRegistrationState {
+        Modem::get_network_registration_state(self)
+    }
+
+    async fn info(&mut self) -> Result<ModemInfo, Error> {
+        Modem::info(self).await
+    }
+
+    async fn lte_connect(&mut self, detached: Detached) -> Result<Attached, Error> {
+        Modem::lte_connect(self, detached).await
+    }
+
+    async fn lte_disconnect(&mut self, attached: Attached) -> Result<Detached, Error> {
+        Modem::lte_disconnect(self, attached).await
+    }
+
+    async fn lte_force_disconnect(
+        &mut self,
+        attached: Option<Attached>,
+    ) -> Result<Detached, Error> {
+        Modem::lte_force_disconnect(self, attached).await
+    }
+
+    async fn get_time(
+        &mut self,
+        policy: ClockSyncPolicy,
+    ) -> Result<device::responses::Clock, Error> {
+        Modem::get_time(self, policy).await
+    }
+
+    async fn set_time(&mut self, time: &ClockTime) -> Result<(), Error> {
+        Modem::set_time(self, time).await
+    }
+
+    async fn sync_time_ntp(
+        &mut self,
+        attached: &Attached,
+        server: &str,
+    ) -> Result<device::responses::Clock, Error> {
+        Modem::sync_time_ntp(self, attached, server).await
+    }
+
+    async fn resolve(
+        &mut self,
+        attached: &Attached,
+        hostname: &str,
+    ) -> Result<dns::responses::HostAddresses, Error> {
+        Modem::resolve(self, attached, hostname).await
+    }
+
+    async fn icmp_ping(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        options: &PingOptions,
+    ) -> Result<ping::responses::PingReplies, Error> {
+        Modem::icmp_ping(self, attached, host, options).await
+    }
+
+    async fn read_diagnostic_log(
+        &mut self,
+        log: diagnostics::types::DiagnosticLog,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        Modem::read_diagnostic_log(self, log, offset, buf).await
+    }
+}
+
+/// The SIM and eSIM operations of the [`Modem`]. See [`ModemInterface`].
+#[allow(async_fn_in_trait)]
+pub trait SimInterface {
+    async fn get_pin_status(&mut self) -> Result<sim::types::SIMState, Error>;
+
+    async fn unblock_sim(&mut self, puk: String<8>, new_pin: String<8>) -> Result<(), Error>;
+
+    async fn read_sim_binary(
+        &mut self,
+        file_id: u16,
+        length: u8,
+    ) -> Result<sim::responses::RestrictedSimAccessResponse, Error>;
+
+    async fn esim_list_profiles(
+        &mut self,
+    ) -> Result<Vec<sim::esim::ProfileInfo, { sim::esim::MAX_PROFILES }>, Error>;
+
+    async fn esim_enable_profile(&mut self, iccid: &str) -> Result<(), Error>;
+
+    async fn esim_disable_profile(&mut self, iccid: &str) -> Result<(), Error>;
+
+    async fn set_sim_lock(&mut self, enabled: bool, pin: String<8>) -> Result<(), Error>;
+
+    async fn is_sim_locked(&mut self) -> Result<bool, Error>;
+}
+
+impl<AtCl, const N: usize, const L: usize, D, M> SimInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn get_pin_status(&mut self) -> Result<sim::types::SIMState, Error> {
+        Modem::get_pin_status(self).await
+    }
+
+    async fn unblock_sim(&mut self, puk: String<8>, new_pin: String<8>) -> Result<(), Error> {
+        Modem::unblock_sim(self, puk, new_pin).await
+    }
+
+    async fn read_sim_binary(
+        &mut self,
+        file_id: u16,
+        length: u8,
+    ) -> Result<sim::responses::RestrictedSimAccessResponse, Error> {
+        Modem::read_sim_binary(self, file_id, length).await
+    }
+
+    async fn esim_list_profiles(
+        &mut self,
+    ) -> Result<Vec<sim::esim::ProfileInfo, { sim::esim::MAX_PROFILES }>, Error> {
+        Modem::esim_list_profiles(self).await
+    }
+
+    async fn esim_enable_profile(&mut self, iccid: &str) -> Result<(), Error> {
+        Modem::esim_enable_profile(self, iccid).await
+    }
+
+    async fn esim_disable_profile(&mut self, iccid: &str) -> Result<(), Error> {
+        Modem::esim_disable_profile(self, iccid).await
+    }
+
+    async fn set_sim_lock(&mut self, enabled: bool, pin: String<8>) -> Result<(), Error> {
+        Modem::set_sim_lock(self, enabled, pin).await
+    }
+
+    async fn is_sim_locked(&mut self) -> Result<bool, Error> {
+        Modem::is_sim_locked(self).await
+    }
+}
+
+/// The manufacturing mode and RF test operations of the [`Modem`]. See [`ModemInterface`].
+#[allow(async_fn_in_trait)]
+pub trait ManufacturingInterface {
+    async fn enter_manufacturing_mode(
+        &mut self,
+        detached: Detached,
+    ) -> Result<Manufacturing, Error>;
+
+    async fn leave_manufacturing_mode(
+        &mut self,
+        manufacturing: Manufacturing,
+    ) -> Result<Detached, Error>;
+
+    async fn rf_test_transmit(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+        power: i8,
+    ) -> Result<(), Error>;
+
+    async fn rf_test_stop_transmit(&mut self, manufacturing: &Manufacturing) -> Result<(), Error>;
+
+    async fn rf_test_measure(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+    ) -> Result<i16, Error>;
+}
+
+impl<AtCl, const N: usize, const L: usize, D, M> ManufacturingInterface
+    for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn enter_manufacturing_mode(
+        &mut self,
+        detached: Detached,
+    ) -> Result<Manufacturing, Error> {
+        Modem::enter_manufacturing_mode(self, detached).await
+    }
+
+    async fn leave_manufacturing_mode(
+        &mut self,
+        manufacturing: Manufacturing,
+    ) -> Result<Detached, Error> {
+        Modem::leave_manufacturing_mode(self, manufacturing).await
+    }
+
+    async fn rf_test_transmit(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+        power: i8,
+    ) -> Result<(), Error> {
+        Modem::rf_test_transmit(self, manufacturing, earfcn, power).await
+    }
+
+    async fn rf_test_stop_transmit(&mut self, manufacturing: &Manufacturing) -> Result<(), Error> {
+        Modem::rf_test_stop_transmit(self, manufacturing).await
+    }
+
+    async fn rf_test_measure(
+        &mut self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+    ) -> Result<i16, Error> {
+        Modem::rf_test_measure(self, manufacturing, earfcn).await
+    }
+}
+
+/// The operations of the [`Modem`] storing the TLS credentials and profiles. See
+/// [`ModemInterface`].
+#[allow(async_fn_in_trait)]
+pub trait TlsInterface {
+    async fn nvm_write(
+        &mut self,
+        data_type: nvm::types::DataType,
+        index: u8,
+        data: &[u8],
+    ) -> Result<(), Error>;
+
+    async fn configure_tls_profile(
+        &mut self,
+        sp_id: u8,
+        ca_cert_id: Option<u8>,
+        client_cert_id: Option<u8>,
+        client_private_key_id: Option<u8>,
+    ) -> Result<(), Error>;
+}
+
+impl<AtCl, const N: usize, const L: usize, D, M> TlsInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn nvm_write(
+        &mut self,
+        data_type: nvm::types::DataType,
+        index: u8,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        Modem::nvm_write(self, data_type, index, data).await
+    }
+
+    async fn configure_tls_profile(
+        &mut self,
+        sp_id: u8,
+        ca_cert_id: Option<u8>,
+        client_cert_id: Option<u8>,
+        client_private_key_id: Option<u8>,
+    ) -> Result<(), Error> {
+        Modem::configure_tls_profile(
+            self,
+            sp_id,
+            ca_cert_id,
+            client_cert_id,
+            client_private_key_id,
+        )
+        .await
+    }
+}
+
+/// The socket operations of the [`Modem`]. See [`ModemInterface`].
+#[cfg(feature = "socket")]
+#[allow(async_fn_in_trait)]
+pub trait SocketInterface {
+    async fn socket_set_hex_mode(&mut self, conn_id: u8) -> Result<(), Error>;
+
+    async fn socket_receive(
+        &mut self,
+        attached: &Attached,
+        conn_id: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, Error>;
+
+    async fn socket_data_usage(&mut self, conn_id: u8) -> Result<socket::types::DataUsage, Error>;
+
+    async fn data_usage(&mut self) -> Result<socket::types::DataUsage, Error>;
+}
+
+#[cfg(feature = "socket")]
+impl<AtCl, const N: usize, const L: usize, D, M> SocketInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn socket_set_hex_mode(&mut self, conn_id: u8) -> Result<(), Error> {
+        Modem::socket_set_hex_mode(self, conn_id).await
+    }
+
+    async fn socket_receive(
+        &mut self,
+        attached: &Attached,
+        conn_id: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        Modem::socket_receive(self, attached, conn_id, buf).await
+    }
+
+    async fn socket_data_usage(&mut self, conn_id: u8) -> Result<socket::types::DataUsage, Error> {
+        Modem::socket_data_usage(self, conn_id).await
+    }
+
+    async fn data_usage(&mut self) -> Result<socket::types::DataUsage, Error> {
+        Modem::data_usage(self).await
+    }
+}
+
+/// The HTTP client operations of the [`Modem`]. See [`ModemInterface`].
+#[cfg(feature = "http")]
+#[allow(async_fn_in_trait)]
+pub trait HttpInterface {
+    async fn http_configure(
+        &mut self,
+        prof_id: u8,
+        host: &str,
+        port: u16,
+        sp_id: Option<u8>,
+    ) -> Result<(), Error>;
+
+    async fn http_query(
+        &mut self,
+        attached: &Attached,
+        prof_id: u8,
+        command: http::types::QueryCommand,
+        resource: &str,
+    ) -> Result<http::urc::Ring, Error>;
+
+    async fn http_receive(&mut self, prof_id: u8, buf: &mut [u8]) -> Result<usize, Error>;
+}
+
+#[cfg(feature = "http")]
+impl<AtCl, const N: usize, const L: usize, D, M> HttpInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn http_configure(
+        &mut self,
+        prof_id: u8,
+        host: &str,
+        port: u16,
+        sp_id: Option<u8>,
+    ) -> Result<(), Error> {
+        Modem::http_configure(self, prof_id, host, port, sp_id).await
+    }
+
+    async fn http_query(
+        &mut self,
+        attached: &Attached,
+        prof_id: u8,
+        command: http::types::QueryCommand,
+        resource: &str,
+    ) -> Result<http::urc::Ring, Error> {
+        Modem::http_query(self, attached, prof_id, command, resource).await
+    }
+
+    async fn http_receive(&mut self, prof_id: u8, buf: &mut [u8]) -> Result<usize, Error> {
+        Modem::http_receive(self, prof_id, buf).await
+    }
+}
+
+/// The Non-IP Data Delivery operations of the [`Modem`]. See [`ModemInterface`].
+#[cfg(feature = "nidd")]
+#[allow(async_fn_in_trait)]
+pub trait NiddInterface {
+    async fn nidd_configure(&mut self) -> Result<(), Error>;
+
+    async fn nidd_send(&mut self, attached: &Attached, data: &[u8]) -> Result<(), Error>;
+
+    async fn control_plane_send(
+        &mut self,
+        attached: &Attached,
+        cid: u8,
+        data: &[u8],
+        rai: Option<nidd::types::ReleaseAssistance>,
+        user_data_type: Option<nidd::types::UserDataType>,
+    ) -> Result<(), Error>;
+
+    async fn nidd_receive(&mut self, timeout: Duration) -> Result<nidd::urc::DataReceived, Error>;
+}
+
+#[cfg(feature = "nidd")]
+impl<AtCl, const N: usize, const L: usize, D, M> NiddInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn nidd_configure(&mut self) -> Result<(), Error> {
+        Modem::nidd_configure(self).await
+    }
+
+    async fn nidd_send(&mut self, attached: &Attached, data: &[u8]) -> Result<(), Error> {
+        Modem::nidd_send(self, attached, data).await
+    }
+
+    async fn control_plane_send(
+        &mut self,
+        attached: &Attached,
+        cid: u8,
+        data: &[u8],
+        rai: Option<nidd::types::ReleaseAssistance>,
+        user_data_type: Option<nidd::types::UserDataType>,
+    ) -> Result<(), Error> {
+        Modem::control_plane_send(self, attached, cid, data, rai, user_data_type).await
+    }
+
+    async fn nidd_receive(&mut self, timeout: Duration) -> Result<nidd::urc::DataReceived, Error> {
+        Modem::nidd_receive(self, timeout).await
+    }
+}
+
+/// The GNSS operations of the [`Modem`]. See [`ModemInterface`].
+#[cfg(feature = "gnss")]
+#[allow(async_fn_in_trait)]
+pub trait GnssInterface {
+    async fn set_gnss_config(&mut self, sensitivity: FixSensitivity) -> Result<(), Error>;
+
+    async fn update_gnss_asistance(&mut self, detached: Detached) -> Result<Detached, Error>;
+
+    async fn upload_gnss_assistance(
+        &mut self,
+        typ: GnssAssistanceType,
+        data: &[u8],
+    ) -> Result<(), Error>;
+
+    async fn get_gnss_fix(&mut self, detached: &Detached) -> Result<GnssFixReady, Error>;
+}
+
+#[cfg(feature = "gnss")]
+impl<AtCl, const N: usize, const L: usize, D, M> GnssInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn set_gnss_config(&mut self, sensitivity: FixSensitivity) -> Result<(), Error> {
+        Modem::set_gnss_config(self, sensitivity).await
+    }
+
+    async fn update_gnss_asistance(&mut self, detached: Detached) -> Result<Detached, Error> {
+        Modem::update_gnss_asistance(self, detached).await
+    }
+
+    async fn upload_gnss_assistance(
+        &mut self,
+        typ: GnssAssistanceType,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        Modem::upload_gnss_assistance(self, typ, data).await
+    }
+
+    async fn get_gnss_fix(&mut self, detached: &Detached) -> Result<GnssFixReady, Error> {
+        Modem::get_gnss_fix(self, detached).await
+    }
+}
+
+/// The MQTT client operations of the [`Modem`], including the AWS IoT and Azure IoT connections.
+/// See [`ModemInterface`].
+#[cfg(feature = "mqtt")]
+#[allow(async_fn_in_trait)]
+pub trait MqttInterface {
+    async fn mqtt_configure(
+        &mut self,
+        client_id: &str,
+        auth: Option<MqttAuth>,
+    ) -> Result<(), Error>;
+
+    async fn mqtt_connect(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        port: Option<u32>,
+    ) -> Result<(), Error>;
+
+    async fn aws_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AwsIotConfig<'_>,
+    ) -> Result<(), Error>;
+
+    async fn azure_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AzureIotConfig<'_>,
+        sas_token: Option<&AzureSasToken>,
+    ) -> Result<(), Error>;
+
+    async fn mqtt_send(
+        &mut self,
+        topic: &str,
+        qos: mqtt::types::Qos,
+        data: &[u8],
+    ) -> Result<(), Error>;
+
+    async fn mqtt_subscribe(&mut self, topic: &str, qos: mqtt::types::Qos) -> Result<(), Error>;
+
+    async fn mqtt_disconnect(&mut self) -> Result<(), Error>;
+}
+
+#[cfg(feature = "mqtt")]
+impl<AtCl, const N: usize, const L: usize, D, M> MqttInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn mqtt_configure(
+        &mut self,
+        client_id: &str,
+        auth: Option<MqttAuth>,
+    ) -> Result<(), Error> {
+        Modem::mqtt_configure(self, client_id, auth).await
+    }
+
+    async fn mqtt_connect(
+        &mut self,
+        attached: &Attached,
+        host: &str,
+        port: Option<u32>,
+    ) -> Result<(), Error> {
+        Modem::mqtt_connect(self, attached, host, port).await
+    }
+
+    async fn aws_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AwsIotConfig<'_>,
+    ) -> Result<(), Error> {
+        Modem::aws_iot_connect(self, attached, config).await
+    }
+
+    async fn azure_iot_connect(
+        &mut self,
+        attached: &Attached,
+        config: &AzureIotConfig<'_>,
+        sas_token: Option<&AzureSasToken>,
+    ) -> Result<(), Error> {
+        Modem::azure_iot_connect(self, attached, config, sas_token).await
+    }
+
+    async fn mqtt_send(
+        &mut self,
+        topic: &str,
+        qos: mqtt::types::Qos,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        Modem::mqtt_send(self, topic, qos, data).await
+    }
+
+    async fn mqtt_subscribe(&mut self, topic: &str, qos: mqtt::types::Qos) -> Result<(), Error> {
+        Modem::mqtt_subscribe(self, topic, qos).await
+    }
+
+    async fn mqtt_disconnect(&mut self) -> Result<(), Error> {
+        Modem::mqtt_disconnect(self).await
+    }
+}
+
+/// The LwM2M client operations of the [`Modem`]. See [`ModemInterface`].
+#[cfg(feature = "lwm2m")]
+#[allow(async_fn_in_trait)]
+pub trait Lwm2mInterface {
+    async fn lwm2m_register(&mut self, attached: &Attached) -> Result<(), Error>;
+
+    async fn lwm2m_set_resource(
+        &mut self,
+        object: u16,
+        instance: u16,
+        resource: u16,
+        value: &str,
+    ) -> Result<(), Error>;
+
+    async fn lwm2m_disable(&mut self) -> Result<(), Error>;
+}
+
+#[cfg(feature = "lwm2m")]
+impl<AtCl, const N: usize, const L: usize, D, M> Lwm2mInterface for Modem<'_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    async fn lwm2m_register(&mut self, attached: &Attached) -> Result<(), Error> {
+        Modem::lwm2m_register(self, attached).await
+    }
+
+    async fn lwm2m_set_resource(
+        &mut self,
+        object: u16,
+        instance: u16,
+        resource: u16,
+        value: &str,
+    ) -> Result<(), Error> {
+        Modem::lwm2m_set_resource(self, object, instance, resource, value).await
+    }
+
+    async fn lwm2m_disable(&mut self) -> Result<(), Error> {
+        Modem::lwm2m_disable(self).await
+    }
+}
@@ -1,4 +1,4 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
 //! # monarch2
 //!
@@ -9,16 +9,109 @@
 // This mod MUST go first, so that the others see its macros.
 pub(crate) mod fmt;
 
+#[cfg(feature = "mqtt")]
+mod aws;
+#[cfg(feature = "mqtt")]
+mod azure;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod calendar;
+mod capacity;
+mod channel;
+#[cfg(feature = "cmux")]
+pub mod cmux;
 mod command;
+mod config;
+mod delay;
+mod drift;
 mod error;
+mod event;
+#[cfg(feature = "std")]
+mod host;
+#[cfg(feature = "mqtt")]
+mod inbox;
+mod info;
+mod interface;
+mod listener;
+mod maintain;
+mod manager;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
 mod modem;
+mod observer;
+#[cfg(feature = "serial")]
+pub mod serial;
+mod shared;
+#[cfg(any(test, feature = "mock"))]
+mod simulator;
+mod wake;
+#[cfg(feature = "walter")]
+pub mod walter;
 
+#[cfg(feature = "mqtt")]
+pub use aws::*;
+#[cfg(feature = "mqtt")]
+pub use azure::*;
+#[cfg(feature = "blocking")]
+pub use blocking::*;
+pub use calendar::*;
+pub use capacity::*;
+pub use channel::*;
 pub use command::*;
+pub use config::*;
+pub use delay::*;
+pub use drift::*;
 pub use error::*;
+pub use event::*;
+#[cfg(feature = "std")]
+pub use host::*;
+#[cfg(feature = "mqtt")]
+pub use inbox::*;
+pub use info::*;
+pub use interface::*;
+pub use listener::*;
+pub use maintain::*;
+pub use manager::*;
+#[cfg(any(test, feature = "mock"))]
+pub use mock::*;
 pub use modem::*;
+pub use observer::*;
+pub use shared::*;
+#[cfg(any(test, feature = "mock"))]
+pub use simulator::*;
+pub use wake::*;
 
 pub mod prelude {
+    #[cfg(feature = "mqtt")]
+    pub use crate::aws::*;
+    #[cfg(feature = "mqtt")]
+    pub use crate::azure::*;
+    #[cfg(feature = "blocking")]
+    pub use crate::blocking::*;
+    pub use crate::calendar::*;
+    pub use crate::capacity::*;
+    pub use crate::channel::*;
     pub use crate::command::*;
+    pub use crate::config::*;
+    pub use crate::delay::*;
+    pub use crate::drift::*;
     pub use crate::error::*;
+    pub use crate::event::*;
+    #[cfg(feature = "std")]
+    pub use crate::host::*;
+    #[cfg(feature = "mqtt")]
+    pub use crate::inbox::*;
+    pub use crate::info::*;
+    pub use crate::interface::*;
+    pub use crate::listener::*;
+    pub use crate::maintain::*;
+    pub use crate::manager::*;
+    #[cfg(any(test, feature = "mock"))]
+    pub use crate::mock::*;
     pub use crate::modem::*;
+    pub use crate::observer::*;
+    pub use crate::shared::*;
+    #[cfg(any(test, feature = "mock"))]
+    pub use crate::simulator::*;
+    pub use crate::wake::*;
 }
@@ -0,0 +1,53 @@
+#[cfg(feature = "gnss")]
+use crate::gnss::urc::GnssFixReady;
+#[cfg(feature = "mqtt")]
+use crate::mqtt::{self, types::MQTTStatusCode};
+use crate::{command::Urc, network::types::NetworkRegistrationState};
+
+/// Receives the URCs processed by the [`UrcHandler`](crate::UrcHandler), set with
+/// [`UrcHandler::set_listener`](crate::UrcHandler::set_listener).
+///
+/// The callbacks run in the URC handler task once the driver state is updated, so they must
+/// return quickly, e.g. by signalling another task. Every callback does nothing by default,
+/// implement only the ones needed:
+///
+/// ```ignore
+/// struct Inbox;
+///
+/// impl UrcListener for Inbox {
+///     fn on_mqtt_message(&self, message: &mqtt::urc::Received) {
+///         INBOX.signal(message.clone());
+///     }
+/// }
+/// ```
+pub trait UrcListener {
+    /// Called for every URC, before the specific callback.
+    fn on_urc(&self, _urc: &Urc) {}
+
+    /// The network registration state changed.
+    fn on_registration_change(&self, _state: &NetworkRegistrationState) {}
+
+    /// A message was received on a subscribed MQTT topic.
+    #[cfg(feature = "mqtt")]
+    fn on_mqtt_message(&self, _message: &mqtt::urc::Received) {}
+
+    /// The MQTT connection was closed.
+    #[cfg(feature = "mqtt")]
+    fn on_mqtt_disconnected(&self, _rc: &MQTTStatusCode) {}
+
+    /// A GNSS fix is available.
+    #[cfg(feature = "gnss")]
+    fn on_gnss_fix(&self, _fix: &GnssFixReady) {}
+
+    /// The modem is about to shut down or restart.
+    fn on_shutdown(&self) {}
+
+    /// The modem (re)started and is ready to operate.
+    fn on_started(&self) {}
+
+    /// The given number of URCs were lost because the handler lagged behind the URC channel.
+    fn on_urcs_dropped(&self, _missed: u64) {}
+}
+
+/// Ignores every URC, the listener of a new [`UrcHandler`](crate::UrcHandler).
+impl UrcListener for () {}
@@ -0,0 +1,105 @@
+use atat::asynch::AtatClient;
+use embassy_futures::select::{Either, select};
+use embassy_sync::{
+    blocking_mutex::raw::{NoopRawMutex, RawMutex},
+    signal::Signal,
+};
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
+
+#[cfg(feature = "mqtt")]
+use crate::modem::MqttAuth;
+use crate::{
+    config::BackoffPolicy,
+    manager::{ConnectionManager, ConnectionProgress},
+    shared::SharedModem,
+};
+
+/// Connection state reported by [`maintain`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConnectionState {
+    /// Not registered to the LTE network.
+    Detached,
+    /// Registered to the LTE network.
+    Attached,
+    /// Registered and connected to the MQTT broker.
+    #[cfg(feature = "mqtt")]
+    MqttConnected,
+}
+
+/// MQTT session kept alive by [`maintain`].
+#[cfg(feature = "mqtt")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MqttSession<'a> {
+    pub client_id: &'a str,
+    pub auth: Option<MqttAuth>,
+    pub host: &'a str,
+    pub port: Option<u32>,
+}
+
+/// How [`maintain`] keeps the connection alive.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MaintainPolicy<'a> {
+    /// How often the connection is checked when no event is received.
+    pub check_interval: Duration,
+
+    /// How long to wait for the network registration before giving up the attempt.
+    pub attach_timeout: Duration,
+
+    /// Delay between failed reconnection attempts.
+    pub reconnect: BackoffPolicy,
+
+    /// MQTT session to keep connected, if any.
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<MqttSession<'a>>,
+}
+
+/// Keeps the modem registered to the network, with the PDP context defined and the
+/// [MQTT session](MaintainPolicy::mqtt) connected.
+///
+/// Runs forever, intended to be spawned as a task next to the [`UrcHandler`](crate::UrcHandler)
+/// with the other tasks using the [`SharedModem`] handles. Every change of the connection is
+/// signalled to `state`. After a modem restart the driver is initialized again with
+/// [`Modem::begin`](crate::Modem::begin) and the connection is brought back up.
+///
+/// A [`ConnectionManager`] reporting only the resulting [`ConnectionState`], see
+/// [`ConnectionManager::run`] for the progress of every stage.
+///
+/// # Panics
+///
+/// Panics if no [`ModemEvent`](crate::ModemEvent) subscriber is available, see
+/// [`Modem::events`](crate::Modem::events).
+pub async fn maintain<M, AtCl, S, const N: usize, const L: usize, D, SM>(
+    modem: &SharedModem<'_, M, AtCl, N, L, D, SM>,
+    policy: &MaintainPolicy<'_>,
+    state: &Signal<S, ConnectionState>,
+) -> !
+where
+    M: RawMutex,
+    AtCl: AtatClient,
+    S: RawMutex,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    let mut manager = ConnectionManager::new(modem, policy).await;
+    let progress = Signal::<NoopRawMutex, ConnectionProgress>::new();
+
+    let report = async {
+        let mut reported = None;
+        loop {
+            let current = progress.wait().await.state();
+            if reported != Some(current) {
+                debug!("Connection state: {:?}", current);
+                state.signal(current);
+                reported = Some(current);
+            }
+        }
+    };
+
+    match select(manager.run(&progress), report).await {
+        Either::First(never) | Either::Second(never) => never,
+    }
+}
@@ -0,0 +1,375 @@
+use atat::asynch::AtatClient;
+use embassy_sync::{
+    blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex},
+    signal::Signal,
+};
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{
+    delay::{self, DefaultDelay},
+    error::{Error, ErrorClass, Operation},
+    event::{ModemEvent, ModemEvents},
+    maintain::{ConnectionState, MaintainPolicy},
+    modem::Attached,
+    network::types::NetworkRegistrationState,
+    shared::SharedModem,
+    sim::types::SIMState,
+};
+
+/// Stage of the connection brought up by the [`ConnectionManager`], in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConnectionStage {
+    /// The driver is initialized and the SIM is ready, unlocked with the
+    /// [configured](crate::ModemConfig::sim_pin) PIN if needed.
+    SimReady,
+    /// The [PDP context](crate::ModemConfig::pdp) and its credentials are defined, which the
+    /// modem only accepts before it attaches.
+    PdpContext,
+    /// The radio is on (CFUN=1) and the modem is registered to the network.
+    Registration,
+    /// The [MQTT session](MaintainPolicy::mqtt) is connected.
+    #[cfg(feature = "mqtt")]
+    MqttSession,
+}
+
+/// Progress of the [`ConnectionManager`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConnectionProgress {
+    /// The stage is being brought up, `attempt` counted from 0.
+    InProgress { stage: ConnectionStage, attempt: u8 },
+    /// All the attempts of the stage failed, or it failed for good (e.g. a wrong SIM PIN).
+    Failed(ConnectionStage),
+    /// All the stages are up.
+    Connected(ConnectionState),
+}
+
+impl ConnectionProgress {
+    /// Returns the state of the connection: the stages before the one in progress (or failed)
+    /// are up.
+    pub fn state(&self) -> ConnectionState {
+        match self {
+            Self::Connected(state) => *state,
+            #[cfg(feature = "mqtt")]
+            Self::InProgress {
+                stage: ConnectionStage::MqttSession,
+                ..
+            }
+            | Self::Failed(ConnectionStage::MqttSession) => ConnectionState::Attached,
+            Self::InProgress { .. } | Self::Failed(_) => ConnectionState::Detached,
+        }
+    }
+}
+
+/// Brings the connection up stage by stage: SIM ready, PDP context, registration and the
+/// optional MQTT session, see [`ConnectionStage`].
+///
+/// Every stage is retried on its own with the [`reconnect`](MaintainPolicy::reconnect) backoff,
+/// a stage lost later (e.g. the network deregistered, the modem restarted) is brought up again
+/// with the ones after it. The progress is signalled to the caller:
+///
+/// ```ignore
+/// static PROGRESS: Signal<CriticalSectionRawMutex, ConnectionProgress> = Signal::new();
+///
+/// let mut manager = ConnectionManager::new(&shared, &policy).await;
+/// manager.connect(&PROGRESS).await?;
+/// // Or keep the connection up in a task.
+/// manager.run(&PROGRESS).await
+/// ```
+///
+/// [`maintain`](crate::maintain) runs a manager reporting only the [`ConnectionState`].
+pub struct ConnectionManager<
+    'm,
+    'a,
+    'p,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: &'m SharedModem<'a, M, AtCl, N, L, D, SM>,
+    policy: &'p MaintainPolicy<'p>,
+    /// Times the retries and the checks, a copy of the delay provider of the modem.
+    delay: D,
+    events: ModemEvents<'a>,
+    /// Last stage brought up, `None` until the SIM is ready.
+    reached: Option<ConnectionStage>,
+    attached: Option<Attached>,
+    /// Last progress signalled, so an unchanged connection isn't signalled again.
+    reported: Option<ConnectionProgress>,
+}
+
+impl<'m, 'a, 'p, M, AtCl, const N: usize, const L: usize, D, SM>
+    ConnectionManager<'m, 'a, 'p, M, AtCl, N, L, D, SM>
+where
+    M: RawMutex,
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// Creates a manager of the connection of `modem`, nothing is sent to the modem yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`ModemEvent`] subscriber is available, see
+    /// [`Modem::events`](crate::Modem::events).
+    pub async fn new(
+        modem: &'m SharedModem<'a, M, AtCl, N, L, D, SM>,
+        policy: &'p MaintainPolicy<'p>,
+    ) -> Self {
+        let events = modem.control().lock().await.events();
+        Self {
+            modem,
+            policy,
+            delay: modem.delay(),
+            events,
+            reached: None,
+            attached: None,
+            reported: None,
+        }
+    }
+
+    /// Returns the state of the connection as last brought up.
+    pub fn state(&self) -> ConnectionState {
+        match self.reached {
+            #[cfg(feature = "mqtt")]
+            Some(ConnectionStage::MqttSession) => ConnectionState::MqttConnected,
+            Some(ConnectionStage::Registration) => ConnectionState::Attached,
+            _ => ConnectionState::Detached,
+        }
+    }
+
+    /// Returns the proof of the attachment, while the modem is registered.
+    pub fn attached(&self) -> Option<&Attached> {
+        self.attached.as_ref()
+    }
+
+    /// Brings up the stages that are not up, returning the resulting state.
+    ///
+    /// Fails with the error of the stage whose attempts are used up, or right away if retrying
+    /// can't help, e.g. the SIM PIN is missing or wrong (a [permanent](Error::is_transient)
+    /// error). Without network service, the modem registers again before the next attempt.
+    pub async fn connect<S: RawMutex>(
+        &mut self,
+        progress: &Signal<S, ConnectionProgress>,
+    ) -> Result<ConnectionState, Error> {
+        let policy = self.policy;
+        let retry = &policy.reconnect;
+        let mut current = None;
+        let mut attempt = 0;
+
+        loop {
+            self.apply_events();
+            if self.reached >= Some(ConnectionStage::Registration) && !self.is_registered().await {
+                self.fall_back(ConnectionStage::PdpContext);
+            }
+
+            let Some(stage) = self.next_stage() else {
+                break;
+            };
+            // The attempts go on counting while an earlier stage is brought up again.
+            if current < Some(stage) {
+                current = Some(stage);
+                attempt = 0;
+            }
+
+            self.report(progress, ConnectionProgress::InProgress { stage, attempt });
+            match self.bring_up(stage).await {
+                Ok(()) => self.reached = Some(stage),
+                Err(Error::ModemRebooted) => {
+                    warn!("The modem restarted, bringing the connection up again");
+                    self.reset();
+                    current = None;
+                }
+                Err(err) if err.is_transient() && attempt + 1 < retry.attempts => {
+                    // Without network service, the modem registers again first.
+                    if err.class() == ErrorClass::StateDependent {
+                        self.fall_back(ConnectionStage::PdpContext);
+                    }
+                    let delay = retry.delay(attempt);
+                    warn!(
+                        "Connection stage {:?} failed: {:?}, retrying in {} ms",
+                        stage,
+                        err,
+                        delay.as_millis()
+                    );
+                    delay::sleep(&mut self.delay, delay).await;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    warn!("Connection stage {:?} failed: {:?}", stage, err);
+                    self.report(progress, ConnectionProgress::Failed(stage));
+                    return Err(err);
+                }
+            }
+        }
+
+        let state = self.state();
+        self.report(progress, ConnectionProgress::Connected(state));
+        Ok(state)
+    }
+
+    /// Keeps the connection up, bringing it up again after a failure or a loss.
+    ///
+    /// Runs forever: the connection is checked every
+    /// [`check_interval`](MaintainPolicy::check_interval) or as soon as the modem reports a
+    /// change, a failed connection is retried with the [`reconnect`](MaintainPolicy::reconnect)
+    /// backoff.
+    pub async fn run<S: RawMutex>(&mut self, progress: &Signal<S, ConnectionProgress>) -> ! {
+        let mut failures = 0u8;
+
+        loop {
+            let delay = match self.connect(progress).await {
+                Ok(_) => {
+                    failures = 0;
+                    self.policy.check_interval
+                }
+                Err(_) => {
+                    let delay = self.policy.reconnect.delay(failures);
+                    failures = failures.saturating_add(1);
+                    delay
+                }
+            };
+
+            // Wake up early when the modem reports a change.
+            let change = self.events.next_message_pure();
+            if let Ok(event) = delay::with_timeout(&mut self.delay, delay, change).await {
+                self.apply_event(event);
+            }
+        }
+    }
+
+    fn next_stage(&self) -> Option<ConnectionStage> {
+        let next = match self.reached {
+            None => ConnectionStage::SimReady,
+            Some(ConnectionStage::SimReady) => ConnectionStage::PdpContext,
+            Some(ConnectionStage::PdpContext) => ConnectionStage::Registration,
+            #[cfg(feature = "mqtt")]
+            Some(ConnectionStage::Registration) if self.policy.mqtt.is_some() => {
+                ConnectionStage::MqttSession
+            }
+            Some(_) => return None,
+        };
+        Some(next)
+    }
+
+    async fn bring_up(&mut self, stage: ConnectionStage) -> Result<(), Error> {
+        let control = self.modem.control();
+
+        match stage {
+            ConnectionStage::SimReady => {
+                // Initializes the driver again after a modem restart, a no-op otherwise.
+                control.begin().await?;
+                match control.get_pin_status().await? {
+                    SIMState::Ready => Ok(()),
+                    state => Err(Error::UnexpectedSimState(state)),
+                }
+            }
+            ConnectionStage::PdpContext => control.define_pdp_context().await,
+            ConnectionStage::Registration => {
+                let timeout = self.policy.attach_timeout;
+                let attached = delay::with_timeout(&mut self.delay, timeout, control.attach())
+                    .await
+                    .map_err(|_| Error::Timeout(Operation::NetworkRegistration))??;
+                self.attached = Some(attached);
+                Ok(())
+            }
+            #[cfg(feature = "mqtt")]
+            ConnectionStage::MqttSession => {
+                let (Some(session), Some(attached)) = (&self.policy.mqtt, &self.attached) else {
+                    return Ok(());
+                };
+                let mqtt = self.modem.mqtt();
+                mqtt.configure(session.client_id, session.auth.clone())
+                    .await?;
+                mqtt.connect(attached, session.host, session.port).await
+            }
+        }
+    }
+
+    async fn is_registered(&self) -> bool {
+        matches!(
+            self.modem.control().get_network_registration_state(),
+            NetworkRegistrationState::RegisteredHome | NetworkRegistrationState::RegisteredRoaming
+        )
+    }
+
+    fn apply_events(&mut self) {
+        while let Some(event) = self.events.try_next_message_pure() {
+            self.apply_event(event);
+        }
+    }
+
+    fn apply_event(&mut self, event: ModemEvent) {
+        match event {
+            // The registration is read from the driver state instead, the changes received while
+            // registering would undo it.
+            ModemEvent::RegistrationChanged(_) => {}
+            #[cfg(feature = "mqtt")]
+            ModemEvent::MqttDisconnected(_) => self.fall_back(ConnectionStage::Registration),
+            ModemEvent::Shutdown | ModemEvent::Started => self.reset(),
+            _ => {}
+        }
+    }
+
+    /// Takes the connection back to `stage` if it went further.
+    fn fall_back(&mut self, stage: ConnectionStage) {
+        if self.reached > Some(stage) {
+            self.reached = Some(stage);
+        }
+        if self.reached < Some(ConnectionStage::Registration) {
+            self.attached = None;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.reached = None;
+        self.attached = None;
+    }
+
+    fn report<S: RawMutex>(
+        &mut self,
+        progress: &Signal<S, ConnectionProgress>,
+        current: ConnectionProgress,
+    ) {
+        if self.reported != Some(current) {
+            debug!("Connection progress: {:?}", current);
+            progress.signal(current);
+            self.reported = Some(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_state() {
+        let registering = ConnectionProgress::InProgress {
+            stage: ConnectionStage::Registration,
+            attempt: 1,
+        };
+        assert_eq!(registering.state(), ConnectionState::Detached);
+        assert_eq!(
+            ConnectionProgress::Failed(ConnectionStage::SimReady).state(),
+            ConnectionState::Detached
+        );
+        assert_eq!(
+            ConnectionProgress::Connected(ConnectionState::Attached).state(),
+            ConnectionState::Attached
+        );
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_progress_state_mqtt() {
+        assert_eq!(
+            ConnectionProgress::Failed(ConnectionStage::MqttSession).state(),
+            ConnectionState::Attached
+        );
+    }
+}
@@ -0,0 +1,1242 @@
+//! Scripted [`AtatClient`] to test the code driving the [`Modem`](crate::Modem) on the host,
+//! without hardware.
+//!
+//! The [`ScriptedClient`] checks every command sent against the script and answers it with the
+//! scripted response. The URCs of the script are fed through the ingress, so they reach the
+//! [`UrcHandler`](crate::UrcHandler) and the waiting operations like the real ones.
+//!
+//! ```ignore
+//! let urc_chan = DefaultUrcChannel::new();
+//! let res_slot = ResponseSlot::<INGRESS_BUF_SIZE>::new();
+//! let state = ModemState::new();
+//! let mut buf = [0; INGRESS_BUF_SIZE];
+//! let ingress = Ingress::new(AtDigester::<Urc>::new(), &mut buf, &res_slot, &urc_chan);
+//!
+//! let client = ScriptedClient::from_transcript(ingress, r#"
+//!     > AT+CCLK?
+//!     < +CCLK: "24/05/30,13:22:45+08"
+//!     < OK
+//! "#)?;
+//! let mut modem = Modem::new_with_delay(client, &urc_chan, &state, config, YieldDelay);
+//! let clock = block_on(modem.get_time(ClockSyncPolicy::NeverConnect))?;
+//! ```
+
+use std::{
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    vec,
+    vec::Vec,
+};
+
+use atat::{AtatCmd, AtatIngress, asynch::AtatClient};
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
+
+/// A command expected by the [`ScriptedClient`] and the reply of the modem.
+#[derive(Debug)]
+pub struct Exchange {
+    command: String,
+    response: Result<String, atat::Error>,
+    urcs: Vec<String>,
+}
+
+impl Exchange {
+    /// Answers the command with the information text `text`, the command succeeds without any
+    /// by default.
+    pub fn respond(&mut self, text: &str) -> &mut Self {
+        self.response = Ok(text.to_string());
+        self
+    }
+
+    /// Fails the command with `err`, e.g. [`atat::Error::CmeError`].
+    pub fn fail(&mut self, err: atat::Error) -> &mut Self {
+        self.response = Err(err);
+        self
+    }
+
+    /// Sends the URC `line` (e.g. `+CEREG: 1`) before the command is answered.
+    pub fn urc(&mut self, line: &str) -> &mut Self {
+        self.urcs.push(line.to_string());
+        self
+    }
+}
+
+/// [`AtatClient`] answering the commands from a script, see the [module](self) documentation.
+///
+/// A command that doesn't match the script panics, as does dropping the client before the whole
+/// script was played.
+pub struct ScriptedClient<I: AtatIngress> {
+    ingress: I,
+    script: VecDeque<Exchange>,
+}
+
+impl<I: AtatIngress> ScriptedClient<I> {
+    /// Creates a client with an empty script, the URCs are fed through `ingress`.
+    pub fn new(ingress: I) -> Self {
+        Self {
+            ingress,
+            script: VecDeque::new(),
+        }
+    }
+
+    /// Creates a client playing a transcript of the AT traffic, one line per command, response
+    /// or URC:
+    ///
+    /// ```text
+    /// > AT+CFUN=1           command, without the AT termination
+    /// < OK                  command succeeded
+    /// > AT+CGPADDR=1
+    /// < +CGPADDR: 1,"10.0.0.1"   information text of the response
+    /// < OK
+    /// > AT+COPS=0
+    /// < ERROR               command failed
+    /// > AT+CPIN?
+    /// < +CME ERROR: 14      command failed with a CME error code
+    /// ! +CEREG: 1           URC sent before the response of the previous command
+    /// ```
+    ///
+    /// Empty lines and lines starting with `#` are ignored. Returns the invalid line on failure.
+    pub fn from_transcript(ingress: I, transcript: &str) -> Result<Self, String> {
+        let mut client = Self::new(ingress);
+        let mut text: Option<Vec<&str>> = None;
+
+        for line in transcript.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((marker, content)) = line.get(..1).zip(line.get(1..)) else {
+                return Err(line.to_string());
+            };
+            let content = content.trim_start();
+            match marker {
+                ">" if text.is_none() => {
+                    client.expect(content);
+                    text = Some(Vec::new());
+                }
+                "<" => {
+                    let (Some(exchange), Some(lines)) = (client.script.back_mut(), text.as_mut())
+                    else {
+                        return Err(line.to_string());
+                    };
+                    match content {
+                        "OK" => exchange.respond(&lines.join("\r\n")),
+                        "ERROR" => exchange.fail(atat::Error::Error),
+                        _ if content.starts_with("+CME ERROR:") => {
+                            let Ok(code) = content["+CME ERROR:".len()..].trim().parse::<u16>()
+                            else {
+                                return Err(line.to_string());
+                            };
+                            exchange.fail(atat::Error::CmeError(code.into()))
+                        }
+                        _ => {
+                            lines.push(content);
+                            continue;
+                        }
+                    };
+                    text = None;
+                }
+                "!" => {
+                    let Some(exchange) = client.script.back_mut() else {
+                        return Err(line.to_string());
+                    };
+                    exchange.urc(content);
+                }
+                _ => return Err(line.to_string()),
+            }
+        }
+
+        match text {
+            Some(_) => Err("missing final result code".to_string()),
+            None => Ok(client),
+        }
+    }
+
+    /// Appends the `command` (e.g. `AT+CFUN=1`) to the script, it succeeds without information
+    /// text unless set otherwise on the returned [`Exchange`].
+    pub fn expect(&mut self, command: &str) -> &mut Exchange {
+        self.script.push_back(Exchange {
+            command: command.to_string(),
+            response: Ok(String::new()),
+            urcs: Vec::new(),
+        });
+        self.script.back_mut().unwrap()
+    }
+
+    /// Returns the number of commands of the script not sent yet.
+    pub fn remaining(&self) -> usize {
+        self.script.len()
+    }
+}
+
+impl<I: AtatIngress> AtatClient for ScriptedClient<I> {
+    async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, atat::Error> {
+        let mut buf = vec![0; Cmd::MAX_LEN];
+        let len = cmd.write(&mut buf);
+        let sent = String::from_utf8_lossy(&buf[..len]);
+        let sent = sent.trim_end_matches(['\r', '\n']);
+
+        let Some(exchange) = self.script.pop_front() else {
+            panic!("unexpected command {sent:?}, the script is over");
+        };
+        assert_eq!(sent, exchange.command, "unexpected command");
+
+        for urc in &exchange.urcs {
+            if self
+                .ingress
+                .try_write(format!("\r\n{urc}\r\n").as_bytes())
+                .is_err()
+            {
+                panic!("URC {urc:?} dropped, the URC channel is full");
+            }
+        }
+
+        if !Cmd::EXPECTS_RESPONSE_CODE {
+            return cmd.parse(Ok(&[]));
+        }
+        let text = exchange.response?;
+        cmd.parse(Ok(text.as_bytes()))
+    }
+}
+
+impl<I: AtatIngress> Drop for ScriptedClient<I> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() && !self.script.is_empty() {
+            panic!(
+                "{} scripted commands not sent, next {:?}",
+                self.script.len(),
+                self.script[0].command
+            );
+        }
+    }
+}
+
+/// Delay elapsing as soon as the other tasks ran, so scripted operations don't wait for their
+/// retry delays.
+///
+/// The timeouts expire as soon as the awaited operation is pending, the awaited URCs must be
+/// scripted with the command starting the operation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct YieldDelay;
+
+impl DelayNs for YieldDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        embassy_futures::yield_now().await
+    }
+}
+
+/// Virtual clock, advanced by the delays of the driver instead of really sleeping, so tests can
+/// check how long an operation would have taken, e.g. the polls of [`Modem::get_time`].
+///
+/// The clones share the same time. Like the [`YieldDelay`], a delay elapses as soon as the other
+/// tasks ran, the clock is only advanced by the delays that elapsed: a timeout dropped because
+/// the awaited operation completed first doesn't count.
+///
+/// ```ignore
+/// let clock = VirtualClock::new();
+/// let mut modem = Modem::new_with_delay(client, &urc_chan, &state, config, clock.clone());
+/// block_on(modem.get_time(ClockSyncPolicy::UseExisting))?;
+/// assert!(clock.now() < Duration::from_secs(5));
+/// ```
+///
+/// [`Modem::get_time`]: crate::Modem::get_time
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock {
+    /// Nanoseconds elapsed since the clock was created.
+    now: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the time elapsed since the clock was created.
+    pub fn now(&self) -> Duration {
+        Duration::from_nanos(self.now.load(Ordering::Relaxed))
+    }
+
+    /// Advances the clock by `duration`, e.g. to simulate the time spent between two operations.
+    pub fn advance(&self, duration: Duration) {
+        let ns = duration.as_micros().saturating_mul(1_000);
+        self.now.fetch_add(ns, Ordering::Relaxed);
+    }
+}
+
+impl DelayNs for VirtualClock {
+    async fn delay_ns(&mut self, ns: u32) {
+        embassy_futures::yield_now().await;
+        self.now.fetch_add(u64::from(ns), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use atat::{AtDigester, Ingress, ResponseSlot, UrcChannel};
+    use embassy_futures::{
+        block_on,
+        join::join,
+        select::{Either, select},
+    };
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+
+    use super::*;
+    use crate::{
+        calendar,
+        channel::{INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS},
+        command::{Urc, UrcParser},
+        config::{ClockSyncPolicy, ModemConfig, PingOptions, UrcPolicy},
+        error::{CmeError, Error, ErrorClass, Operation},
+        mobile_equipment::types::PowerState,
+        modem::{Attached, Detached, Modem, ModemState},
+        network::types::NetworkRegistrationState,
+        nvm::{NVM_CHUNK_LEN, types::DataType},
+        shared::SharedModem,
+        system_features::types::RingIndicatorMode,
+        wake::ModemWaker,
+    };
+
+    type TestIngress<'a> =
+        Ingress<'a, AtDigester<Urc>, UrcParser, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+    /// The transcripts answer as an attached modem.
+    fn attached_token() -> Attached {
+        Attached::new_unchecked()
+    }
+
+    /// The transcripts answer as a modem with the radio off.
+    fn detached_token() -> Detached {
+        Detached::new_unchecked()
+    }
+
+    struct Harness {
+        urc_chan: UrcChannel<Urc, URC_CAPACITY, URC_SUBSCRIBERS>,
+        res_slot: ResponseSlot<INGRESS_BUF_SIZE>,
+        state: ModemState,
+        buf: [u8; INGRESS_BUF_SIZE],
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self {
+                urc_chan: UrcChannel::new(),
+                res_slot: ResponseSlot::new(),
+                state: ModemState::new(),
+                buf: [0; INGRESS_BUF_SIZE],
+            }
+        }
+
+        fn modem(
+            &mut self,
+            transcript: &str,
+        ) -> Modem<'_, ScriptedClient<TestIngress<'_>>, URC_CAPACITY, URC_SUBSCRIBERS, YieldDelay>
+        {
+            self.modem_with_delay(transcript, YieldDelay)
+        }
+
+        fn modem_with_delay<D: DelayNs + Clone>(
+            &mut self,
+            transcript: &str,
+            delay: D,
+        ) -> Modem<'_, ScriptedClient<TestIngress<'_>>, URC_CAPACITY, URC_SUBSCRIBERS, D> {
+            let ingress = Ingress::new(
+                AtDigester::<Urc>::new(),
+                &mut self.buf,
+                &self.res_slot,
+                &self.urc_chan,
+            );
+            let client = ScriptedClient::from_transcript(ingress, transcript).unwrap();
+            Modem::new_with_delay(
+                client,
+                &self.urc_chan,
+                &self.state,
+                ModemConfig::default(),
+                delay,
+            )
+        }
+    }
+
+    #[test]
+    fn test_from_transcript() {
+        let mut harness = Harness::new();
+        let ingress = Ingress::new(
+            AtDigester::<Urc>::new(),
+            &mut harness.buf,
+            &harness.res_slot,
+            &harness.urc_chan,
+        );
+
+        let mut client = ScriptedClient::from_transcript(
+            ingress,
+            "# Comment
+            > AT+CGPADDR=1
+            < +CGPADDR: 1,\"10.0.0.1\"
+            < OK
+
+            > AT+COPS=0
+            ! +CEREG: 2
+            < ERROR",
+        )
+        .unwrap();
+        assert_eq!(client.remaining(), 2);
+        assert_eq!(
+            client.script[0].response.as_deref(),
+            Ok("+CGPADDR: 1,\"10.0.0.1\"")
+        );
+        assert_eq!(client.script[1].response, Err(atat::Error::Error));
+        assert_eq!(client.script[1].urcs, ["+CEREG: 2"]);
+        client.script.clear();
+    }
+
+    #[test]
+    fn test_from_transcript_invalid() {
+        let mut harness = Harness::new();
+        for (transcript, err) in [
+            ("! +CEREG: 1", "! +CEREG: 1"),
+            ("> AT+CFUN=1\n> AT+CFUN=0", "> AT+CFUN=0"),
+            ("> AT+CFUN=1", "missing final result code"),
+        ] {
+            let ingress = Ingress::new(
+                AtDigester::<Urc>::new(),
+                &mut harness.buf,
+                &harness.res_slot,
+                &harness.urc_chan,
+            );
+            let res = ScriptedClient::from_transcript(ingress, transcript);
+            assert_eq!(res.err().as_deref(), Some(err));
+        }
+    }
+
+    #[test]
+    fn test_get_time() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CCLK?
+            < +CCLK: \"24/05/30,13:22:45+08\"
+            < OK
+            > AT+CCLK?
+            < +CCLK: \"70/01/01,00:00:10+00\"
+            < OK",
+        );
+
+        let clock = block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap();
+        assert_eq!(
+            clock.time().map(calendar::unix_seconds),
+            Some(1_717_068_165)
+        );
+
+        let err = block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap_err();
+        assert_eq!(err, Error::ClockInvalid);
+    }
+
+    /// A busy SIM is waited for, repeating the command without network service doesn't help.
+    #[test]
+    fn test_send_retry_transient_only() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CCLK?
+            < +CME ERROR: 14
+            > AT+CCLK?
+            < +CCLK: \"24/05/30,13:22:45+08\"
+            < OK
+            > AT+CCLK?
+            < +CME ERROR: 30",
+        );
+
+        block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap();
+        let err = block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap_err();
+        assert_eq!(err.class(), ErrorClass::StateDependent);
+    }
+
+    #[test]
+    fn test_wake_before_command() {
+        struct CountingWaker(std::cell::Cell<u32>);
+
+        impl ModemWaker for CountingWaker {
+            fn wake(&self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let waker = CountingWaker(std::cell::Cell::new(0));
+        let mut harness = Harness::new();
+        harness.state.set_asleep();
+        let mut modem = harness.modem(
+            "> AT
+            < ERROR
+            > AT
+            < OK
+            > AT+CCLK?
+            < +CCLK: \"24/05/30,13:22:45+08\"
+            < OK
+            > AT+CCLK?
+            < +CCLK: \"24/05/30,13:22:46+08\"
+            < OK",
+        );
+        modem.set_waker(&waker);
+
+        block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap();
+        // Awake now, the next command is sent right away.
+        block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap();
+        assert_eq!(waker.0.get(), 1);
+    }
+
+    #[test]
+    fn test_asleep_without_waker() {
+        let mut harness = Harness::new();
+        harness.state.set_asleep();
+        let mut modem = harness.modem("");
+
+        // Nothing wakes the modem, the command is never sent.
+        let err = block_on(modem.get_time(ClockSyncPolicy::NeverConnect)).unwrap_err();
+        assert_eq!(err, Error::Timeout(Operation::Wake));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNDNSLKUP=\"example.com\"
+            < +SQNDNSLKUP: \"example.com\",\"93.184.215.14\"
+            < OK",
+        );
+        let attached = attached_token();
+
+        let addresses = block_on(modem.resolve(&attached, "example.com")).unwrap();
+        assert_eq!(
+            addresses.first(),
+            Some(core::net::Ipv4Addr::new(93, 184, 215, 14).into())
+        );
+
+        let hostname = "a".repeat(crate::dns::HOSTNAME_LEN + 1);
+        let err = block_on(modem.resolve(&attached, &hostname)).unwrap_err();
+        assert_eq!(err, Error::CommandTooLong);
+    }
+
+    #[test]
+    fn test_icmp_ping() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+PING=\"8.8.8.8\",2,32,1,5
+            < +PING: 1,\"8.8.8.8\",62,117
+            < +PING: 2,\"8.8.8.8\",-1,-1
+            < OK",
+        );
+
+        let options = PingOptions {
+            count: 2,
+            ..PingOptions::default()
+        };
+        let replies = block_on(modem.icmp_ping(&attached_token(), "8.8.8.8", &options)).unwrap();
+        assert_eq!(replies.replies.len(), 2);
+        assert_eq!(replies.received(), 1);
+    }
+
+    #[cfg(feature = "socket")]
+    #[test]
+    fn test_socket_receive() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSRECV=1,4
+            < +SQNSRECV: 1,3
+            < 0D0A00
+            < OK",
+        );
+
+        let mut buf = [0; 4];
+        let len = block_on(modem.socket_receive(&attached_token(), 1, &mut buf)).unwrap();
+        assert_eq!(&buf[..len], b"\r\n\0");
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_query() {
+        use crate::http::types::QueryCommand;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNHTTPQRY=1,0,\"/status\"
+            # The response of another profile is skipped.
+            ! +SQNHTTPRING: 2,404,\"\",0
+            ! +SQNHTTPRING: 1,200,\"application/json\",11
+            < OK
+            > AT+SQNHTTPRCV=1,8
+            < <<<{\"on\":tr
+            < OK
+            > AT+SQNHTTPRCV=1,8
+            < <<<ue}
+            < OK",
+        );
+
+        let flow = async {
+            let ring = modem
+                .http_query(&attached_token(), 1, QueryCommand::Get, "/status")
+                .await
+                .unwrap();
+            assert_eq!(ring.status_code, 200);
+            assert_eq!(ring.data_size, 11);
+
+            let mut body = Vec::new();
+            let mut buf = [0; 8];
+            while body.len() < ring.data_size as usize {
+                let len = modem.http_receive(1, &mut buf).await.unwrap();
+                body.extend_from_slice(&buf[..len]);
+            }
+            assert_eq!(body, b"{\"on\":true}");
+        };
+        block_on(flow);
+    }
+
+    #[test]
+    fn test_read_diagnostic_log() {
+        use crate::diagnostics::types::DiagnosticLog;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSLOGREAD=0,0,4
+            < +SQNSLOGREAD: 4
+            < 41535352
+            < OK
+            > AT+SQNSLOGREAD=0,4,4
+            < +SQNSLOGREAD: 2
+            < 5421
+            < OK
+            > AT+SQNSLOGREAD=0,6,4
+            < +SQNSLOGREAD: 0
+            < OK",
+        );
+
+        let flow = async {
+            let mut log = Vec::new();
+            let mut buf = [0; 4];
+            loop {
+                let offset = log.len() as u32;
+                let len = modem
+                    .read_diagnostic_log(DiagnosticLog::Crash, offset, &mut buf)
+                    .await
+                    .unwrap();
+                if len == 0 {
+                    break;
+                }
+                log.extend_from_slice(&buf[..len]);
+            }
+            assert_eq!(log, b"ASSRT!");
+        };
+        block_on(flow);
+    }
+
+    #[cfg(feature = "socket")]
+    #[test]
+    fn test_data_usage() {
+        let mut transcript = String::from(
+            "> AT+SQNSI=1
+            < +SQNSI: 1,1200,3400,0,0
+            < OK
+            > AT+SQNSI=2
+            < +SQNSI: 2,56,78,12,0
+            < OK",
+        );
+        for conn_id in 3..=6 {
+            transcript.push_str(&format!(
+                "\n> AT+SQNSI={conn_id}\n< +SQNSI: {conn_id},0,0,0,0\n< OK"
+            ));
+        }
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(&transcript);
+
+        let usage = block_on(modem.data_usage()).unwrap();
+        assert_eq!(usage.sent, 1256);
+        assert_eq!(usage.received, 3478);
+    }
+
+    #[test]
+    fn test_rf_test_session() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CFUN=5
+            < OK
+            > AT+SMCWRX=300
+            < +SMCWRX: -67
+            < OK
+            > AT+SMCWTX=1,18300,-10
+            < OK
+            > AT+SMCWTX=0
+            < OK
+            > AT+CFUN=0
+            < OK",
+        );
+
+        block_on(async {
+            let session = modem
+                .enter_manufacturing_mode(detached_token())
+                .await
+                .unwrap();
+            assert_eq!(modem.rf_test_measure(&session, 300).await, Ok(-67));
+            modem.rf_test_transmit(&session, 18300, -10).await.unwrap();
+            modem.leave_manufacturing_mode(session).await.unwrap();
+        });
+        assert_eq!(block_on(modem.power_state()), Ok(PowerState::Minimum));
+    }
+
+    #[test]
+    fn test_set_urc_policy_ring_indicator() {
+        let mut transcript = String::from("> AT+CEREG=1\n< OK\n> AT+CGEREP=0\n< OK\n");
+        if cfg!(feature = "sms") {
+            transcript.push_str("> AT+CNMI=2,0\n< OK\n");
+        }
+        transcript.push_str("> AT+SQNRICFG=3\n< OK");
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(&transcript);
+
+        let policy = UrcPolicy {
+            ring_indicator: Some(RingIndicatorMode::All),
+            ..UrcPolicy::default()
+        };
+        block_on(modem.set_urc_policy(policy)).unwrap();
+    }
+
+    #[test]
+    fn test_set_autoconnect() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNAUTOCONNECT?
+            < +SQNAUTOCONNECT: 1
+            < OK
+            > AT+SQNAUTOCONNECT=0
+            < OK
+            > AT+SQNAUTOCONNECT?
+            < +SQNAUTOCONNECT: 0
+            < OK",
+        );
+
+        block_on(modem.set_autoconnect(false)).unwrap();
+        // Already disabled, nothing is written.
+        block_on(modem.set_autoconnect(false)).unwrap();
+    }
+
+    #[test]
+    fn test_set_carrier_profile() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNCTM?
+            < +SQNCTM: \"standard\"
+            < OK
+            > AT+SQNCTM=\"att\"
+            < OK
+            > AT^RESET
+            ! +SYSSTART
+            < OK
+            > AT+SQNCTM?
+            < +SQNCTM: \"att\"
+            < OK",
+        );
+        let mut urc_handler = modem.urc_handler();
+
+        let flow = async {
+            let detached = detached_token();
+            assert_eq!(modem.set_carrier_profile(&detached, "att").await, Ok(true));
+            assert_eq!(modem.set_carrier_profile(&detached, "att").await, Ok(false));
+        };
+        match block_on(select(flow, urc_handler.run())) {
+            Either::First(()) => {}
+            Either::Second(never) => never,
+        }
+    }
+
+    #[test]
+    fn test_lte_connect() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CFUN=1
+            < OK
+            > AT+COPS=0
+            ! +CEREG: 2
+            ! +CEREG: 1
+            < OK",
+        );
+        let mut urc_handler = modem.urc_handler();
+
+        match block_on(select(
+            modem.lte_connect(detached_token()),
+            urc_handler.run(),
+        )) {
+            Either::First(attached) => assert!(attached.is_ok()),
+            Either::Second(never) => never,
+        }
+        assert_eq!(
+            modem.get_network_registration_state(),
+            NetworkRegistrationState::RegisteredHome
+        );
+    }
+
+    /// The registration is waited on without polling, no time passes on the modem side.
+    #[test]
+    fn test_lte_connect_disconnect_without_polling() {
+        let mut harness = Harness::new();
+        let clock = VirtualClock::new();
+        let mut modem = harness.modem_with_delay(
+            "> AT+CFUN=1
+            < OK
+            > AT+COPS=0
+            ! +CEREG: 2
+            ! +CEREG: 5
+            < OK
+            > AT+CFUN=0
+            ! +CEREG: 0
+            < OK",
+            clock.clone(),
+        );
+        let mut urc_handler = modem.urc_handler();
+
+        let flow = async {
+            let attached = modem.lte_connect(detached_token()).await?;
+            modem.lte_disconnect(attached).await
+        };
+        match block_on(select(flow, urc_handler.run())) {
+            Either::First(detached) => assert!(detached.is_ok()),
+            Either::Second(never) => never,
+        }
+        assert_eq!(clock.now(), Duration::ZERO);
+    }
+
+    /// Without coverage the modem keeps searching, the attach gives up after the timeout.
+    #[test]
+    fn test_lte_connect_registration_timeout() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CFUN=1
+            < OK
+            > AT+COPS=0
+            ! +CEREG: 2
+            < OK",
+        );
+        let mut urc_handler = modem.urc_handler();
+
+        match block_on(select(
+            modem.lte_connect(detached_token()),
+            urc_handler.run(),
+        )) {
+            Either::First(attached) => assert_eq!(
+                attached.unwrap_err(),
+                Error::Timeout(Operation::NetworkRegistration)
+            ),
+            Either::Second(never) => never,
+        }
+    }
+
+    #[test]
+    fn test_nvm_writer() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSNVW=\"certificate\",19,12
+            < OK
+            # The modem only answers the last part, the OK of the others is ignored.
+            > -----
+            < OK
+            > BEGIN
+            < OK
+            > --
+            < OK",
+        );
+
+        let flow = async {
+            let mut writer = modem
+                .nvm_writer(DataType::Certificate, 19, 12)
+                .await
+                .unwrap();
+            writer.write(b"-----").await.unwrap();
+            writer.write(b"BEGIN").await.unwrap();
+            assert_eq!(writer.write(b"---").await, Err(Error::PayloadTooLong));
+            assert_eq!(writer.remaining(), 2);
+            writer.write(b"--").await.unwrap();
+            assert_eq!(writer.write(b"").await, Err(Error::PayloadTooLong));
+        };
+        block_on(flow);
+    }
+
+    #[cfg(feature = "gnss")]
+    /// A large entry is sent in slices, the command buffer only has to fit one of them.
+    #[test]
+    fn test_nvm_write_chunks() {
+        let data = "a".repeat(NVM_CHUNK_LEN + 476);
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(&format!(
+            "> AT+SQNSNVW=\"certificate\",19,{}
+            < OK
+            > {}
+            < OK
+            > {}
+            < OK",
+            data.len(),
+            &data[..NVM_CHUNK_LEN],
+            &data[NVM_CHUNK_LEN..],
+        ));
+
+        block_on(modem.nvm_write(DataType::Certificate, 19, data.as_bytes())).unwrap();
+    }
+
+    #[test]
+    fn test_upload_gnss_assistance() {
+        use crate::gnss::types::GnssAssistanceType;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+LPGNSSUPLOADASSISTANCE=0,7
+            < OK
+            > almanac
+            < OK",
+        );
+
+        block_on(modem.upload_gnss_assistance(GnssAssistanceType::Almanac, b"almanac")).unwrap();
+    }
+
+    /// The modem left the prompt when it rejected the data, the data isn't sent again.
+    #[test]
+    fn test_nvm_writer_not_retried() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSNVW=\"certificate\",19,2
+            < OK
+            > --
+            < +CME ERROR: 14",
+        );
+
+        let flow = async {
+            let mut writer = modem
+                .nvm_writer(DataType::Certificate, 19, 2)
+                .await
+                .unwrap();
+            assert!(matches!(
+                writer.write(b"--").await,
+                Err(Error::Cme(CmeError::SimBusy, _))
+            ));
+        };
+        block_on(flow);
+    }
+
+    /// The prompt of a rejected write never opened, the next command isn't preceded by an abort.
+    #[test]
+    fn test_nvm_writer_rejected() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSNVW=\"certificate\",19,12
+            < ERROR
+            > AT
+            < OK",
+        );
+
+        let flow = async {
+            assert!(
+                modem
+                    .nvm_writer(DataType::Certificate, 19, 12)
+                    .await
+                    .is_err()
+            );
+            modem.send(&command::AT).await.unwrap();
+        };
+        block_on(flow);
+    }
+
+    #[test]
+    fn test_nvm_write_reserved_index() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNSNVW=\"certificate\",5,2
+            < OK
+            > --
+            < OK",
+        );
+
+        let flow = async {
+            for index in [0, 4, 7, 10] {
+                assert_eq!(
+                    modem.nvm_write(DataType::Certificate, index, b"--").await,
+                    Err(Error::ReservedNvmIndex(index))
+                );
+            }
+            modem
+                .nvm_write(DataType::Certificate, 5, b"--")
+                .await
+                .unwrap();
+        };
+        block_on(flow);
+    }
+
+    type TestSharedModem<'a> = SharedModem<
+        'a,
+        CriticalSectionRawMutex,
+        ScriptedClient<TestIngress<'a>>,
+        URC_CAPACITY,
+        URC_SUBSCRIBERS,
+        YieldDelay,
+    >;
+
+    /// A handle waiting for its URC lets the other handles send their commands meanwhile.
+    #[test]
+    fn test_shared_modem_waits_without_lock() {
+        let mut harness = Harness::new();
+        let shared: TestSharedModem<'_> = SharedModem::new(harness.modem(
+            "> AT+SQNNTP=\"pool.ntp.org\"
+            < OK
+            # Sent by the other handle during the synchronization.
+            > AT
+            ! +SQNNTP: 0
+            < OK
+            > AT+CCLK?
+            < +CCLK: \"24/05/30,13:22:45+08\"
+            < OK",
+        ));
+        let (ntp, control) = (shared.control(), shared.control());
+
+        let (clock, pinged) = block_on(join(
+            ntp.sync_time_ntp(&attached_token(), "pool.ntp.org"),
+            control.ping(),
+        ));
+        clock.unwrap();
+        pinged.unwrap();
+    }
+
+    /// The registration state is read while the handle is locked, e.g. by an attach.
+    #[test]
+    fn test_shared_modem_registration_state_without_lock() {
+        let mut harness = Harness::new();
+        let shared: TestSharedModem<'_> = SharedModem::new(harness.modem(""));
+        let control = shared.control();
+
+        let _modem = block_on(control.lock());
+        assert_eq!(
+            control.get_network_registration_state(),
+            NetworkRegistrationState::NotSearching
+        );
+    }
+
+    /// The other handles wait for the payload of a prompt, their commands would land in it.
+    #[test]
+    fn test_shared_modem_prompt_held() {
+        let mut harness = Harness::new();
+        let shared: TestSharedModem<'_> = SharedModem::new(harness.modem(
+            "> AT+SQNSNVW=\"certificate\",19,4
+            < OK
+            > --
+            < OK
+            > --
+            < OK
+            > AT
+            < OK",
+        ));
+        let (nvm, control) = (shared.control(), shared.control());
+
+        let flow = async {
+            let mut modem = nvm.lock().await;
+            let mut writer = modem
+                .nvm_writer(DataType::Certificate, 19, 4)
+                .await
+                .unwrap();
+            let write = async {
+                writer.write(b"--").await?;
+                writer.write(b"--").await
+            };
+            let (pinged, written) = join(control.ping(), write).await;
+            pinged.unwrap();
+            written.unwrap();
+        };
+        block_on(flow);
+    }
+
+    /// The payload is checked before the prompt, nothing is sent.
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_mqtt_send_payload_too_long() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem("");
+
+        let payload = [0; crate::mqtt::MQTT_PAYLOAD_LEN + 1];
+        let sent =
+            block_on(modem.mqtt_send("topic", crate::mqtt::types::Qos::AtMostOnce, &payload));
+        assert_eq!(sent, Err(Error::PayloadTooLong));
+    }
+
+    /// The configuration is checked before the TLS profile, nothing is sent.
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_aws_iot_connect_legacy_endpoint() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem("");
+
+        let config = crate::AwsIotConfig::new(
+            "a1b2c3d4e5f6g7.iot.eu-west-1.amazonaws.com",
+            "walter-01",
+            1,
+            5,
+            6,
+            6,
+        );
+        let connected = block_on(modem.aws_iot_connect(&attached_token(), &config));
+        assert_eq!(
+            connected,
+            Err(Error::AwsIot(crate::AwsIotError::NotAtsEndpoint))
+        );
+    }
+
+    /// Without a SAS token the device needs a client certificate, nothing is sent.
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_azure_iot_connect_missing_credentials() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem("");
+
+        let config = crate::AzureIotConfig {
+            hub: "myhub.azure-devices.net",
+            device_id: "walter-01",
+            sp_id: 1,
+            ca_cert_id: 5,
+            client_cert: None,
+        };
+        let connected = block_on(modem.azure_iot_connect(&attached_token(), &config, None));
+        assert_eq!(
+            connected,
+            Err(Error::AzureIot(crate::AzureIotError::MissingCredentials))
+        );
+    }
+
+    #[cfg(feature = "nidd")]
+    #[test]
+    fn test_nidd_send_receive() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CSODCP=1,3,\"0A0B0C\"
+            ! +CRTDCP: 1,2,\"CAFE\"
+            < OK",
+        );
+        let mut urc_handler = modem.urc_handler();
+
+        let flow = async {
+            modem
+                .nidd_send(&attached_token(), &[0x0A, 0x0B, 0x0C])
+                .await
+                .unwrap();
+            modem.nidd_receive(Duration::from_secs(1)).await
+        };
+        let received = match block_on(select(flow, urc_handler.run())) {
+            Either::First(received) => received.unwrap(),
+            Either::Second(never) => never,
+        };
+        assert_eq!(received.payload().as_deref(), Some(&[0xCA, 0xFE][..]));
+
+        let payload = [0; crate::nidd::NIDD_PAYLOAD_LEN + 1];
+        let sent = block_on(modem.nidd_send(&attached_token(), &payload));
+        assert_eq!(sent, Err(Error::PayloadTooLong));
+    }
+
+    #[cfg(feature = "nidd")]
+    #[test]
+    fn test_control_plane_send_last_report() {
+        use crate::nidd::types::ReleaseAssistance;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+CSODCP=3,2,\"BEEF\",1
+            < OK",
+        );
+
+        block_on(modem.control_plane_send(
+            &attached_token(),
+            3,
+            &[0xBE, 0xEF],
+            Some(ReleaseAssistance::NoFurtherData),
+            None,
+        ))
+        .unwrap();
+    }
+
+    #[cfg(feature = "lwm2m")]
+    #[test]
+    fn test_lwm2m_register() {
+        use crate::lwm2m::types::ClientEvent;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(
+            "> AT+SQNLWM2MENABLE=1
+            ! +SQNLWM2MEVT: 1
+            ! +SQNLWM2MEVT: 2
+            ! +SQNLWM2MEVT: 4
+            < OK
+            > AT+SQNLWM2MENABLE=1
+            ! +SQNLWM2MEVT: 1
+            ! +SQNLWM2MEVT: 3
+            < OK",
+        );
+        let attached = attached_token();
+
+        block_on(modem.lwm2m_register(&attached)).unwrap();
+        let err = block_on(modem.lwm2m_register(&attached)).unwrap_err();
+        assert_eq!(err, Error::Lwm2mRegistration(ClientEvent::BootstrapFailed));
+    }
+
+    #[test]
+    fn test_get_time_clock_sync_timeout() {
+        const UNSET: &str = "> AT+CCLK?
+            < +CCLK: \"70/01/01,00:00:10+00\"
+            < OK
+            ";
+
+        let mut harness = Harness::new();
+        let clock = VirtualClock::new();
+        let transcript = format!(
+            "{UNSET}
+            > AT+CFUN=1
+            < OK
+            > AT+COPS=0
+            ! +CEREG: 1
+            < OK
+            {}
+            > AT+CFUN=0
+            ! +CEREG: 0
+            < OK",
+            UNSET.repeat(5)
+        );
+        let mut modem = harness.modem_with_delay(&transcript, clock.clone());
+        let mut urc_handler = modem.urc_handler();
+
+        match block_on(select(
+            modem.get_time(ClockSyncPolicy::ConnectIfNeeded),
+            urc_handler.run(),
+        )) {
+            Either::First(res) => assert_eq!(res.unwrap_err(), Error::ClockSynchronization),
+            Either::Second(never) => never,
+        }
+
+        // The 5 polls of the clock, the registration and detach are waited on without delay.
+        let retry = ModemConfig::default().clock_sync_retry;
+        let polls = retry.delay * u32::from(retry.attempts);
+        assert!(clock.now() >= polls, "{:?}", clock.now());
+        assert!(
+            clock.now() < polls + Duration::from_secs(2),
+            "{:?}",
+            clock.now()
+        );
+    }
+
+    #[test]
+    fn test_virtual_clock() {
+        let clock = VirtualClock::new();
+        let mut delay = clock.clone();
+        block_on(delay.delay_ms(1500));
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(clock.now(), Duration::from_secs(2));
+
+        // A delay dropped before elapsing doesn't count.
+        let res = block_on(crate::delay::with_timeout(
+            &mut delay,
+            Duration::from_secs(10),
+            async { 42 },
+        ));
+        assert_eq!(res, Ok(42));
+        assert_eq!(clock.now(), Duration::from_secs(2));
+    }
+}
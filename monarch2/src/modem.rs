@@ -1,72 +1,450 @@
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 
 use atat::{AtatCmd, UrcChannel, UrcSubscription, asynch::AtatClient};
+use embassy_futures::select::{Either, select};
+#[cfg(any(feature = "coap", feature = "nidd"))]
+use embassy_sync::channel::Channel;
 use embassy_sync::{
     blocking_mutex::{
         Mutex,
-        raw::{CriticalSectionRawMutex, NoopRawMutex},
+        raw::{CriticalSectionRawMutex, RawMutex},
     },
+    mutex::{Mutex as AsyncMutex, MutexGuard},
+    pubsub::{PubSubChannel, WaitResult},
     signal::Signal,
 };
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
 use heapless::String;
-use static_cell::StaticCell;
 
-#[cfg(feature = "gm02sp")]
+#[cfg(feature = "coap")]
+use crate::command::coap;
+#[cfg(feature = "http")]
+use crate::command::http;
+#[cfg(feature = "lwm2m")]
+use crate::command::lwm2m;
+#[cfg(feature = "socket")]
+use crate::command::socket;
+#[cfg(feature = "gnss")]
 use crate::{
     Reserved,
     command::{
         device::GetClock,
         gnss::{
-            GetGnssAssitance, ProgramGnss, SetGnssConfig, UpdateGnssAssitance,
-            types::FixSensitivity, urc::GnssFixReady,
+            GetGnssAssistance, PrepareUploadGnssAssistance, ProgramGnss, SetGnssConfig,
+            UpdateGnssAssistance,
+            types::{FixSensitivity, GnssAssistanceType, GnssError},
+            urc::GnssFixReady,
         },
     },
 };
+#[cfg(feature = "mqtt")]
 use crate::{
+    aws::AwsIotConfig,
+    azure::{AZURE_IOT_PORT, AzureIotConfig, AzureSasToken},
+    capacity::MqttCredential,
+    command::mqtt,
+    inbox::MqttInbox,
+};
+use crate::{
+    calendar::ClockTime,
     command::{
-        self, Urc, device, mobile_equipment, mqtt,
+        self, Urc, device, diagnostics, dns, manufacturing,
+        mobile_equipment::{self, types::PowerState},
         network::{self, types::NetworkRegistrationState},
-        nvm, pdp, ssl_tls,
-        system_features::{ConfigureCEREGReports, ConfigureCMEErrorReports},
+        nvm, pdp, ping, raw, sim, ssl_tls,
+        system_features::{
+            ConfigureCEREGReports, ConfigureCMEErrorReports, ConfigurePacketDomainEventReports,
+            ConfigureRingIndicator,
+        },
     },
-    error::Error,
+    config::{ClockSyncPolicy, ModemConfig, PingOptions, UrcPolicy},
+    delay::{self, DefaultDelay},
+    error::{CmeError, CommandName, Error, Operation},
+    event::{EVENT_CAPACITY, EVENT_SUBSCRIBERS, ModemEvent, ModemEvents},
+    info::ModemInfo,
+    listener::UrcListener,
+    observer::{AtObserver, Observed},
     types::Bool,
+    wake::ModemWaker,
 };
-use embassy_time::{Duration, Timer, with_timeout};
+#[cfg(feature = "nidd")]
+use crate::{command::nidd, types::hex_encode};
+
+/// Maximum number of automatic SIM PIN attempts.
+///
+/// The SIM is blocked after 3 wrong attempts, stopping after 2 leaves one attempt for manual
+/// recovery.
+const MAX_SIM_PIN_ATTEMPTS: u8 = 2;
+
+/// Number of CoAP message notifications buffered until they are read.
+#[cfg(feature = "coap")]
+const COAP_RING_CAPACITY: usize = 4;
+
+/// Number of NIDD payloads buffered until they are received.
+#[cfg(feature = "nidd")]
+const NIDD_INBOX_CAPACITY: usize = 2;
+
+/// Delay between the `AT` probes of a modem being woken, see [`ModemWaker`].
+const WAKE_PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Proof that the modem was attached to the LTE network with [`Modem::lte_connect`].
+///
+/// Required by the operations that need network registration, e.g. [`Modem::mqtt_connect`], and
+/// given back to [`Modem::lte_disconnect`].
+#[must_use]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Attached(());
+
+impl Attached {
+    /// Creates the token without attaching, for mock [`ModemInterface`](crate::ModemInterface)
+    /// implementations only.
+    ///
+    /// The token must only exist while the modem is attached: the operations taking it don't
+    /// check the network registration again.
+    #[doc(hidden)]
+    pub fn new_unchecked() -> Self {
+        Self(())
+    }
+}
+
+/// Proof that the modem was detached from the LTE network with [`Modem::lte_disconnect`] or
+/// [`Modem::lte_force_disconnect`].
+///
+/// Required by the operations that can only run while the radio is off, e.g. the GNSS fix or
+/// switching the operating mode, and given back to [`Modem::lte_connect`].
+#[must_use]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Detached(());
+
+impl Detached {
+    /// Creates the token without detaching, for mock [`ModemInterface`](crate::ModemInterface)
+    /// implementations only.
+    ///
+    /// The token must only exist while the radio is off: the operations taking it don't check the
+    /// functionality level again.
+    #[doc(hidden)]
+    pub fn new_unchecked() -> Self {
+        Self(())
+    }
+}
+
+/// Proof that the modem entered the manufacturing mode with
+/// [`Modem::enter_manufacturing_mode`].
+///
+/// Required by the RF test commands, given back to [`Modem::leave_manufacturing_mode`].
+#[must_use]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Manufacturing(());
+
+impl Manufacturing {
+    /// Creates the token without entering the manufacturing mode, for mock
+    /// [`ManufacturingInterface`](crate::ManufacturingInterface) implementations only.
+    ///
+    /// The token must only exist while the modem is in the manufacturing mode.
+    #[doc(hidden)]
+    pub fn new_unchecked() -> Self {
+        Self(())
+    }
+}
 
 /// Represents the state of the modem.
 ///
 /// The state is designed to be shared across multiple components of the modem stack,
 /// such as the URC (unsolicited result code) handler and any control interface.
-struct ModemState {
-    reg_state: Mutex<CriticalSectionRawMutex, RefCell<NetworkRegistrationState>>,
-    mqtt_connected: Signal<NoopRawMutex, mqtt::urc::Connected>,
+///
+/// The state is owned by the caller and borrowed by the [`Modem`], so it can live in a `static`
+/// for `'static` tasks or on the stack, e.g. in tests:
+///
+/// ```ignore
+/// static STATE: ModemState = ModemState::new();
+///
+/// let modem = Modem::new(client, &URC_CHANNEL, &STATE, ModemConfig::default());
+/// ```
+///
+/// The state is guarded by the `M` raw mutex, [`CriticalSectionRawMutex`] by default. Targets
+/// where the modem tasks all run in thread mode on a single core can use
+/// [`NoopRawMutex`](embassy_sync::blocking_mutex::raw::NoopRawMutex) to avoid the critical
+/// sections, the [`Modem`] and its [`UrcHandler`] follow the type of the state:
+///
+/// ```ignore
+/// static STATE: ModemState<NoopRawMutex> = ModemState::new();
+/// ```
+pub struct ModemState<M: RawMutex = CriticalSectionRawMutex> {
+    reg_state: Mutex<M, RefCell<NetworkRegistrationState>>,
+    /// Signaled when `reg_state` changed, wakes the [`Modem`] waiting for a registration state.
+    reg_changed: Signal<M, ()>,
+    #[cfg(feature = "mqtt")]
+    mqtt_inbox: MqttInbox<M>,
+    #[cfg(feature = "coap")]
+    coap_connected: Signal<M, coap::urc::Connected>,
+    #[cfg(feature = "coap")]
+    coap_closed: Signal<M, coap::urc::Closed>,
+    /// Received CoAP message notifications, waiting to be read.
+    #[cfg(feature = "coap")]
+    coap_rings: Channel<M, coap::urc::Ring, COAP_RING_CAPACITY>,
+    /// Received NIDD payloads, waiting for [`Modem::nidd_receive`].
+    #[cfg(feature = "nidd")]
+    nidd_inbox: Channel<M, nidd::urc::DataReceived, NIDD_INBOX_CAPACITY>,
+    events: PubSubChannel<M, ModemEvent, EVENT_CAPACITY, EVENT_SUBSCRIBERS, 0>,
+    /// Power state set by the driver, `None` until known.
+    power_state: Mutex<M, Cell<Option<PowerState>>>,
+    /// Set when an operation was cancelled while the modem waited for a prompt payload.
+    prompt_pending: Mutex<M, Cell<bool>>,
+    /// Held for every exchange with the modem, and from the command opening a prompt until its
+    /// payload is written, so that the modems sharing the state don't interleave their commands.
+    exchanges: AsyncMutex<M, ()>,
+    /// Set once the driver is initialized by [`Modem::begin`], until the modem restarts.
+    initialized: Mutex<M, Cell<bool>>,
+    /// Number of times the configured SIM PIN was entered, until it was accepted.
+    sim_pin_attempts: Mutex<M, Cell<u8>>,
+    /// Number of URCs lost because the [`UrcHandler`] lagged behind the URC channel.
+    dropped_urcs: Mutex<M, Cell<u64>>,
+    urc_handler_running: Mutex<M, Cell<bool>>,
+    /// Set when the modem (re)started, until the [`Modem`] takes notice.
+    rebooted: Mutex<M, Cell<bool>>,
+    /// Set while the modem sleeps and its UART is down, see [`ModemState::set_asleep`].
+    asleep: Mutex<M, Cell<bool>>,
+    /// Signaled when the modem woke up, wakes the [`Modem`] holding a command back.
+    woke: Signal<M, ()>,
+    urc_handler_stop: Signal<M, ()>,
+    /// Code of the last `+CME ERROR`, set by the [`CmeCodeDigester`](crate::CmeCodeDigester).
+    cme_code: Mutex<M, Cell<Option<u16>>>,
+    /// Set while the [`Modem`] waits for the response to a command, the
+    /// [`CmeCodeDigester`](crate::CmeCodeDigester) only takes unknown lines for URCs otherwise.
+    #[cfg(feature = "unknown-urc")]
+    command_pending: Mutex<M, Cell<bool>>,
+    /// Extended error of the last failed GNSS command, set by the
+    /// [`GnssErrorDigester`](crate::gnss::GnssErrorDigester).
+    #[cfg(feature = "gnss")]
+    gnss_error: Mutex<M, Cell<Option<GnssError>>>,
+}
 
-    #[cfg(feature = "gm02sp")]
-    fix_subscriber: Signal<NoopRawMutex, GnssFixReady>,
+impl<M: RawMutex> Default for ModemState<M> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl ModemState {
+impl<M: RawMutex> ModemState<M> {
     /// Creates a new `ModemState`.
-    const fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             reg_state: Mutex::new(RefCell::new(NetworkRegistrationState::NotSearching)),
-            mqtt_connected: Signal::new(),
-            #[cfg(feature = "gm02sp")]
-            fix_subscriber: Signal::new(),
+            reg_changed: Signal::new(),
+            #[cfg(feature = "mqtt")]
+            mqtt_inbox: MqttInbox::new(),
+            #[cfg(feature = "coap")]
+            coap_connected: Signal::new(),
+            #[cfg(feature = "coap")]
+            coap_closed: Signal::new(),
+            #[cfg(feature = "coap")]
+            coap_rings: Channel::new(),
+            #[cfg(feature = "nidd")]
+            nidd_inbox: Channel::new(),
+            events: PubSubChannel::new(),
+            power_state: Mutex::new(Cell::new(None)),
+            prompt_pending: Mutex::new(Cell::new(false)),
+            exchanges: AsyncMutex::new(()),
+            initialized: Mutex::new(Cell::new(false)),
+            sim_pin_attempts: Mutex::new(Cell::new(0)),
+            dropped_urcs: Mutex::new(Cell::new(0)),
+            urc_handler_running: Mutex::new(Cell::new(false)),
+            rebooted: Mutex::new(Cell::new(false)),
+            asleep: Mutex::new(Cell::new(false)),
+            woke: Signal::new(),
+            urc_handler_stop: Signal::new(),
+            cme_code: Mutex::new(Cell::new(None)),
+            #[cfg(feature = "unknown-urc")]
+            command_pending: Mutex::new(Cell::new(false)),
+            #[cfg(feature = "gnss")]
+            gnss_error: Mutex::new(Cell::new(None)),
+        }
+    }
+}
+
+impl<M: RawMutex> ModemState<M> {
+    /// Returns the number of URCs lost because the [`UrcHandler`] lagged behind the URC channel.
+    ///
+    /// Lost URCs leave the driver state stale (e.g. a missed +CEREG or MQTT message), a growing
+    /// count calls for a larger [`URC_CAPACITY`](crate::URC_CAPACITY) or a higher priority of the
+    /// URC handler task. Every loss is also reported as [`ModemEvent::UrcsDropped`].
+    pub fn dropped_urcs(&self) -> u64 {
+        self.dropped_urcs.lock(Cell::get)
+    }
+
+    /// Stops the [`UrcHandler`] started with [`UrcHandler::run_until_stopped`].
+    ///
+    /// A stop requested while no handler runs stops the next one right after it starts.
+    pub fn stop_urc_handler(&self) {
+        self.urc_handler_stop.signal(());
+    }
+
+    /// Whether a [`UrcHandler`] task is running.
+    pub fn is_urc_handler_running(&self) -> bool {
+        self.urc_handler_running.lock(Cell::get)
+    }
+
+    /// Reports that the modem entered a sleep state (e.g. PSM) and its UART is down.
+    ///
+    /// The modem doesn't announce its sleep over the UART, the application derives it from the
+    /// hardware, e.g. from the CTS line. Until the modem is awake again, the commands are held
+    /// back by [`Modem::send`], or the modem is woken first if a
+    /// [`ModemWaker`](crate::ModemWaker) is set.
+    pub fn set_asleep(&self) {
+        self.asleep.lock(|asleep| asleep.set(true));
+    }
+
+    /// Reports that the modem woke up, e.g. on a pulse of its ring indicator line, releasing the
+    /// commands held back.
+    ///
+    /// The [`UrcHandler`] calls it for every URC, the modem only reports them while awake.
+    pub fn set_awake(&self) {
+        if self.asleep.lock(|asleep| asleep.replace(false)) {
+            self.woke.signal(());
+        }
+    }
+
+    /// Whether the modem was reported asleep, see [`set_asleep`](Self::set_asleep).
+    pub fn is_asleep(&self) -> bool {
+        self.asleep.lock(Cell::get)
+    }
+
+    /// Returns the queue of the received MQTT message notifications.
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_inbox(&self) -> &MqttInbox<M> {
+        &self.mqtt_inbox
+    }
+
+    /// Returns the last network registration state reported by the modem.
+    pub(crate) fn registration_state(&self) -> NetworkRegistrationState {
+        self.reg_state.lock(|v| v.borrow().clone())
+    }
+
+    pub(crate) fn set_cme_code(&self, code: u16) {
+        self.cme_code.lock(|cme_code| cme_code.set(Some(code)));
+    }
+
+    pub(crate) fn take_cme_code(&self) -> Option<u16> {
+        self.cme_code.lock(Cell::take)
+    }
+
+    #[cfg(feature = "unknown-urc")]
+    pub(crate) fn set_command_pending(&self, pending: bool) {
+        self.command_pending.lock(|cell| cell.set(pending));
+    }
+
+    #[cfg(feature = "unknown-urc")]
+    pub(crate) fn is_command_pending(&self) -> bool {
+        self.command_pending.lock(Cell::get)
+    }
+
+    #[cfg(feature = "gnss")]
+    pub(crate) fn set_gnss_error(&self, err: GnssError) {
+        self.gnss_error.lock(|error| error.set(Some(err)));
+    }
+
+    #[cfg(feature = "gnss")]
+    fn take_gnss_error(&self) -> Option<GnssError> {
+        self.gnss_error.lock(Cell::take)
+    }
+
+    /// Resets the state cached from the modem after it (re)started.
+    fn invalidate(&self) {
+        self.power_state.lock(|s| s.set(None));
+        // The prompt of an interrupted operation is gone with the restart.
+        self.prompt_pending.lock(|pending| pending.set(false));
+        self.rebooted.lock(|rebooted| rebooted.set(true));
+        self.set_awake();
+
+        let previous = self
+            .reg_state
+            .lock(|v| v.replace(NetworkRegistrationState::NotSearching));
+        if previous != NetworkRegistrationState::NotSearching {
+            self.reg_changed.signal(());
+            self.publish(ModemEvent::RegistrationChanged(
+                NetworkRegistrationState::NotSearching,
+            ));
+        }
+
+        #[cfg(feature = "mqtt")]
+        self.mqtt_inbox.clear();
+        #[cfg(feature = "coap")]
+        {
+            self.coap_connected.reset();
+            self.coap_closed.reset();
+            self.coap_rings.clear();
+        }
+        #[cfg(feature = "nidd")]
+        self.nidd_inbox.clear();
+    }
+
+    fn publish(&self, event: ModemEvent) {
+        self.events.immediate_publisher().publish_immediate(event);
+    }
+}
+
+/// Guards an operation that leaves the modem waiting for a prompt payload, armed once the command
+/// opening the prompt succeeded. The exchanges with the modem stay locked until the guard is
+/// released, no other command may land in the prompt.
+///
+/// Futures can't run async code when dropped, so a guard dropped before being disarmed (e.g. the
+/// operation was cancelled or the payload failed) only marks the prompt as pending. The prompt is
+/// aborted with [`AbortPrompt`](command::AbortPrompt) before the next command is sent.
+#[must_use]
+struct PromptGuard<'a, M: RawMutex> {
+    state: &'a ModemState<M>,
+    armed: bool,
+    _exchanges: MutexGuard<'a, M, ()>,
+}
+
+impl<'a, M: RawMutex> PromptGuard<'a, M> {
+    fn arm(state: &'a ModemState<M>, exchanges: MutexGuard<'a, M, ()>) -> Self {
+        Self {
+            state,
+            armed: true,
+            _exchanges: exchanges,
+        }
+    }
+
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl<M: RawMutex> Drop for PromptGuard<'_, M> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.state.prompt_pending.lock(|pending| pending.set(true));
         }
     }
 }
 
 /// A handle to the modem, providing access to AT command operations and URC subscription handling.
-pub struct Modem<'a, AtCl, const N: usize, const L: usize> {
+///
+/// The retry delays and timeouts run on the `D` [`DelayNs`] provider, see
+/// [`Modem::new_with_delay`].
+pub struct Modem<
+    'a,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    M: RawMutex = CriticalSectionRawMutex,
+> {
     client: AtCl,
-    state: &'a ModemState,
+    delay: D,
+    state: &'a ModemState<M>,
     urc_chan: &'a UrcChannel<Urc, N, L>,
-    initialized: bool,
-    #[cfg(feature = "gm02sp")]
+    config: ModemConfig,
+    observer: Option<&'a dyn AtObserver>,
+    waker: Option<&'a dyn ModemWaker>,
+    #[cfg(feature = "gnss")]
     update_almanac: bool,
-    #[cfg(feature = "gm02sp")]
+    #[cfg(feature = "gnss")]
     update_ephemeris: bool,
 }
 
@@ -75,67 +453,331 @@ pub struct Modem<'a, AtCl, const N: usize, const L: usize> {
 /// This handler is intended to run as a long-lived task that continuously polls for URC messages
 /// and processes them. It is typically launched by calling [`Modem::urc_handler`] followed by
 /// `.run().await`.
-pub struct UrcHandler<'a, const N: usize, const L: usize> {
+pub struct UrcHandler<'a, const N: usize, const L: usize, M: RawMutex = CriticalSectionRawMutex> {
     urc_subscription: UrcSubscription<'a, Urc, N, L>,
-    state: &'a ModemState,
+    state: &'a ModemState<M>,
+    listener: &'a dyn UrcListener,
 }
 
-impl<'a, const N: usize, const L: usize> UrcHandler<'a, N, L> {
+impl<'a, const N: usize, const L: usize, M: RawMutex> UrcHandler<'a, N, L, M> {
     /// Runs the URC handler task indefinitely.
     ///
-    /// This method should be spawned as a background task alongside other modem activities.
+    /// This method should be spawned as a background task alongside other modem activities. Use
+    /// [`run_until_stopped`](Self::run_until_stopped) if the task must end, e.g. before deep sleep.
     pub async fn run(&mut self) -> ! {
+        self.state
+            .urc_handler_running
+            .lock(|running| running.set(true));
         loop {
-            let msg = self.urc_subscription.next_message_pure().await;
-            match msg {
-                #[cfg(feature = "gm02sp")]
-                command::Urc::GnssFixReady(fix_ready) => {
-                    debug!("GNSS fix ready: {:?}", fix_ready);
-                    self.state.fix_subscriber.signal(fix_ready);
-                }
-                command::Urc::MqttConnected(connected) => {
-                    debug!("MQTT connected: {:?}", connected);
-                    self.state.mqtt_connected.signal(connected);
-                }
-                command::Urc::MqttDisconnected(disconnected) => {
-                    debug!("MQTT disconnected: {:?}", disconnected);
-                    // self.state.mqtt_connected.signal(connected);
-                }
-                command::Urc::MqttMessagePublished(published) => {
-                    debug!("MQTT message published: {:?}", published);
-                }
-                command::Urc::MqttMessageReceived(received) => {
-                    debug!("MQTT message received: {:?}", received);
-                }
-                command::Urc::MqttSubscribed(subscribed) => {
-                    debug!("MQTT subscribed: {:?}", subscribed);
-                }
-                command::Urc::MqttPromptToPublish(prompt) => {
-                    debug!("MQTT prompt to publish: {:?}", prompt);
-                }
-                command::Urc::Shutdown => {
-                    debug!("Device shutdown");
-                }
-                command::Urc::Start => {
-                    debug!("Device started");
+            let msg = self.urc_subscription.next_message().await;
+            self.receive(msg);
+        }
+    }
+
+    /// Runs the URC handler task until [`ModemState::stop_urc_handler`] is called.
+    ///
+    /// The URCs already queued are processed before returning, so the [`ModemState`] reflects
+    /// everything the modem reported, e.g. before entering deep sleep or resetting the modem.
+    pub async fn run_until_stopped(&mut self) {
+        self.state
+            .urc_handler_running
+            .lock(|running| running.set(true));
+        loop {
+            let next = select(
+                self.urc_subscription.next_message(),
+                self.state.urc_handler_stop.wait(),
+            )
+            .await;
+            match next {
+                Either::First(msg) => self.receive(msg),
+                Either::Second(()) => break,
+            }
+        }
+
+        self.process_pending();
+        self.state
+            .urc_handler_running
+            .lock(|running| running.set(false));
+        debug!("URC handler stopped");
+    }
+
+    /// Sets the listener called for the URCs processed by this handler.
+    pub fn set_listener(&mut self, listener: &'a dyn UrcListener) {
+        self.listener = listener;
+    }
+
+    /// Processes all the URC messages currently queued without waiting for new ones.
+    ///
+    /// Intended for applications without an async executor that poll the handler periodically.
+    pub fn process_pending(&mut self) {
+        while let Some(msg) = self.urc_subscription.try_next_message() {
+            self.receive(msg);
+        }
+    }
+
+    fn receive(&self, msg: WaitResult<Urc>) {
+        match msg {
+            WaitResult::Message(urc) => self.handle(urc),
+            WaitResult::Lagged(missed) => {
+                // The URC channel overwrote the oldest URCs before the handler received them.
+                error!("URC handler lagged, {} URCs lost", missed);
+                self.state.dropped_urcs.lock(|dropped| {
+                    dropped.set(dropped.get().saturating_add(missed));
+                });
+                self.listener.on_urcs_dropped(missed);
+                self.state.publish(ModemEvent::UrcsDropped(missed));
+            }
+        }
+    }
+
+    fn handle(&self, msg: command::Urc) {
+        self.state.set_awake();
+        self.listener.on_urc(&msg);
+
+        match msg {
+            #[cfg(feature = "gnss")]
+            command::Urc::GnssFixReady(fix_ready) => {
+                debug!("GNSS fix ready: {:?}", fix_ready);
+                self.listener.on_gnss_fix(&fix_ready);
+                self.state.publish(ModemEvent::GnssFix(fix_ready));
+            }
+            #[cfg(feature = "gnss")]
+            command::Urc::GnssFixStopped => {
+                debug!("GNSS fix stopped");
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttConnected(connected) => {
+                debug!("MQTT connected: {:?}", connected);
+                self.state.publish(ModemEvent::MqttConnected(connected.rc));
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttDisconnected(disconnected) => {
+                debug!("MQTT disconnected: {:?}", disconnected);
+                self.state
+                    .publish(ModemEvent::MqttDisconnected(disconnected.rc));
+                self.listener.on_mqtt_disconnected(&disconnected.rc);
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttMessagePublished(published) => {
+                debug!("MQTT message published: {:?}", published);
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttMessageReceived(received) => {
+                debug!("MQTT message received: {:?}", received);
+                self.listener.on_mqtt_message(&received);
+                self.state.mqtt_inbox.push(received.clone());
+                self.state
+                    .publish(ModemEvent::MqttMessageReceived(received));
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttSubscribed(subscribed) => {
+                debug!("MQTT subscribed: {:?}", subscribed);
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttPromptToPublish(prompt) => {
+                debug!("MQTT prompt to publish: {:?}", prompt);
+            }
+            #[cfg(feature = "mqtt")]
+            command::Urc::MqttMemoryFull(full) => {
+                warn!("MQTT message queue full, messages lost: {:?}", full);
+            }
+            command::Urc::Shutdown => {
+                debug!("Device shutdown");
+                self.state.publish(ModemEvent::Shutdown);
+                self.state.power_state.lock(|s| s.set(None));
+                self.listener.on_shutdown();
+            }
+            command::Urc::Start => {
+                debug!("Device started");
+                self.state.invalidate();
+                self.state.publish(ModemEvent::Started);
+                self.listener.on_started();
+            }
+            #[cfg(feature = "coap")]
+            command::Urc::CoapConnected(conn) => {
+                debug!("COAP connected: {:?}", conn);
+                self.state.coap_connected.signal(conn);
+            }
+            #[cfg(feature = "coap")]
+            command::Urc::CoapClosed(closed) => {
+                debug!("COAP closed: {:?}", closed);
+                self.state.coap_closed.signal(closed);
+            }
+            #[cfg(feature = "coap")]
+            command::Urc::CoapRing(ring) => {
+                debug!("COAP ring: {:?}", ring);
+                if self.state.coap_rings.try_send(ring).is_err() {
+                    warn!("COAP ring queue full, notification dropped");
                 }
-                command::Urc::CoapConnected(conn) => {
-                    debug!("COAP connected: {:?}", conn);
+            }
+            #[cfg(feature = "lwm2m")]
+            command::Urc::Lwm2mEvent(event) => {
+                debug!("LwM2M event: {:?}", event);
+                self.state.publish(ModemEvent::Lwm2m(event.event));
+            }
+            #[cfg(feature = "lwm2m")]
+            command::Urc::Lwm2mResourceWritten(written) => {
+                debug!("LwM2M resource written: {:?}", written);
+                self.state
+                    .publish(ModemEvent::Lwm2mResourceWritten(written));
+            }
+            #[cfg(feature = "lwm2m")]
+            command::Urc::Lwm2mResourceExecuted(executed) => {
+                debug!("LwM2M resource executed: {:?}", executed);
+                self.state
+                    .publish(ModemEvent::Lwm2mResourceExecuted(executed));
+            }
+            #[cfg(feature = "lwm2m")]
+            command::Urc::Lwm2mObservation(observation) => {
+                debug!("LwM2M observation: {:?}", observation);
+            }
+            command::Urc::NetworkRegistrationStatus(status) => {
+                debug!("Network registration status: {:?}", status);
+                let previous = self
+                    .state
+                    .reg_state
+                    .lock(|v| v.replace(status.stat.clone()));
+                if previous != status.stat {
+                    self.state.reg_changed.signal(());
+                    self.listener.on_registration_change(&status.stat);
+                    self.state
+                        .publish(ModemEvent::RegistrationChanged(status.stat));
                 }
-                command::Urc::NetworkRegistrationStatus(status) => {
-                    debug!("Network registration status: {:?}", status);
-                    self.state.reg_state.lock(|v| {
-                        v.replace(status.stat);
-                    });
+            }
+            command::Urc::PacketDomainEvent(event) => {
+                debug!("Packet domain event: {:?}", event);
+            }
+            command::Urc::EdrxParameters(params) => {
+                debug!("eDRX parameters: {:?}", params);
+            }
+            command::Urc::SimStatus(status) => {
+                debug!("SIM status: {:?}", status);
+            }
+            #[cfg(feature = "socket")]
+            command::Urc::SocketRing(ring) => {
+                debug!("Socket ring: {:?}", ring);
+            }
+            #[cfg(feature = "http")]
+            command::Urc::HttpRing(ring) => {
+                debug!("HTTP ring: {:?}", ring);
+            }
+            #[cfg(feature = "sms")]
+            command::Urc::SmsReceived(message) => {
+                debug!("SMS received: {:?}", message);
+            }
+            #[cfg(feature = "nidd")]
+            command::Urc::NiddDataReceived(data) => {
+                debug!("NIDD data received: {:?}", data);
+                if self.state.nidd_inbox.try_send(data).is_err() {
+                    warn!("NIDD inbox full, payload dropped");
                 }
-            };
+            }
+            command::Urc::UpgradeStatus(status) => {
+                debug!("Upgrade status: {:?}", status);
+            }
+            command::Urc::NtpSynchronized(ntp) => {
+                debug!("NTP synchronization: {:?}", ntp);
+            }
+            #[cfg(feature = "unknown-urc")]
+            command::Urc::Unknown(line) => {
+                info!("Unknown URC: {}", line.as_str());
+            }
+        };
+    }
+}
+
+/// Lifts the timeout of the wrapped command, the caller enforces its own instead.
+struct Untimed<'c, Cmd>(&'c Cmd);
+
+impl<Cmd: AtatCmd> AtatCmd for Untimed<'_, Cmd> {
+    type Response = Cmd::Response;
+
+    const MAX_LEN: usize = Cmd::MAX_LEN;
+    const MAX_TIMEOUT_MS: u32 = u32::MAX;
+    const CAN_ABORT: bool = Cmd::CAN_ABORT;
+    const EXPECTS_RESPONSE_CODE: bool = Cmd::EXPECTS_RESPONSE_CODE;
+    const ATTEMPTS: u8 = Cmd::ATTEMPTS;
+    const REATTEMPT_ON_PARSE_ERR: bool = Cmd::REATTEMPT_ON_PARSE_ERR;
+
+    fn write(&self, buf: &mut [u8]) -> usize {
+        self.0.write(buf)
+    }
+
+    fn parse(
+        &self,
+        resp: Result<&[u8], atat::InternalError>,
+    ) -> Result<Self::Response, atat::Error> {
+        self.0.parse(resp)
+    }
+}
+
+impl<'a, AtCl, const N: usize, const L: usize, D, M> Modem<'a, AtCl, N, L, D, M>
+where
+    D: Clone,
+    M: RawMutex,
+{
+    /// Returns the state shared with the [`UrcHandler`].
+    pub(crate) fn state(&self) -> &'a ModemState<M> {
+        self.state
+    }
+
+    /// Returns a copy of the delay provider, to time the tasks driving the modem.
+    pub(crate) fn delay(&self) -> D {
+        self.delay.clone()
+    }
+
+    /// Splits the modem into its client and the modem left without one, see
+    /// [`with_client`](Self::with_client).
+    pub(crate) fn into_client(self) -> (AtCl, Modem<'a, (), N, L, D, M>) {
+        let Self {
+            client,
+            delay,
+            state,
+            urc_chan,
+            config,
+            observer,
+            waker,
+            ..
+        } = self;
+        let modem = Modem {
+            client: (),
+            delay,
+            state,
+            urc_chan,
+            config,
+            observer,
+            waker,
+            #[cfg(feature = "gnss")]
+            update_almanac: false,
+            #[cfg(feature = "gnss")]
+            update_ephemeris: false,
+        };
+        (client, modem)
+    }
+
+    /// Returns a modem sending through `client`, with the state, configuration, observer and
+    /// waker of this one.
+    pub(crate) fn with_client<C>(&self, client: C) -> Modem<'a, C, N, L, D, M> {
+        Modem {
+            client,
+            delay: self.delay.clone(),
+            state: self.state,
+            urc_chan: self.urc_chan,
+            config: self.config.clone(),
+            observer: self.observer,
+            waker: self.waker,
+            #[cfg(feature = "gnss")]
+            update_almanac: false,
+            #[cfg(feature = "gnss")]
+            update_ephemeris: false,
         }
     }
 }
 
-impl<'a, AtCl, const N: usize, const L: usize> Modem<'a, AtCl, N, L>
+impl<'a, AtCl, const N: usize, const L: usize, M> Modem<'a, AtCl, N, L, DefaultDelay, M>
 where
     AtCl: AtatClient,
+    M: RawMutex,
 {
     /// Constructs a new `Modem` instance with a client, URC channel, and shared state.
     ///
@@ -143,19 +785,61 @@ where
     ///
     /// - `client`: An AT command client for communicating with the modem.
     /// - `urc_chan`: A reference to the URC channel used to receive asynchronous modem messages.
+    /// - `state`: The state shared between the modem and its [`UrcHandler`].
+    /// - `config`: The driver configuration.
     ///
     /// This method does not initialize the modem; call [`begin`](Self::begin) to do so.
-    pub fn new(client: AtCl, urc_chan: &'a UrcChannel<Urc, N, L>) -> Self {
-        static MODEM_STATE_CELL: StaticCell<ModemState> = StaticCell::new();
-        let modem_state: &'static ModemState = MODEM_STATE_CELL.init(ModemState::new());
+    ///
+    /// The channel must buffer at least one URC and accept the [`UrcHandler`] subscription, see
+    /// [`DefaultUrcChannel`](crate::DefaultUrcChannel) for recommended sizes.
+    pub fn new(
+        client: AtCl,
+        urc_chan: &'a UrcChannel<Urc, N, L>,
+        state: &'a ModemState<M>,
+        config: ModemConfig,
+    ) -> Self {
+        Self::new_with_delay(client, urc_chan, state, config, DefaultDelay {})
+    }
+}
+
+impl<'a, AtCl, const N: usize, const L: usize, D, M> Modem<'a, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Constructs a new `Modem` instance like [`new`](Modem::new), with the `delay` provider used
+    /// for the retry delays and timeouts instead of the embassy-time driver.
+    ///
+    /// The provider is cloned for every timeout, so it should be a cheap handle, e.g. to a
+    /// monotonic timer.
+    pub fn new_with_delay(
+        client: AtCl,
+        urc_chan: &'a UrcChannel<Urc, N, L>,
+        state: &'a ModemState<M>,
+        config: ModemConfig,
+        delay: D,
+    ) -> Self {
+        const {
+            assert!(N >= 1, "the URC channel must buffer at least one URC");
+            // More for the handles of a SharedModem, see URC_SUBSCRIBERS.
+            assert!(
+                L >= 2,
+                "the URC channel must accept the UrcHandler and Modem subscriptions"
+            );
+        }
+
         Self {
             client,
+            delay,
             urc_chan,
-            state: modem_state,
-            initialized: false,
-            #[cfg(feature = "gm02sp")]
+            state,
+            config,
+            observer: None,
+            waker: None,
+            #[cfg(feature = "gnss")]
             update_almanac: false,
-            #[cfg(feature = "gm02sp")]
+            #[cfg(feature = "gnss")]
             update_ephemeris: false,
         }
     }
@@ -168,107 +852,831 @@ where
     /// # Panics
     ///
     /// Panics if the subscription to the URC channel fails (e.g., buffer full or uninitialized).
-    pub fn urc_handler(&self) -> UrcHandler<'a, N, L> {
+    pub fn urc_handler(&self) -> UrcHandler<'a, N, L, M> {
         UrcHandler {
             urc_subscription: self.urc_chan.subscribe().unwrap(),
             state: self.state,
+            listener: &(),
         }
     }
 
-    pub async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, Error> {
-        self.client.send(cmd).await.map_err(|e| e.into())
-    }
-
-    /// Initializes the modem by sending basic configuration commands.
-    ///
-    /// This method must be called once before other modem operations are invoked.
-    /// It is safe to call multiple times; subsequent calls will be no-ops.
+    /// Takes the restart reported by the [`UrcHandler`], uninitializing the driver.
     ///
-    /// - Enables numeric CME error reporting.
-    /// - Enables network registration URC reporting.
-    pub async fn begin(&mut self) -> Result<(), Error> {
-        if self.initialized {
-            return Ok(());
+    /// Returns whether the driver was initialized when the modem restarted.
+    fn take_reboot(&mut self) -> bool {
+        if !self.state.rebooted.lock(|rebooted| rebooted.replace(false)) {
+            return false;
         }
-
-        self.send(&ConfigureCMEErrorReports {
-            typ: crate::command::system_features::types::CMEErrorReports::Numeric,
-        })
-        .await?;
-
-        self.send(&ConfigureCEREGReports {
-            typ: crate::command::system_features::types::CEREGReports::Enabled,
-        })
-        .await?;
-
-        self.initialized = true;
-
-        Ok(())
+        self.state
+            .initialized
+            .lock(|initialized| initialized.replace(false))
     }
 
-    pub async fn get_operation_mode(&mut self) -> Result<device::types::RAT, Error> {
-        let res = self.send(&device::GetOperatingMode).await?;
-        Ok(res.rat)
+    /// Sets the observer reporting the commands sent and the responses received.
+    ///
+    /// Use an [`ObservedDigester`](crate::ObservedDigester) on the ingress to report URCs too.
+    pub fn set_observer(&mut self, observer: &'a dyn AtObserver) {
+        self.observer = Some(observer);
     }
 
-    pub async fn set_opeartion_mode(&mut self, mode: device::types::RAT) -> Result<(), Error> {
-        self.send(&device::SetOperatingMode { mode }).await?;
-        Ok(())
+    /// Sets the waker used to wake the modem when a command is sent while it sleeps.
+    ///
+    /// Without a waker, the commands wait for the modem to wake up on its own, e.g. for the next
+    /// paging occasion or TAU, reported with [`ModemState::set_awake`].
+    pub fn set_waker(&mut self, waker: &'a dyn ModemWaker) {
+        self.waker = Some(waker);
     }
 
-    pub async fn ping(&mut self) -> Result<(), Error> {
-        self.send(&command::AT).await?;
-        Ok(())
+    /// Returns the stream of [`ModemEvent`]s, published while the [`UrcHandler`] runs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`EVENT_SUBSCRIBERS`] streams are subscribed at the same time.
+    pub fn events(&self) -> ModemEvents<'a, M> {
+        self.state.events.subscriber().unwrap()
     }
 
-    pub async fn define_pdp_context(&mut self) -> Result<(), Error> {
-        self.send(&pdp::DefinePDPContext {
-            cid: 1,
-            pdp_type: command::pdp::types::PDPType::IP,
-            apn: String::try_from("").unwrap(),
-            pdp_addr: String::try_from("").unwrap(),
-            d_comp: command::pdp::types::PDPDComp::default(),
-            h_comp: command::pdp::types::PDPHComp::default(),
-            ipv4_alloc: command::pdp::types::PDPIPv4Alloc::NAS,
-            request_type: command::pdp::types::PDPRequestType::NewOrHandover,
-            pdp_pcscf_discovery_method: command::pdp::types::PDPPCSCF::Auto,
-            for_imcn: Bool::False,
-            nslpi: Bool::False,
-            secure_pco: Bool::False,
-            ipv4_mtu_discovery: Bool::False,
-            local_addr_ind: Bool::False,
-            non_ip_mtu_discovery: Bool::False,
-        })
-        .await?;
-        Ok(())
+    /// Waits up to `timeout` for the first URC mapped to `Some` by `f`.
+    ///
+    /// Only the URCs received during the wait are considered, use
+    /// [`send_and_wait_for`](Self::send_and_wait_for) to wait for the URC completing a command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URC channel has no subscriber left, see
+    /// [`URC_SUBSCRIBERS`](crate::channel::URC_SUBSCRIBERS).
+    pub async fn wait_for<T>(
+        &mut self,
+        timeout: Duration,
+        f: impl FnMut(&Urc) -> Option<T>,
+    ) -> Result<T, Error> {
+        let subscription = self.urc_chan.subscribe().unwrap();
+        self.wait_on(subscription, timeout, Operation::Urc, f).await
     }
 
-    pub async fn set_op_state(
+    /// Sends a command, then waits up to `timeout` for the first URC mapped to `Some` by `f`,
+    /// e.g. the completion of an asynchronous command:
+    ///
+    /// ```ignore
+    /// let rc = modem
+    ///     .send_and_wait_for(&cmd, timeout, |urc| match urc {
+    ///         Urc::MqttConnected(connected) => Some(connected.rc),
+    ///         _ => None,
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// Fails with [`Error::Timeout`] if no such URC was received in time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URC channel has no subscriber left, see
+    /// [`URC_SUBSCRIBERS`](crate::channel::URC_SUBSCRIBERS).
+    pub async fn send_and_wait_for<Cmd: AtatCmd, T>(
         &mut self,
-        mode: mobile_equipment::types::FunctionalMode,
-    ) -> Result<(), Error> {
-        self.send(&mobile_equipment::SetFunctionality {
-            fun: mode,
-            rst: None,
-        })
-        .await?;
-        Ok(())
+        cmd: &Cmd,
+        timeout: Duration,
+        f: impl FnMut(&Urc) -> Option<T>,
+    ) -> Result<T, Error> {
+        self.send_and_wait_on(cmd, timeout, Operation::Urc, f).await
     }
 
-    pub fn get_network_registration_state(&self) -> NetworkRegistrationState {
-        self.state.reg_state.lock(|v| v.borrow().clone())
+    /// [`send_and_wait_for`](Self::send_and_wait_for) reporting a timeout of `operation`.
+    async fn send_and_wait_on<Cmd: AtatCmd, T>(
+        &mut self,
+        cmd: &Cmd,
+        timeout: Duration,
+        operation: Operation,
+        f: impl FnMut(&Urc) -> Option<T>,
+    ) -> Result<T, Error> {
+        // Subscribe first, the URC may be received before the command response.
+        let subscription = self.urc_chan.subscribe().unwrap();
+        self.send(cmd).await?;
+        self.wait_on(subscription, timeout, operation, f).await
     }
-}
 
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+    async fn wait_on<T>(
+        &mut self,
+        mut subscription: UrcSubscription<'_, Urc, N, L>,
+        timeout: Duration,
+        operation: Operation,
+        mut f: impl FnMut(&Urc) -> Option<T>,
+    ) -> Result<T, Error> {
+        let wait = async {
+            loop {
+                match subscription.next_message().await {
+                    WaitResult::Message(urc) => {
+                        if let Some(value) = f(&urc) {
+                            return value;
+                        }
+                    }
+                    WaitResult::Lagged(missed) => {
+                        warn!("Lagged behind the URC channel, {} URCs missed", missed);
+                    }
+                }
+            }
+        };
+
+        delay::with_timeout(&mut self.delay, timeout, wait)
+            .await
+            .map_err(|_| Error::Timeout(operation))
+    }
+
+    /// Sends a command to the modem.
+    ///
+    /// Commands rejected with a transient `+CME ERROR` (e.g. SIM busy, network timeout) are
+    /// repeated according to the [configured](ModemConfig::command_retry) backoff policy.
+    ///
+    /// Fails with [`Error::ModemRebooted`] without sending the command if the modem restarted
+    /// since it was [initialized](Self::begin), the modem configuration is lost and `begin` must
+    /// be called again.
+    ///
+    /// While the modem is [asleep](ModemState::set_asleep), the command is held back until it
+    /// wakes up, or fails with [`Error::Timeout`] after the
+    /// [configured](ModemConfig::wake_timeout) time.
+    pub async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, Error> {
+        let (response, _exchanges) = self.send_holding(cmd).await?;
+        Ok(response)
+    }
+
+    /// Sends a command like [`send`](Self::send), returning the lock of the exchanges with the
+    /// modem still held, e.g. to write the payload of the prompt opened by the command.
+    async fn send_holding<Cmd: AtatCmd>(
+        &mut self,
+        cmd: &Cmd,
+    ) -> Result<(Cmd::Response, MutexGuard<'a, M, ()>), Error> {
+        let retry = self.config.command_retry.clone();
+        let mut attempt = 0;
+        loop {
+            // Released between the attempts, the other modems sharing the state go on meanwhile.
+            let exchanges = self.lock_exchanges().await?;
+            match self.exchange(cmd).await {
+                Err(err) if err.is_transient_cme() && attempt + 1 < retry.attempts => {
+                    drop(exchanges);
+                    let delay = retry.delay(attempt);
+                    warn!(
+                        "Transient error {:?}, retrying in {} ms",
+                        err,
+                        delay.as_millis()
+                    );
+                    delay::sleep(&mut self.delay, delay).await;
+                    attempt += 1;
+                }
+                res => return res.map(|response| (response, exchanges)),
+            }
+        }
+    }
+
+    /// Sends the command opening a prompt, the returned guard holds the exchanges with the modem
+    /// until the payload is written with [`send_once`](Self::send_once).
+    async fn open_prompt<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<PromptGuard<'a, M>, Error> {
+        let (_, exchanges) = self.send_holding(cmd).await?;
+        // A rejected command opens no prompt, there is nothing to abort.
+        Ok(PromptGuard::arm(self.state, exchanges))
+    }
+
+    /// Sends a command to the modem once, even if rejected with a transient error.
+    ///
+    /// For the payloads of a prompt: the modem leaves the prompt once it answered, a repeated
+    /// payload would be taken for a command. The exchanges are already locked by the
+    /// [`PromptGuard`] of the prompt.
+    async fn send_once<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, Error> {
+        self.prepare_send().await?;
+        self.exchange(cmd).await
+    }
+
+    /// Locks the exchanges with the modem once it can take a command, see
+    /// [`SharedModem`](crate::SharedModem).
+    ///
+    /// The prompt left by a cancelled operation is aborted first, its guard marked it pending
+    /// before releasing the lock.
+    async fn lock_exchanges(&mut self) -> Result<MutexGuard<'a, M, ()>, Error> {
+        let state = self.state;
+        let exchanges = state.exchanges.lock().await;
+        self.prepare_send().await?;
+
+        if self
+            .state
+            .prompt_pending
+            .lock(|pending| pending.replace(false))
+        {
+            warn!("Aborting the prompt left by a cancelled operation");
+            // The modem doesn't confirm the abort, nothing to handle.
+            let _ = self.client.send(&command::AbortPrompt).await;
+        }
+
+        Ok(exchanges)
+    }
+
+    /// Checks the modem can take a command, see [`Modem::send`].
+    async fn prepare_send(&mut self) -> Result<(), Error> {
+        if self.take_reboot() {
+            warn!("The modem rebooted, the driver must be initialized again");
+            return Err(Error::ModemRebooted);
+        }
+
+        self.wait_awake().await
+    }
+
+    /// Sends a command to the client, reporting it to the observer if one is set.
+    async fn exchange<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, Error> {
+        // Clear the code left by an earlier response, e.g. to a wake probe.
+        self.state.take_cme_code();
+        #[cfg(feature = "unknown-urc")]
+        self.state.set_command_pending(true);
+        let res = match self.observer {
+            Some(observer) => self.client.send(&Observed { cmd, observer }).await,
+            None => self.client.send(cmd).await,
+        };
+        #[cfg(feature = "unknown-urc")]
+        self.state.set_command_pending(false);
+        res.map_err(|err| Error::command::<Cmd>(err).with_cme_code(self.state.take_cme_code()))
+    }
+
+    /// Waits until the modem reported asleep is awake, waking it with the waker if one is set.
+    ///
+    /// Runs with the exchanges locked, the probes don't interleave with other commands.
+    async fn wait_awake(&mut self) -> Result<(), Error> {
+        let state = self.state;
+        if !state.is_asleep() {
+            return Ok(());
+        }
+
+        let waker = self.waker;
+        let timeout = self.config.wake_timeout;
+        let client = &mut self.client;
+        let mut probe_delay = self.delay.clone();
+        let wake = async {
+            match waker {
+                Some(waker) => {
+                    debug!("Waking the modem");
+                    waker.wake();
+                    // The modem doesn't tell when its UART is up, probe it until it answers.
+                    while state.is_asleep() {
+                        if client.send(&command::AT).await.is_ok() {
+                            state.set_awake();
+                        } else {
+                            delay::sleep(&mut probe_delay, WAKE_PROBE_INTERVAL).await;
+                        }
+                    }
+                }
+                None => {
+                    debug!("The modem is asleep, waiting for it to wake up");
+                    while state.is_asleep() {
+                        state.woke.wait().await;
+                    }
+                }
+            }
+        };
+
+        delay::with_timeout(&mut self.delay, timeout, wake)
+            .await
+            .map_err(|_| Error::Timeout(Operation::Wake))
+    }
+
+    /// Sends a command, overriding its default timeout.
+    ///
+    /// Useful for commands whose duration depends on the network, e.g. an operator scan or a TLS
+    /// handshake. The timeout covers the [retries](ModemConfig::command_retry) as well.
+    pub async fn send_with_timeout<Cmd: AtatCmd>(
+        &mut self,
+        cmd: &Cmd,
+        timeout: Duration,
+    ) -> Result<Cmd::Response, Error> {
+        let mut timer = self.delay.clone();
+        delay::with_timeout(&mut timer, timeout, self.send(&Untimed(cmd)))
+            .await
+            .map_err(|_| Error::Timeout(Operation::Command(CommandName::of::<Cmd>())))?
+    }
+
+    /// Sends an AT command not modelled by this crate, e.g. `AT+CGSN=1`.
+    ///
+    /// Returns the information text of the response, up to `R` bytes. The command fails with
+    /// [`Error::Timeout`] if the modem doesn't respond within `timeout`.
+    pub async fn send_raw<const R: usize>(
+        &mut self,
+        cmd: &str,
+        timeout: Duration,
+    ) -> Result<String<R>, Error> {
+        if cmd.len() + 2 > raw::MAX_RAW_CMD_LEN {
+            return Err(Error::CommandTooLong);
+        }
+
+        let mut timer = self.delay.clone();
+        let res = delay::with_timeout(
+            &mut timer,
+            timeout,
+            self.send(&raw::RawCommand::<R> { cmd }),
+        )
+        .await
+        .map_err(|_| {
+            Error::Timeout(Operation::Command(CommandName::of::<raw::RawCommand<R>>()))
+        })??;
+        Ok(res.0)
+    }
+
+    /// Initializes the modem by sending basic configuration commands.
+    ///
+    /// This method must be called once before other modem operations are invoked.
+    /// It is safe to call multiple times; subsequent calls will be no-ops.
+    ///
+    /// - Configures CME error reporting (numeric by default).
+    /// - Configures the [reported URCs](ModemConfig::urc), network registration reporting is
+    ///   enabled by default.
+    /// - Enables the [automatic update](ModemConfig::automatic_time_zone_update) of the clock
+    ///   and time zone from the network, so [`get_time`](Self::get_time) rarely has to wait.
+    /// - Applies the [autoconnect](ModemConfig::autoconnect) setting, if any.
+    /// - Checks that the SIM card is not waiting for a PIN/PUK, entering the
+    ///   [configured](ModemConfig::sim_pin) SIM PIN if needed.
+    ///
+    /// Returns [`Error::SimPinRequired`] if the SIM card is locked. If the SIM state cannot be
+    /// read (e.g. the SIM is not powered in `CFUN=0`) the check is skipped.
+    ///
+    /// The configured PIN is tried at most twice over the lifetime of the driver so a wrong PIN
+    /// can't block the SIM.
+    pub async fn begin(&mut self) -> Result<(), Error> {
+        self.take_reboot();
+        if self.state.initialized.lock(Cell::get) {
+            return Ok(());
+        }
+
+        self.send(&ConfigureCMEErrorReports {
+            typ: self.config.cme_error_reports.clone(),
+        })
+        .await?;
+
+        self.apply_urc_policy().await?;
+
+        self.send(&device::ConfigureAutomaticTimeZoneUpdate {
+            enabled: self.config.automatic_time_zone_update.into(),
+        })
+        .await?;
+
+        if let Some(enabled) = self.config.autoconnect {
+            self.set_autoconnect(enabled).await?;
+        }
+
+        match self.get_pin_status().await {
+            Ok(sim::types::SIMState::Ready) | Err(Error::AT(..) | Error::Cme(..)) => {}
+            Ok(sim::types::SIMState::PinRequired) => self.enter_configured_pin().await?,
+            Ok(state) => return Err(Error::SimPinRequired(state)),
+            Err(err) => return Err(err),
+        }
+
+        self.state
+            .initialized
+            .lock(|initialized| initialized.set(true));
+
+        Ok(())
+    }
+
+    /// Changes the URCs reported by the modem, see [`UrcPolicy`].
+    ///
+    /// The GNSS notifications are applied by the next [`set_gnss_config`](Self::set_gnss_config).
+    pub async fn set_urc_policy(&mut self, policy: UrcPolicy) -> Result<(), Error> {
+        self.config.urc = policy;
+        self.apply_urc_policy().await
+    }
+
+    async fn apply_urc_policy(&mut self) -> Result<(), Error> {
+        self.send(&ConfigureCEREGReports {
+            typ: self.config.urc.cereg.clone(),
+        })
+        .await?;
+
+        self.send(&ConfigurePacketDomainEventReports {
+            mode: self.config.urc.packet_domain_events.clone(),
+        })
+        .await?;
+
+        if let Some(mode) = self.config.urc.ring_indicator {
+            self.send(&ConfigureRingIndicator {
+                mode,
+                duration: None,
+            })
+            .await?;
+        }
+
+        #[cfg(feature = "sms")]
+        self.send(&command::sms::ConfigureNewMessageIndications {
+            mode: 2,
+            mt: self.config.urc.sms_indications.into(),
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_operation_mode(&mut self) -> Result<device::types::RAT, Error> {
+        let res = self.send(&device::GetOperatingMode).await?;
+        Ok(res.rat)
+    }
+
+    /// Switches the operating mode, which is only possible while detached.
+    pub async fn set_opeartion_mode(
+        &mut self,
+        _detached: &Detached,
+        mode: device::types::RAT,
+    ) -> Result<(), Error> {
+        // Switching the mode in CFUN=1 fails with +CME ERROR 591 (Device is in active state).
+        self.require_state(PowerState::Minimum).await?;
+        self.send(&device::SetOperatingMode { mode }).await?;
+        Ok(())
+    }
+
+    /// Returns whether the modem attaches to the network on its own after it starts.
+    pub async fn get_autoconnect(&mut self) -> Result<bool, Error> {
+        let res = self.send(&device::GetAutoconnect).await?;
+        Ok(res.enabled.into())
+    }
+
+    /// Enables or disables the autonomous attach of the modem after it starts, see
+    /// [`ModemConfig::autoconnect`].
+    ///
+    /// The setting persists and takes effect at the next start of the modem, it is only written
+    /// when it changes.
+    pub async fn set_autoconnect(&mut self, enabled: bool) -> Result<(), Error> {
+        if self.get_autoconnect().await? != enabled {
+            self.send(&device::SetAutoconnect {
+                enabled: enabled.into(),
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Restarts the modem and waits until it started again.
+    ///
+    /// The modem configuration is lost, every command fails with [`Error::ModemRebooted`] until
+    /// the driver is initialized again with [`Modem::begin`].
+    pub async fn reboot(&mut self) -> Result<(), Error> {
+        // Subscribe first, the modem may start before the response is processed.
+        let mut events = self.events();
+        self.send(&device::Reboot).await?;
+
+        let started =
+            async { while !matches!(events.next_message_pure().await, ModemEvent::Started) {} };
+        delay::with_timeout(&mut self.delay, self.config.reboot_timeout, started)
+            .await
+            .map_err(|_| Error::Timeout(Operation::Startup))
+    }
+
+    /// Returns the name of the carrier configuration profile, e.g. `"standard"`.
+    pub async fn get_carrier_profile(
+        &mut self,
+    ) -> Result<String<{ device::responses::CARRIER_PROFILE_LEN }>, Error> {
+        let res = self.send(&device::GetCarrierProfile).await?;
+        Ok(res.profile)
+    }
+
+    /// Selects the carrier configuration `profile` and reboots the modem to apply it, see
+    /// [`Modem::reboot`].
+    ///
+    /// Returns whether the profile changed, nothing is done if it was already selected.
+    pub async fn set_carrier_profile(
+        &mut self,
+        _detached: &Detached,
+        profile: &str,
+    ) -> Result<bool, Error> {
+        if profile.len() > device::responses::CARRIER_PROFILE_LEN {
+            return Err(Error::CommandTooLong);
+        }
+        if self.get_carrier_profile().await? == profile {
+            return Ok(false);
+        }
+
+        self.send(&device::SetCarrierProfile { profile }).await?;
+        self.reboot().await?;
+        Ok(true)
+    }
+
+    pub async fn ping(&mut self) -> Result<(), Error> {
+        self.send(&command::AT).await?;
+        Ok(())
+    }
+
+    /// Defines the PDP context [configured](ModemConfig::pdp) for the modem, with its
+    /// [credentials](crate::PdpConfig::auth) if any.
+    pub async fn define_pdp_context(&mut self) -> Result<(), Error> {
+        self.send(&pdp::DefinePDPContext {
+            cid: self.config.pdp.cid,
+            pdp_type: self.config.pdp.pdp_type.clone(),
+            apn: self.config.pdp.apn.clone(),
+            pdp_addr: String::try_from("").unwrap(),
+            d_comp: command::pdp::types::PDPDComp::default(),
+            h_comp: command::pdp::types::PDPHComp::default(),
+            ipv4_alloc: command::pdp::types::PDPIPv4Alloc::NAS,
+            request_type: command::pdp::types::PDPRequestType::NewOrHandover,
+            pdp_pcscf_discovery_method: command::pdp::types::PDPPCSCF::Auto,
+            for_imcn: Bool::False,
+            nslpi: Bool::False,
+            secure_pco: Bool::False,
+            ipv4_mtu_discovery: Bool::False,
+            local_addr_ind: Bool::False,
+            // The MTU of a Non-IP context is only known through NAS signalling.
+            non_ip_mtu_discovery: Bool::from(
+                self.config.pdp.pdp_type == command::pdp::types::PDPType::NonIP,
+            ),
+        })
+        .await?;
+
+        if let Some(auth) = self.config.pdp.auth.clone() {
+            self.send(&pdp::SetPDPAuthentication {
+                cid: self.config.pdp.cid,
+                auth_prot: auth.protocol,
+                username: Some(auth.username),
+                password: Some(auth.password),
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_op_state(
+        &mut self,
+        mode: mobile_equipment::types::FunctionalMode,
+    ) -> Result<(), Error> {
+        self.send(&mobile_equipment::SetFunctionality {
+            fun: mode.clone(),
+            rst: None,
+        })
+        .await?;
+        self.state.power_state.lock(|s| s.set(Some(mode.into())));
+        Ok(())
+    }
+
+    /// Returns the power state of the modem, reading it from the modem if not known yet.
+    pub async fn power_state(&mut self) -> Result<PowerState, Error> {
+        if let Some(state) = self.state.power_state.lock(|s| s.get()) {
+            return Ok(state);
+        }
+
+        let res = self.send(&mobile_equipment::GetFunctionality).await?;
+        let state = res.fun.into();
+        self.state.power_state.lock(|s| s.set(Some(state)));
+        Ok(state)
+    }
+
+    /// Checks the modem is in the `expected` power state, failing with
+    /// [`Error::UnexpectedPowerState`] otherwise.
+    pub async fn require_state(&mut self, expected: PowerState) -> Result<(), Error> {
+        let actual = self.power_state().await?;
+        if actual != expected {
+            return Err(Error::UnexpectedPowerState { expected, actual });
+        }
+        Ok(())
+    }
+
+    // Enters the SIM PIN from the configuration, refusing to do so more than
+    // `MAX_SIM_PIN_ATTEMPTS` times.
+    async fn enter_configured_pin(&mut self) -> Result<(), Error> {
+        let attempts = &self.state.sim_pin_attempts;
+        let pin = match &self.config.sim_pin {
+            Some(pin) if attempts.lock(Cell::get) < MAX_SIM_PIN_ATTEMPTS => pin.clone(),
+            _ => return Err(Error::SimPinRequired(sim::types::SIMState::PinRequired)),
+        };
+
+        attempts.lock(|attempts| attempts.set(attempts.get() + 1));
+        let res = self.send(&sim::EnterPin { pin, new_pin: None }).await;
+
+        match res {
+            Ok(_) => {
+                attempts.lock(|attempts| attempts.set(0));
+                Ok(())
+            }
+            Err(Error::Cme(CmeError::IncorrectPassword, _)) => Err(Error::SimIncorrectPassword),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns whether the SIM card is ready or waits for some password to be entered.
+    pub async fn get_pin_status(&mut self) -> Result<sim::types::SIMState, Error> {
+        let res = self.send(&sim::GetPinStatus).await?;
+        self.state.publish(ModemEvent::SimState(res.code.clone()));
+        Ok(res.code)
+    }
+
+    /// Unblocks the SIM with the PUK code and sets a new PIN.
+    ///
+    /// The SIM must be waiting for the PUK, otherwise [`Error::UnexpectedSimState`] is returned
+    /// and no attempt is consumed. A wrong PUK results in [`Error::SimIncorrectPassword`], once
+    /// all the attempts are used up the SIM reports a failure and [`Error::SimBlocked`] is
+    /// returned.
+    pub async fn unblock_sim(&mut self, puk: String<8>, new_pin: String<8>) -> Result<(), Error> {
+        match self.get_pin_status().await? {
+            sim::types::SIMState::PukRequired => {}
+            state => return Err(Error::UnexpectedSimState(state)),
+        }
+
+        let res = self
+            .send(&sim::EnterPin {
+                pin: puk,
+                new_pin: Some(new_pin),
+            })
+            .await;
+
+        match res {
+            Ok(_) => Ok(()),
+            Err(Error::Cme(CmeError::IncorrectPassword, _)) => Err(Error::SimIncorrectPassword),
+            // Reported once the PUK attempts are exhausted.
+            Err(Error::Cme(CmeError::SimFailure, _)) => Err(Error::SimBlocked),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reads `length` bytes from the transparent elementary file `file_id` on the SIM.
+    ///
+    /// E.g. the forbidden PLMN list is stored in the file `0x6F7B`. The returned data is
+    /// hexadecimal encoded, `sw1` and `sw2` report the result of the operation on the SIM.
+    pub async fn read_sim_binary(
+        &mut self,
+        file_id: u16,
+        length: u8,
+    ) -> Result<sim::responses::RestrictedSimAccessResponse, Error> {
+        self.send(&sim::RestrictedSimAccess {
+            command: sim::types::RestrictedSimCommand::ReadBinary,
+            file_id: Some(file_id),
+            p1: Some(0),
+            p2: Some(0),
+            p3: Some(length),
+            data: None,
+            path_id: None,
+        })
+        .await
+    }
+
+    /// Lists the profiles installed on the eUICC (eSIM).
+    pub async fn esim_list_profiles(
+        &mut self,
+    ) -> Result<heapless::Vec<sim::esim::ProfileInfo, { sim::esim::MAX_PROFILES }>, Error> {
+        let res = self.esim_transmit(sim::esim::get_profiles_info).await?;
+        sim::esim::parse_profiles(&res).ok_or(Self::esim_parse_error())
+    }
+
+    /// Enables the eSIM profile identified by `iccid`, disabling the currently enabled one.
+    ///
+    /// The eUICC refreshes the SIM session afterwards, the modem has to register to the network
+    /// again using the new profile.
+    pub async fn esim_enable_profile(&mut self, iccid: &str) -> Result<(), Error> {
+        let res = self
+            .esim_transmit(|session_id| sim::esim::enable_profile(session_id, iccid))
+            .await?;
+        Self::esim_check_result(&res)
+    }
+
+    /// Disables the eSIM profile identified by `iccid`.
+    pub async fn esim_disable_profile(&mut self, iccid: &str) -> Result<(), Error> {
+        let res = self
+            .esim_transmit(|session_id| sim::esim::disable_profile(session_id, iccid))
+            .await?;
+        Self::esim_check_result(&res)
+    }
+
+    // Opens a logical channel to the ISD-R, transmits the APDU built by `apdu` and closes the
+    // channel again.
+    async fn esim_transmit(
+        &mut self,
+        apdu: impl FnOnce(u32) -> Option<sim::esim::Apdu>,
+    ) -> Result<String<{ sim::responses::SIM_RESPONSE_LEN }>, Error> {
+        let channel = self
+            .send(&sim::OpenLogicalChannel {
+                dfname: sim::esim::ISD_R_AID,
+            })
+            .await?;
+
+        let res = match apdu(channel.session_id) {
+            Some(command) => self
+                .send(&sim::LogicalChannelAccess {
+                    session_id: channel.session_id,
+                    length: command.len(),
+                    command: &command,
+                })
+                .await
+                .map(|res| res.response),
+            None => Err(Self::esim_parse_error()),
+        };
+
+        self.send(&sim::CloseLogicalChannel {
+            session_id: channel.session_id,
+        })
+        .await?;
+
+        res
+    }
+
+    fn esim_check_result(res: &str) -> Result<(), Error> {
+        match sim::esim::parse_operation_result(res) {
+            Some(sim::esim::ProfileOperationResult::Ok) => Ok(()),
+            Some(result) => Err(Error::Esim(result)),
+            None => Err(Self::esim_parse_error()),
+        }
+    }
+
+    fn esim_parse_error() -> Error {
+        Error::AT(
+            atat::Error::Parse,
+            CommandName::of::<sim::LogicalChannelAccess>(),
+        )
+    }
+
+    /// Enables or disables the SIM PIN lock.
+    ///
+    /// With the lock disabled the device can boot unattended without entering the SIM PIN.
+    pub async fn set_sim_lock(&mut self, enabled: bool, pin: String<8>) -> Result<(), Error> {
+        let mode = if enabled {
+            sim::types::FacilityLockMode::Lock
+        } else {
+            sim::types::FacilityLockMode::Unlock
+        };
+
+        self.send(&sim::SetFacilityLock {
+            fac: sim::types::Facility::Sim,
+            mode,
+            passwd: Some(pin),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Returns whether the SIM PIN lock is enabled.
+    pub async fn is_sim_locked(&mut self) -> Result<bool, Error> {
+        let res = self
+            .send(&sim::GetFacilityLock {
+                fac: sim::types::Facility::Sim,
+                mode: sim::types::FacilityLockMode::QueryStatus,
+            })
+            .await?;
+        Ok(res.status == sim::types::FacilityLockStatus::Active)
+    }
+
+    pub fn get_network_registration_state(&self) -> NetworkRegistrationState {
+        self.state.registration_state()
+    }
+
+    /// Gathers the identity and the SIM, network and connection details into a [`ModemInfo`].
+    ///
+    /// Only the identity queries are required to succeed, the other details are left empty when
+    /// the modem can't provide them.
+    pub async fn info(&mut self) -> Result<ModemInfo, Error> {
+        let manufacturer = self.send(&device::GetManufacturerId).await?.id;
+        let model = self.send(&device::GetModelId).await?.id;
+        let imei = self.send(&device::GetImei).await?.imei;
+        let firmware_version = self.send(&device::GetFirmwareVersion).await?.version;
+
+        let sim_state = self.get_pin_status().await.ok();
+        let iccid = self.send(&sim::GetIccid).await.ok().map(|res| res.iccid);
+        let operator = self
+            .send(&network::GetPLMNSelection)
+            .await
+            .ok()
+            .and_then(|res| res.oper);
+        let signal_quality = self.send(&mobile_equipment::GetSignalQuality).await.ok();
+        let cid = self.config.pdp.cid;
+        let ip_address = self
+            .send(&pdp::GetPDPAddress { cid })
+            .await
+            .ok()
+            .and_then(|res| res.pdp_addr_1);
+
+        Ok(ModemInfo {
+            manufacturer,
+            model,
+            imei,
+            firmware_version,
+            sim_state,
+            iccid,
+            registration_state: self.get_network_registration_state(),
+            operator,
+            signal_quality,
+            ip_address,
+        })
+    }
+}
+
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
     /// Connect to the LTE network.
     ///
     /// This function will connect the modem to the LTE network. This function will
     /// block until the modem is attached.
-    pub async fn lte_connect(&mut self) -> Result<(), Error> {
+    ///
+    /// Fails with [`Error::Timeout`] if the modem isn't registered after the
+    /// [configured](ModemConfig::registration_timeout) time.
+    ///
+    /// The [`Detached`] token is consumed: once attached, the operations requiring the radio off
+    /// can't be called anymore. A failed attach leaves the radio in an unknown state, use
+    /// [`Modem::lte_force_disconnect`] to get a new token.
+    pub async fn lte_connect(&mut self, _detached: Detached) -> Result<Attached, Error> {
+        self.attach().await
+    }
+
+    /// Attaches to the LTE network from any state, for the connection loops of the driver that
+    /// track the registration themselves.
+    pub(crate) async fn attach(&mut self) -> Result<Attached, Error> {
         self.set_op_state(mobile_equipment::types::FunctionalMode::Full)
             .await?;
 
@@ -279,19 +1687,16 @@ where
         })
         .await?;
 
-        loop {
-            match self.get_network_registration_state() {
-                NetworkRegistrationState::RegisteredHome => break,
-                NetworkRegistrationState::RegisteredRoaming => break,
-                _ => {
-                    Timer::after(Duration::from_millis(1000)).await;
-                    // let signal = self.send(&GetSignalQuality).await?;
-                    // debug!("rssi: {:?}", signal);
-                }
-            }
-        }
+        self.wait_for_registration(|state| {
+            matches!(
+                state,
+                NetworkRegistrationState::RegisteredHome
+                    | NetworkRegistrationState::RegisteredRoaming
+            )
+        })
+        .await?;
 
-        Ok(())
+        Ok(Attached(()))
     }
 
     /// Disconnect from the LTE network.
@@ -299,63 +1704,453 @@ where
     /// This function will disconnect the modem from the LTE network and block until
     /// the network is actually disconnected. After the network is disconnected the
     /// GNSS subsystem can be used.
-    pub async fn lte_disconnect(&mut self) -> Result<(), Error> {
+    pub async fn lte_disconnect(&mut self, attached: Attached) -> Result<Detached, Error> {
+        self.lte_force_disconnect(Some(attached)).await
+    }
+
+    /// Disconnects from the LTE network whatever the current state, e.g. after [`Modem::begin`]
+    /// to get the first [`Detached`] token, or after a failed [`Modem::lte_connect`].
+    ///
+    /// The [`Attached`] token must be given back if the application holds one: once detached, the
+    /// operations requiring the network registration can't be called anymore.
+    pub async fn lte_force_disconnect(
+        &mut self,
+        _attached: Option<Attached>,
+    ) -> Result<Detached, Error> {
+        self.set_op_state(command::mobile_equipment::types::FunctionalMode::Minimum)
+            .await?;
+
+        self.wait_for_registration(|state| *state == NetworkRegistrationState::NotSearching)
+            .await?;
+
+        Ok(Detached(()))
+    }
+
+    /// Enters the manufacturing mode (CFUN=5) for the RF tests, e.g. on the production line.
+    pub async fn enter_manufacturing_mode(
+        &mut self,
+        _detached: Detached,
+    ) -> Result<Manufacturing, Error> {
+        self.set_op_state(command::mobile_equipment::types::FunctionalMode::Manufacturing)
+            .await?;
+        Ok(Manufacturing(()))
+    }
+
+    /// Stops any RF test and leaves the manufacturing mode for the minimum functionality.
+    pub async fn leave_manufacturing_mode(
+        &mut self,
+        manufacturing: Manufacturing,
+    ) -> Result<Detached, Error> {
+        self.rf_test_stop_transmit(&manufacturing).await?;
         self.set_op_state(command::mobile_equipment::types::FunctionalMode::Minimum)
             .await?;
+        Ok(Detached(()))
+    }
+
+    /// Transmits an unmodulated carrier on the uplink `earfcn` at `power` dBm until
+    /// [`Modem::rf_test_stop_transmit`].
+    pub async fn rf_test_transmit(
+        &mut self,
+        _manufacturing: &Manufacturing,
+        earfcn: u32,
+        power: i8,
+    ) -> Result<(), Error> {
+        self.send(&manufacturing::ContinuousWaveTransmit {
+            enabled: Bool::True,
+            earfcn: Some(earfcn),
+            power: Some(power),
+        })
+        .await?;
+        Ok(())
+    }
 
-        while self.get_network_registration_state() != NetworkRegistrationState::NotSearching {
-            Timer::after(Duration::from_millis(100)).await;
+    /// Stops the carrier started by [`Modem::rf_test_transmit`], if any.
+    pub async fn rf_test_stop_transmit(
+        &mut self,
+        _manufacturing: &Manufacturing,
+    ) -> Result<(), Error> {
+        self.send(&manufacturing::ContinuousWaveTransmit {
+            enabled: Bool::False,
+            earfcn: None,
+            power: None,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the power received on the downlink `earfcn`, in dBm.
+    pub async fn rf_test_measure(
+        &mut self,
+        _manufacturing: &Manufacturing,
+        earfcn: u32,
+    ) -> Result<i16, Error> {
+        let res = self
+            .send(&manufacturing::MeasureReceivedPower { earfcn })
+            .await?;
+        Ok(res.rssi)
+    }
+
+    /// Waits until the registration state satisfies `f`, woken by the [`UrcHandler`] on every
+    /// change of the state rather than polling it.
+    ///
+    /// Fails with [`Error::Timeout`] after the [configured](ModemConfig::registration_timeout)
+    /// time.
+    async fn wait_for_registration(
+        &mut self,
+        f: impl Fn(&NetworkRegistrationState) -> bool,
+    ) -> Result<(), Error> {
+        let state = self.state;
+        let wait = async {
+            loop {
+                // Reset before reading the state, a change in between wakes the wait right away.
+                state.reg_changed.reset();
+                if f(&state.registration_state()) {
+                    return;
+                }
+                state.reg_changed.wait().await;
+            }
+        };
+
+        delay::with_timeout(&mut self.delay, self.config.registration_timeout, wait)
+            .await
+            .map_err(|_| Error::Timeout(Operation::NetworkRegistration))
+    }
+}
+
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Reads the modem clock, the returned clock is always
+    /// [valid](device::responses::Clock::is_valid).
+    ///
+    /// If the clock is not synchronized yet, `policy` decides whether to wait for the network to
+    /// synchronize it. The modem is only attached (and detached afterwards) with
+    /// [`ClockSyncPolicy::ConnectIfNeeded`] when it is not registered already, so an active
+    /// session is never disrupted.
+    pub async fn get_time(
+        &mut self,
+        policy: ClockSyncPolicy,
+    ) -> Result<device::responses::Clock, Error> {
+        // Even with valid assistance data the system clock could be invalid
+        let clock = self.send(&GetClock).await?;
+        if clock.is_valid() {
+            return Ok(clock);
+        }
+
+        let registered = matches!(
+            self.get_network_registration_state(),
+            NetworkRegistrationState::RegisteredHome | NetworkRegistrationState::RegisteredRoaming
+        );
+
+        if policy == ClockSyncPolicy::NeverConnect {
+            return Err(Error::ClockInvalid);
+        }
+        if registered {
+            return self.wait_for_clock_sync().await;
+        }
+        if policy == ClockSyncPolicy::UseExisting {
+            return Err(Error::ClockInvalid);
+        }
+
+        debug!("Clock time out of sync, synchronizing");
+
+        // The system clock is invalid, connect to LTE network to sync time
+        let attached = self.attach().await?;
+        let clock = self.wait_for_clock_sync().await;
+        let _detached = self.lte_disconnect(attached).await?;
+
+        clock
+    }
+
+    /// Polls the modem clock until the network synchronized it.
+    async fn wait_for_clock_sync(&mut self) -> Result<device::responses::Clock, Error> {
+        let retry = self.config.clock_sync_retry.clone();
+        for _ in 0..retry.attempts {
+            delay::sleep(&mut self.delay, retry.delay).await;
+            let clock = self.send(&GetClock).await?;
+            if clock.is_valid() {
+                return Ok(clock);
+            }
+        }
+
+        Err(Error::ClockSynchronization)
+    }
+
+    /// Sets the modem clock, e.g. from the RTC of the host or an NTP client.
+    ///
+    /// A clock set this way allows GNSS assisted fixes without attaching to the LTE network, the
+    /// network time (if any) overrides it once attached.
+    pub async fn set_time(&mut self, time: &ClockTime) -> Result<(), Error> {
+        self.send(&device::SetClock::new(time)).await?;
+        Ok(())
+    }
+
+    /// Synchronizes the modem clock with the NTP `server` and returns the synchronized clock.
+    ///
+    /// An alternative to the network time when the carrier doesn't provide it (NITZ), the PDN
+    /// must be active. Fails with [`Error::ClockSynchronization`] if the server couldn't be
+    /// reached, or [`Error::Timeout`] after [`ModemConfig::ntp_timeout`].
+    pub async fn sync_time_ntp(
+        &mut self,
+        _attached: &Attached,
+        server: &str,
+    ) -> Result<device::responses::Clock, Error> {
+        let ntp = self
+            .send_and_wait_on(
+                &device::SynchronizeTimeNtp { server, port: None },
+                self.config.ntp_timeout,
+                Operation::NtpSync,
+                |urc| match urc {
+                    Urc::NtpSynchronized(ntp) => Some(ntp.clone()),
+                    _ => None,
+                },
+            )
+            .await?;
+
+        if !ntp.is_success() {
+            error!("NTP synchronization error: {}", ntp.result);
+            return Err(Error::ClockSynchronization);
+        }
+
+        let clock = self.send(&GetClock).await?;
+        if !clock.is_valid() {
+            return Err(Error::ClockSynchronization);
+        }
+
+        Ok(clock)
+    }
+}
+
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Resolves `hostname` with the DNS servers of the network, e.g. to check a host before
+    /// opening a connection to it.
+    ///
+    /// Fails with [`Error::CommandTooLong`] if `hostname` exceeds [`dns::HOSTNAME_LEN`].
+    pub async fn resolve(
+        &mut self,
+        _attached: &Attached,
+        hostname: &str,
+    ) -> Result<dns::responses::HostAddresses, Error> {
+        if hostname.len() > dns::HOSTNAME_LEN {
+            return Err(Error::CommandTooLong);
+        }
+
+        self.send(&dns::ResolveHost {
+            hostname,
+            ip_version: None,
+        })
+        .await
+    }
+
+    /// Pings `host`, an IP address or a host name, and returns the reply of every echo request,
+    /// e.g. to check the connectivity and latency from the field.
+    ///
+    /// Fails with [`Error::CommandTooLong`] if `host` exceeds [`dns::HOSTNAME_LEN`].
+    pub async fn icmp_ping(
+        &mut self,
+        _attached: &Attached,
+        host: &str,
+        options: &PingOptions,
+    ) -> Result<ping::responses::PingReplies, Error> {
+        if host.len() > dns::HOSTNAME_LEN {
+            return Err(Error::CommandTooLong);
+        }
+
+        let count = options
+            .count
+            .clamp(1, ping::responses::MAX_PING_COUNT as u8);
+        let timeout = options.timeout.as_secs().clamp(1, u8::MAX as u64) as u8;
+        let cmd = ping::Ping {
+            host,
+            count: Some(count),
+            size: Some(options.size),
+            interval: Some(1),
+            timeout: Some(timeout),
+        };
+
+        // Every request waits for its reply, then for the interval before the next one.
+        let duration = Duration::from_secs(count as u64 * (timeout as u64 + 1));
+        self.send_with_timeout(&cmd, duration + Duration::from_secs(5))
+            .await
+    }
+}
+
+#[cfg(feature = "socket")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Switches the socket `conn_id` to the hexadecimal data mode, to read its data with
+    /// [`Modem::socket_receive`].
+    ///
+    /// The data written to the socket must then be hexadecimal encoded too. Must be called while
+    /// the socket is closed.
+    pub async fn socket_set_hex_mode(&mut self, conn_id: u8) -> Result<(), Error> {
+        self.send(&socket::ConfigureExtended {
+            conn_id,
+            ring_mode: socket::types::RingMode::DataAmount,
+            recv_data_mode: socket::types::DataMode::Hex,
+            keepalive: 0,
+            listen_auto_rsp: Some(Bool::False),
+            send_data_mode: Some(socket::types::DataMode::Hex),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Reads the data received on the socket `conn_id` into `buf`, returning the number of bytes
+    /// read, 0 if none was available.
+    ///
+    /// Reads up to [`socket::SOCKET_RECV_LEN`] bytes at once, the socket must be in the
+    /// hexadecimal data mode, see [`Modem::socket_set_hex_mode`].
+    pub async fn socket_receive(
+        &mut self,
+        _attached: &Attached,
+        conn_id: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let max_bytes = buf.len().min(socket::SOCKET_RECV_LEN) as u16;
+        let received = self.send(&socket::Receive { conn_id, max_bytes }).await?;
+        // The modem never returns more than requested.
+        let len = received.data.len().min(buf.len());
+        buf[..len].copy_from_slice(&received.data[..len]);
+        Ok(len)
+    }
+
+    /// Returns the bytes sent and received on the socket `conn_id` since it was opened.
+    pub async fn socket_data_usage(
+        &mut self,
+        conn_id: u8,
+    ) -> Result<socket::types::DataUsage, Error> {
+        let info = self.send(&socket::GetSocketInfo { conn_id }).await?;
+        Ok(socket::types::DataUsage {
+            sent: info.sent.into(),
+            received: info.received.into(),
+        })
+    }
+
+    /// Returns the bytes sent and received on all the sockets, each since it was opened.
+    ///
+    /// The modem doesn't count the traffic of a PDP context as a whole, nor the traffic of the
+    /// sockets before they were reopened. To enforce a budget, e.g. of a metered SIM, read the
+    /// usage of a socket with [`Modem::socket_data_usage`] before closing it and add it up.
+    pub async fn data_usage(&mut self) -> Result<socket::types::DataUsage, Error> {
+        let mut usage = socket::types::DataUsage::default();
+        for conn_id in 1..=socket::SOCKET_COUNT {
+            usage = usage.saturating_add(self.socket_data_usage(conn_id).await?);
         }
-
-        Ok(())
+        Ok(usage)
     }
 }
 
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+#[cfg(feature = "http")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
-    pub async fn get_time(&mut self) -> Result<device::responses::Clock, Error> {
-        // Even with valid assistance data the system clock could be invalid
-        let mut clock = self.send(&GetClock).await?;
-
-        if clock.time.0.timestamp().is_zero() {
-            debug!("Clock time out of sync, synchronizing");
-
-            // The system clock is invalid, connect to LTE network to sync time
-            self.lte_connect().await?;
+    /// Configures the HTTP profile `prof_id` to send its requests to `host`, over TLS with the
+    /// security profile `sp_id` if set, see [`Modem::configure_tls_profile`].
+    ///
+    /// Fails with [`Error::CommandTooLong`] if `host` exceeds [`dns::HOSTNAME_LEN`].
+    pub async fn http_configure(
+        &mut self,
+        prof_id: u8,
+        host: &str,
+        port: u16,
+        sp_id: Option<u8>,
+    ) -> Result<(), Error> {
+        if host.len() > dns::HOSTNAME_LEN {
+            return Err(Error::CommandTooLong);
+        }
 
-            // Wait for the modem to synchronize time with the LTE network, try 5 times
-            // with a delay of 500ms.
-            for _ in 0..5 {
-                Timer::after(Duration::from_millis(500)).await;
-                clock = self.send(&GetClock).await?;
-                if !clock.time.0.timestamp().is_zero() {
-                    break;
-                }
-            }
+        self.send(&http::Configure {
+            prof_id,
+            host,
+            port,
+            auth_type: http::types::AuthType::None,
+            username: "",
+            password: "",
+            ssl_enabled: Bool::from(sp_id.is_some()),
+            timeout: self.config.http_timeout.as_secs().min(u16::MAX.into()) as u16,
+            cid: self.config.pdp.cid,
+            sp_id,
+        })
+        .await?;
+        Ok(())
+    }
 
-            self.lte_disconnect().await?;
+    /// Sends a request without body, e.g. a GET of `resource`, on the HTTP profile `prof_id`,
+    /// then waits for the response.
+    ///
+    /// Returns the status code and the length of the body, read with [`Modem::http_receive`].
+    /// Fails with [`Error::CommandTooLong`] if `resource` exceeds [`http::HTTP_RESOURCE_LEN`],
+    /// and with [`Error::Timeout`] if no response came within [`ModemConfig::http_timeout`].
+    pub async fn http_query(
+        &mut self,
+        _attached: &Attached,
+        prof_id: u8,
+        command: http::types::QueryCommand,
+        resource: &str,
+    ) -> Result<http::urc::Ring, Error> {
+        if resource.len() > http::HTTP_RESOURCE_LEN {
+            return Err(Error::CommandTooLong);
+        }
 
-            if clock.time.0.timestamp().is_zero() {
-                return Err(Error::ClockSynchronization);
-            }
-        };
+        self.send_and_wait_on(
+            &http::Query {
+                prof_id,
+                command,
+                resource,
+            },
+            // The modem gives up on the server after the same timeout.
+            self.config.http_timeout + Duration::from_secs(5),
+            Operation::HttpResponse,
+            |urc| match urc {
+                Urc::HttpRing(ring) if ring.prof_id == prof_id => Some(ring.clone()),
+                _ => None,
+            },
+        )
+        .await
+    }
 
-        Ok(clock)
+    /// Reads the body of the last response received on the HTTP profile `prof_id` into `buf`,
+    /// returning the number of bytes read, 0 once the whole body was read.
+    ///
+    /// Reads up to [`http::HTTP_RECV_LEN`] bytes at once.
+    pub async fn http_receive(&mut self, prof_id: u8, buf: &mut [u8]) -> Result<usize, Error> {
+        let max_bytes = buf.len().min(http::HTTP_RECV_LEN) as u16;
+        let received = self.send(&http::Receive { prof_id, max_bytes }).await?;
+        // The modem never returns more than requested.
+        let len = received.data.len().min(buf.len());
+        buf[..len].copy_from_slice(&received.data[..len]);
+        Ok(len)
     }
 }
 
-#[cfg(feature = "gm02sp")]
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+#[cfg(feature = "gnss")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
     pub async fn set_gnss_config(&mut self, sensitivity: FixSensitivity) -> Result<(), Error> {
         self.send(&SetGnssConfig {
             location_mode: command::gnss::types::LocationMode::OnDeviceLocation,
             fix_sensitivity: sensitivity,
-            urc_settings: command::gnss::types::UrcNotificationSetting::Full,
+            urc_settings: self.config.urc.gnss.clone(),
             reserved: Reserved,
             metrics: false.into(),
             acquisition_mode: command::gnss::types::AcquisitionMode::ColdWarmStart,
@@ -372,48 +2167,41 @@ where
     // response. This function also sets a flag if any of the assistance databases
     // should be updated.
     async fn check_assistance_data(&mut self) -> Result<(), Error> {
-        use crate::gnss::responses::GnssAsssitance;
-
-        let data = self.send(&GetGnssAssitance).await?;
+        let status = self.send(&GetGnssAssistance).await?;
 
+        // The assistance types missing from the response are not downloaded.
         self.update_almanac = false;
         self.update_ephemeris = false;
 
-        for GnssAsssitance {
-            typ,
-            available,
-            time_to_update,
-            ..
-        } in data
-        {
-            match typ {
-                crate::gnss::types::GnssAssitanceType::Almanac => match available {
-                    Bool::True => {
-                        debug!(
-                            "almanace data is available and should be updated within {}",
-                            time_to_update
-                        );
-                        self.update_almanac = time_to_update <= 0;
-                    }
-                    Bool::False => {
-                        debug!("almanace data is not available",);
-                        self.update_almanac = true;
-                    }
-                },
-                crate::gnss::types::GnssAssitanceType::RealTimeEphemeris => match available {
-                    Bool::True => {
-                        debug!(
-                            "real-time ephemeris data is available and should be updated within {}",
-                            time_to_update
-                        );
-                        self.update_ephemeris = time_to_update <= 0;
-                    }
-                    Bool::False => {
-                        debug!("real-time ephemerise data is not available",);
-                        self.update_ephemeris = true;
-                    }
-                },
-                crate::gnss::types::GnssAssitanceType::PredictedEphemeris => {}
+        if let Some(almanac) = status.almanac {
+            match almanac.available {
+                Bool::True => {
+                    debug!(
+                        "almanace data is available and should be updated within {}",
+                        almanac.time_to_update
+                    );
+                    self.update_almanac = almanac.time_to_update <= 0;
+                }
+                Bool::False => {
+                    debug!("almanace data is not available",);
+                    self.update_almanac = true;
+                }
+            }
+        }
+
+        if let Some(ephemeris) = status.real_time_ephemeris {
+            match ephemeris.available {
+                Bool::True => {
+                    debug!(
+                        "real-time ephemeris data is available and should be updated within {}",
+                        ephemeris.time_to_update
+                    );
+                    self.update_ephemeris = ephemeris.time_to_update <= 0;
+                }
+                Bool::False => {
+                    debug!("real-time ephemerise data is not available",);
+                    self.update_ephemeris = true;
+                }
             }
         }
 
@@ -425,65 +2213,110 @@ where
     /// This funtion will check if the current real-time ephemeris data is good
     /// enough to get a fast GNSS fix. If not the function will attach to the LTE
     /// network to download newer assistance data.
-    pub async fn update_gnss_asistance(&mut self) -> Result<(), Error> {
-        self.lte_disconnect().await?;
-
+    ///
+    /// The [`Detached`] token is given back once the modem is detached again, ready for
+    /// [`Modem::get_gnss_fix`].
+    pub async fn update_gnss_asistance(&mut self, detached: Detached) -> Result<Detached, Error> {
         // Even with valid assistance data the system clock could be invalid,
         // get_time ensures the device synchronizes the clock first.
-        self.get_time().await?;
+        self.get_time(ClockSyncPolicy::ConnectIfNeeded).await?;
 
         // Check the availability of assistance data
         self.check_assistance_data().await?;
 
         if !self.update_almanac && !self.update_ephemeris {
-            return Ok(());
+            return Ok(detached);
         }
 
-        self.lte_connect().await?;
+        let attached = self.lte_connect(detached).await?;
 
         if self.update_almanac {
-            self.send(&UpdateGnssAssitance {
-                typ: command::gnss::types::GnssAssitanceType::Almanac,
+            self.send(&UpdateGnssAssistance {
+                typ: command::gnss::types::GnssAssistanceType::Almanac,
             })
             .await?;
         }
 
         if self.update_ephemeris {
-            self.send(&UpdateGnssAssitance {
-                typ: command::gnss::types::GnssAssitanceType::RealTimeEphemeris,
+            self.send(&UpdateGnssAssistance {
+                typ: command::gnss::types::GnssAssistanceType::RealTimeEphemeris,
             })
             .await?;
         }
 
-        for _ in 0..10 {
-            Timer::after(Duration::from_secs(10)).await;
+        let retry = self.config.gnss_assistance_retry.clone();
+        for _ in 0..retry.attempts {
+            delay::sleep(&mut self.delay, retry.delay).await;
             self.check_assistance_data().await?;
             if !self.update_almanac && !self.update_ephemeris {
                 break;
             }
         }
 
-        self.lte_disconnect().await?;
+        self.lte_disconnect(attached).await
+    }
 
-        Ok(())
+    /// Uploads GNSS assistance data obtained by the application, e.g. over its own backhaul, for
+    /// the devices whose LTE connectivity is too constrained for [`Modem::update_gnss_asistance`].
+    ///
+    /// See [`Modem::gnss_assistance_writer`] to stream the data in slices.
+    pub async fn upload_gnss_assistance(
+        &mut self,
+        typ: GnssAssistanceType,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let mut writer = self.gnss_assistance_writer(typ, data.len()).await?;
+        writer.write(data).await
     }
 
-    pub async fn get_gnss_fix(&mut self) -> Result<GnssFixReady, Error> {
-        use embassy_time::TimeoutError;
+    /// Starts uploading `size` bytes of GNSS assistance data, the data is then streamed in slices
+    /// with [`PromptWriter::write`] like an NVM entry, see [`Modem::nvm_writer`].
+    pub async fn gnss_assistance_writer(
+        &mut self,
+        typ: GnssAssistanceType,
+        size: usize,
+    ) -> Result<PromptWriter<'_, 'sub, AtCl, N, L, D, M>, Error> {
+        debug!("Uploading GNSS assistance data {:?}", typ);
 
-        self.state.fix_subscriber.reset();
+        let guard = self
+            .open_prompt(&PrepareUploadGnssAssistance { typ, size })
+            .await?;
 
-        self.send(&ProgramGnss {
-            action: command::gnss::types::ProgramGnssAction::Single,
+        Ok(PromptWriter {
+            modem: self,
+            remaining: size,
+            guard: Some(guard),
         })
-        .await?;
+    }
+
+    /// Gets a single GNSS fix, the radio is shared with LTE so the modem must be detached.
+    ///
+    /// Fails with [`Error::Gnss`] if the modem rejected the fix with an extended error, which
+    /// requires a [`GnssErrorDigester`](crate::gnss::GnssErrorDigester) on the ingress.
+    pub async fn get_gnss_fix(&mut self, _detached: &Detached) -> Result<GnssFixReady, Error> {
+        // Drop the error left by a previous command.
+        self.state.take_gnss_error();
+
+        let fix = self
+            .send_and_wait_on(
+                &ProgramGnss {
+                    action: command::gnss::types::ProgramGnssAction::Single,
+                },
+                self.config.gnss_fix_timeout,
+                Operation::GnssFix,
+                |urc| match urc {
+                    Urc::GnssFixReady(fix) => Some(fix.clone()),
+                    _ => None,
+                },
+            )
+            .await;
 
-        match with_timeout(Duration::from_secs(180), self.state.fix_subscriber.wait()).await {
+        match fix {
             Ok(fix) => {
                 debug!("GNSS fix received: {:?}", fix);
                 Ok(fix)
             }
-            Err(TimeoutError) => {
+            Err(Error::Timeout(timeout)) => {
                 debug!("GNSS fix timed out");
 
                 self.send(&ProgramGnss {
@@ -491,23 +2324,50 @@ where
                 })
                 .await?;
 
-                Err(TimeoutError.into())
+                Err(Error::Timeout(timeout))
             }
+            Err(e) => Err(self.state.take_gnss_error().map_or(e, Error::Gnss)),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[cfg(feature = "mqtt")]
+#[derive(Clone, PartialEq)]
 pub struct UsernamePassword {
     /// Username for broker authentication.
-    pub username: String<256>,
+    pub username: MqttCredential,
 
     /// Password for broker authentication.
-    pub password: String<256>,
+    pub password: MqttCredential,
+}
+
+#[cfg(feature = "mqtt")]
+impl core::fmt::Debug for UsernamePassword {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never log the password.
+        f.debug_struct("UsernamePassword")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+#[cfg(all(feature = "mqtt", feature = "defmt"))]
+impl defmt::Format for UsernamePassword {
+    fn format(&self, f: defmt::Formatter) {
+        // Never log the password.
+        defmt::write!(
+            f,
+            "UsernamePassword {{ username: {}, password: <redacted> }}",
+            self.username
+        );
+    }
 }
 
 // TODO: replace enum with dedicated methods.
+#[cfg(feature = "mqtt")]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(clippy::large_enum_variant)]
 pub enum MqttAuth {
     UsernamePassword(UsernamePassword),
@@ -515,16 +2375,168 @@ pub enum MqttAuth {
     SecurityProfile(u8),
 }
 
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+#[cfg(feature = "lwm2m")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Enables the LwM2M client and waits until it registered to its server.
+    ///
+    /// The server (or bootstrap server) and its credentials are provisioned in the modem. Fails
+    /// with [`Error::Lwm2mRegistration`] if the bootstrap or the registration failed, or
+    /// [`Error::Timeout`] after [`ModemConfig::lwm2m_registration_timeout`].
+    pub async fn lwm2m_register(&mut self, _attached: &Attached) -> Result<(), Error> {
+        let event = self
+            .send_and_wait_on(
+                &lwm2m::Enable {
+                    enabled: Bool::True,
+                },
+                self.config.lwm2m_registration_timeout,
+                Operation::Lwm2mRegistration,
+                |urc| match urc {
+                    Urc::Lwm2mEvent(event) if event.event.ends_registration() => Some(event.event),
+                    _ => None,
+                },
+            )
+            .await?;
+
+        match event {
+            lwm2m::types::ClientEvent::Registered => Ok(()),
+            event => {
+                error!("LwM2M registration error: {:?}", event);
+                Err(Error::Lwm2mRegistration(event))
+            }
+        }
+    }
+
+    /// Sets the value of a resource, notified to the server if it observes it.
+    ///
+    /// Fails with [`Error::PayloadTooLong`] if `value` exceeds [`lwm2m::LWM2M_VALUE_LEN`].
+    pub async fn lwm2m_set_resource(
+        &mut self,
+        object: u16,
+        instance: u16,
+        resource: u16,
+        value: &str,
+    ) -> Result<(), Error> {
+        if value.len() > lwm2m::LWM2M_VALUE_LEN {
+            return Err(Error::PayloadTooLong);
+        }
+
+        self.send(&lwm2m::SetResource {
+            object,
+            instance,
+            resource,
+            value,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Deregisters from the server and disables the LwM2M client.
+    pub async fn lwm2m_disable(&mut self) -> Result<(), Error> {
+        self.send(&lwm2m::Enable {
+            enabled: Bool::False,
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nidd")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Prepares the modem for Non-IP Data Delivery: enables the control plane CIoT optimization
+    /// and the reporting of the received payloads.
+    ///
+    /// Must be called before [`Modem::lte_connect`], with a
+    /// [`NonIP`](command::pdp::types::PDPType::NonIP) context in [`ModemConfig::pdp`].
+    pub async fn nidd_configure(&mut self) -> Result<(), Error> {
+        self.send(&nidd::SetCiotOptimization {
+            reporting: nidd::types::CiotOptimizationReporting::Disabled,
+            supported: Some(nidd::types::CiotOptimization::ControlPlane),
+            preferred: Some(nidd::types::PreferredCiotOptimization::ControlPlane),
+        })
+        .await?;
+        self.send(&nidd::ConfigureDataReporting {
+            reporting: Bool::True,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Sends a payload on the Non-IP context of [`ModemConfig::pdp`].
+    ///
+    /// Fails with [`Error::PayloadTooLong`] if `data` exceeds [`nidd::NIDD_PAYLOAD_LEN`].
+    pub async fn nidd_send(&mut self, attached: &Attached, data: &[u8]) -> Result<(), Error> {
+        let cid = self.config.pdp.cid;
+        self.control_plane_send(attached, cid, data, None, None)
+            .await
+    }
+
+    /// Sends data over the control plane on the context `cid`, a Non-IP payload or an IP packet.
+    ///
+    /// `rai` tells the network whether the connection can be released after the data, e.g. to
+    /// enter PSM right after a last report.
+    ///
+    /// Fails with [`Error::PayloadTooLong`] if `data` exceeds [`nidd::NIDD_PAYLOAD_LEN`].
+    pub async fn control_plane_send(
+        &mut self,
+        _attached: &Attached,
+        cid: u8,
+        data: &[u8],
+        rai: Option<nidd::types::ReleaseAssistance>,
+        user_data_type: Option<nidd::types::UserDataType>,
+    ) -> Result<(), Error> {
+        let mut hex = nidd::NiddHexData::new();
+        if data.len() > nidd::NIDD_PAYLOAD_LEN || hex_encode(data, &mut hex).is_err() {
+            return Err(Error::PayloadTooLong);
+        }
+
+        self.send(&nidd::SendData {
+            cid,
+            length: data.len(),
+            data: hex,
+            rai,
+            user_data_type,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for data received over the control plane, on any context.
+    ///
+    /// The payloads are buffered by the [`UrcHandler`] until they are received, the oldest are
+    /// kept when the buffer is full.
+    pub async fn nidd_receive(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<nidd::urc::DataReceived, Error> {
+        delay::with_timeout(&mut self.delay, timeout, self.state.nidd_inbox.receive())
+            .await
+            .map_err(|_| Error::Timeout(Operation::NiddReceive))
+    }
+}
+
+#[cfg(feature = "mqtt")]
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
     pub async fn mqtt_configure(
         &mut self,
         client_id: &str,
         auth: Option<MqttAuth>,
     ) -> Result<(), Error> {
-        let msg = match auth {
+        let msg = match &auth {
             Some(MqttAuth::UsernamePassword(UsernamePassword { username, password })) => {
                 &mqtt::Configure {
                     id: 0,
@@ -537,15 +2549,15 @@ where
             Some(MqttAuth::SecurityProfile(id)) => &mqtt::Configure {
                 id: 0,
                 client_id,
-                username: String::new(),
-                password: String::new(),
-                sp_id: Some(id),
+                username: "",
+                password: "",
+                sp_id: Some(*id),
             },
             None => &mqtt::Configure {
                 id: 0,
                 client_id,
-                username: String::new(),
-                password: String::new(),
+                username: "",
+                password: "",
                 sp_id: None,
             },
         };
@@ -555,29 +2567,141 @@ where
         Ok(())
     }
 
-    pub async fn mqtt_connect(&mut self, host: &str, port: Option<u32>) -> Result<(), Error> {
-        self.lte_connect().await?;
+    /// Connects to the MQTT broker, the modem must be attached with [`Modem::lte_connect`].
+    pub async fn mqtt_connect(
+        &mut self,
+        _attached: &Attached,
+        host: &str,
+        port: Option<u32>,
+    ) -> Result<(), Error> {
+        self.mqtt_connect_with(host, port, None).await
+    }
+
+    /// Configures the TLS profile and the MQTT client for AWS IoT Core, then connects to it.
+    ///
+    /// The configuration is checked first, fails with [`Error::AwsIot`] or
+    /// [`Error::ReservedNvmIndex`] without sending anything. The thing name is the MQTT client id
+    /// and the connection uses TLS 1.2 with mutual authentication, the server certificate is
+    /// verified against the CA and the endpoint name (also sent as SNI).
+    pub async fn aws_iot_connect(
+        &mut self,
+        _attached: &Attached,
+        config: &AwsIotConfig<'_>,
+    ) -> Result<(), Error> {
+        config.validate()?;
+
+        self.send(&ssl_tls::Configure {
+            sp_id: config.sp_id,
+            version: ssl_tls::types::SslTlsVersion::Tls12,
+            cipher_specs: String::new(),
+            // Validate the certificate chain and the host name.
+            cert_valid_level: 0b101,
+            ca_cert_id: Some(config.ca_cert_id).into(),
+            client_cert_id: Some(config.client_cert_id).into(),
+            client_private_key_id: Some(config.client_private_key_id).into(),
+            psk: String::new(),
+            psk_identity: String::new(),
+            storage_id: ssl_tls::types::StorageId::NVM,
+            resume: ssl_tls::types::Resume::Disabled,
+            lifetime: 0,
+        })
+        .await?;
+
+        self.mqtt_configure(
+            config.thing_name,
+            Some(MqttAuth::SecurityProfile(config.sp_id)),
+        )
+        .await?;
+
+        self.mqtt_connect_with(
+            config.endpoint,
+            Some(config.port.into()),
+            Some(config.keepalive.as_secs() as u32),
+        )
+        .await
+    }
+
+    /// Configures the TLS profile and the MQTT client for an Azure IoT hub, then connects to it.
+    ///
+    /// The device authenticates with `sas_token` if given, else with the client certificate of
+    /// the configuration. The configuration is checked first, fails with [`Error::AzureIot`] or
+    /// [`Error::ReservedNvmIndex`] without sending anything.
+    pub async fn azure_iot_connect(
+        &mut self,
+        _attached: &Attached,
+        config: &AzureIotConfig<'_>,
+        sas_token: Option<&AzureSasToken>,
+    ) -> Result<(), Error> {
+        config.validate(sas_token.is_some())?;
+        let username = config.username()?;
+        let (client_cert_id, client_private_key_id) = config.client_cert.unzip();
+
+        self.send(&ssl_tls::Configure {
+            sp_id: config.sp_id,
+            version: ssl_tls::types::SslTlsVersion::Tls12,
+            cipher_specs: String::new(),
+            // Validate the certificate chain and the host name.
+            cert_valid_level: 0b101,
+            ca_cert_id: Some(config.ca_cert_id).into(),
+            client_cert_id: client_cert_id.into(),
+            client_private_key_id: client_private_key_id.into(),
+            psk: String::new(),
+            psk_identity: String::new(),
+            storage_id: ssl_tls::types::StorageId::NVM,
+            resume: ssl_tls::types::Resume::Disabled,
+            lifetime: 0,
+        })
+        .await?;
 
-        self.send(&mqtt::Connect {
+        self.send(&mqtt::Configure {
             id: 0,
-            host,
-            port,
-            keepalive: None,
+            client_id: config.device_id,
+            username: &username,
+            password: sas_token.map_or("", AzureSasToken::as_str),
+            sp_id: Some(config.sp_id),
         })
         .await?;
 
-        let connected =
-            with_timeout(Duration::from_secs(30), self.state.mqtt_connected.wait()).await?;
+        self.mqtt_connect_with(config.hub, Some(AZURE_IOT_PORT.into()), None)
+            .await
+    }
+
+    async fn mqtt_connect_with(
+        &mut self,
+        host: &str,
+        port: Option<u32>,
+        keepalive: Option<u32>,
+    ) -> Result<(), Error> {
+        let rc = self
+            .send_and_wait_on(
+                &mqtt::Connect {
+                    id: 0,
+                    host,
+                    port,
+                    keepalive,
+                },
+                self.config.mqtt_connect_timeout,
+                Operation::MqttConnect,
+                |urc| match urc {
+                    Urc::MqttConnected(connected) => Some(connected.rc),
+                    _ => None,
+                },
+            )
+            .await?;
 
-        match connected.rc {
+        match rc {
             mqtt::types::MQTTStatusCode::Success => Ok(()),
             status => {
-                error!("MQTT connect error: {:?}", connected.rc);
-                Err(Error::MQTT(status))
+                error!("MQTT connect error: {:?}", status);
+                Err(Error::MqttConnect(status))
             }
         }
     }
 
+    /// Publishes a message and waits until the modem reports the publication done.
+    ///
+    /// Fails with [`Error::PayloadTooLong`] if `data` exceeds [`mqtt::MQTT_PAYLOAD_LEN`], and with
+    /// [`Error::MqttPublish`] if the message couldn't be published.
     pub async fn mqtt_send(
         &mut self,
         topic: &str,
@@ -586,73 +2710,258 @@ where
     ) -> Result<(), Error> {
         debug!("Sending MQTT message");
 
-        self.send(&mqtt::PreparePublish {
-            id: 0,
-            topic,
-            qos: Some(qos),
-            length: data.len(),
-        })
-        .await?;
+        if data.len() > mqtt::MQTT_PAYLOAD_LEN {
+            return Err(Error::PayloadTooLong);
+        }
+
+        // Subscribe first, the publication may complete before the payload is acknowledged.
+        let subscription = self.urc_chan.subscribe().unwrap();
+
+        let guard = self
+            .open_prompt(&mqtt::PreparePublish {
+                id: 0,
+                topic,
+                qos: Some(qos),
+                length: data.len(),
+            })
+            .await?;
 
         debug!("MQTT publish prepared");
 
-        self.send(&mqtt::Publish {
-            payload: atat::serde_bytes::Bytes::new(data),
-        })
-        .await?;
+        self.send_once(&mqtt::Publish { payload: data }).await?;
+        guard.disarm();
 
         debug!("MQTT publish Sent");
 
-        Ok(())
+        let published = self
+            .wait_on(
+                subscription,
+                self.config.mqtt_operation_timeout,
+                Operation::MqttPublish,
+                |urc| match urc {
+                    Urc::MqttMessagePublished(published) => Some(published.clone()),
+                    _ => None,
+                },
+            )
+            .await?;
+
+        match published.rc {
+            mqtt::types::MQTTStatusCode::Success => {
+                debug!("MQTT message {} published", published.pmid);
+                Ok(())
+            }
+            rc => {
+                error!("MQTT publish error: {:?}", rc);
+                Err(Error::MqttPublish {
+                    pmid: published.pmid,
+                    rc,
+                })
+            }
+        }
+    }
+
+    /// Subscribes to an MQTT topic and waits until the broker accepted the subscription.
+    ///
+    /// Fails with [`Error::MqttSubscribe`] if the subscription was rejected.
+    pub async fn mqtt_subscribe(
+        &mut self,
+        topic: &str,
+        qos: mqtt::types::Qos,
+    ) -> Result<(), Error> {
+        let rc = self
+            .send_and_wait_on(
+                &mqtt::Subscribe {
+                    id: 0,
+                    topic,
+                    qos: Some(qos),
+                },
+                self.config.mqtt_operation_timeout,
+                Operation::MqttSubscribe,
+                |urc| match urc {
+                    Urc::MqttSubscribed(subscribed) => Some(subscribed.rc),
+                    _ => None,
+                },
+            )
+            .await?;
+
+        match rc {
+            mqtt::types::MQTTStatusCode::Success => Ok(()),
+            rc => {
+                error!("MQTT subscribe error: {:?}", rc);
+                Err(Error::MqttSubscribe(rc))
+            }
+        }
     }
 
     pub async fn mqtt_disconnect(&mut self) -> Result<(), Error> {
         self.send(&mqtt::Disconnect { id: 0 }).await?;
-        self.lte_disconnect().await?;
         Ok(())
     }
 }
 
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
+    /// Reads the diagnostic log `log` from `offset` into `buf`, returning the number of bytes
+    /// read, 0 past the end of the log.
+    ///
+    /// Reads up to [`diagnostics::LOG_READ_LEN`] bytes at once, a whole log is streamed slice by
+    /// slice, e.g. to forward a crash dump to support:
+    ///
+    /// ```ignore
+    /// let mut buf = [0; LOG_READ_LEN];
+    /// let mut offset = 0;
+    /// loop {
+    ///     let len = modem.read_diagnostic_log(DiagnosticLog::Crash, offset, &mut buf).await?;
+    ///     if len == 0 {
+    ///         break;
+    ///     }
+    ///     uplink.write_all(&buf[..len]).await?;
+    ///     offset += len as u32;
+    /// }
+    /// ```
+    pub async fn read_diagnostic_log(
+        &mut self,
+        log: diagnostics::types::DiagnosticLog,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let max_bytes = buf.len().min(diagnostics::LOG_READ_LEN) as u16;
+        let read = self
+            .send(&diagnostics::ReadLog {
+                log,
+                offset,
+                max_bytes,
+            })
+            .await?;
+        // The modem never returns more than requested.
+        let len = read.data.len().min(buf.len());
+        buf[..len].copy_from_slice(&read.data[..len]);
+        Ok(len)
+    }
+
     pub async fn nvm_write(
         &mut self,
         data_type: nvm::types::DataType,
         index: u8,
         data: &[u8],
     ) -> Result<(), Error> {
+        let mut writer = self.nvm_writer(data_type, index, data.len()).await?;
+        writer.write(data).await
+    }
+
+    /// Starts writing an NVM entry of `size` bytes, the data is then streamed in slices with
+    /// [`PromptWriter::write`], e.g. to upload a certificate bundle read from flash without holding it
+    /// in RAM:
+    ///
+    /// ```ignore
+    /// let mut writer = modem.nvm_writer(DataType::Certificate, 19, bundle.len()).await?;
+    /// for chunk in bundle.chunks(512) {
+    ///     writer.write(chunk).await?;
+    /// }
+    /// ```
+    ///
+    /// The slices are sent in commands of at most [`NVM_CHUNK_LEN`](nvm::NVM_CHUNK_LEN) bytes.
+    ///
+    /// Fails with [`Error::ReservedNvmIndex`] for the indexes reserved by the modem, 0 to 4 and 7
+    /// to 10.
+    pub async fn nvm_writer(
+        &mut self,
+        data_type: nvm::types::DataType,
+        index: u8,
+        size: usize,
+    ) -> Result<PromptWriter<'_, 'sub, AtCl, N, L, D, M>, Error> {
         debug!("Writing to nvm");
 
-        assert!(
-            !(0..=4).contains(&index) && !(7..=10).contains(&index),
-            "Indexes O to 4 and 7 to 10 are reserved for Sequans's internal use."
-        );
+        if nvm::is_reserved_index(index) {
+            return Err(Error::ReservedNvmIndex(index));
+        }
 
-        self.send(&nvm::PrepareWrite {
-            data_type,
-            index,
-            size: data.len(),
-        })
-        .await?;
+        let guard = self
+            .open_prompt(&nvm::PrepareWrite {
+                data_type,
+                index,
+                size,
+            })
+            .await?;
 
         debug!("NVM write ready");
 
-        self.send(&nvm::Write {
-            data: atat::serde_bytes::Bytes::new(data),
+        Ok(PromptWriter {
+            modem: self,
+            remaining: size,
+            guard: Some(guard),
         })
-        .await?;
+    }
+}
+
+/// Data being written to a prompt of the modem, an NVM entry (see [`Modem::nvm_writer`]) or GNSS
+/// assistance data (see [`Modem::gnss_assistance_writer`]).
+///
+/// The modem stays in the prompt until it received the announced size: a writer dropped before
+/// aborts the write before the next command is sent.
+pub struct PromptWriter<'m, 'a, AtCl, const N: usize, const L: usize, D, M: RawMutex> {
+    modem: &'m mut Modem<'a, AtCl, N, L, D, M>,
+    remaining: usize,
+    guard: Option<PromptGuard<'a, M>>,
+}
+
+impl<AtCl, const N: usize, const L: usize, D, M> PromptWriter<'_, '_, AtCl, N, L, D, M>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    /// Number of bytes left to write.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Sends the next slice of the data, the last one waits for the modem to store the data.
+    ///
+    /// Slices longer than [`NVM_CHUNK_LEN`](nvm::NVM_CHUNK_LEN) are sent in several commands, the
+    /// command buffer only has to fit one of them.
+    ///
+    /// Fails with [`Error::PayloadTooLong`] if `data` exceeds the remaining size, without sending
+    /// it.
+    pub async fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        if self.guard.is_none() || data.len() > self.remaining {
+            return Err(Error::PayloadTooLong);
+        }
+
+        // An empty slice is still sent, the last one waits for the modem, e.g. deleting an entry.
+        let slices = data.chunks(nvm::NVM_CHUNK_LEN);
+        for data in slices.chain(data.is_empty().then_some(data)) {
+            if data.len() < self.remaining {
+                self.modem.send_once(&nvm::WriteChunk { data }).await?;
+                self.remaining -= data.len();
+            } else {
+                self.modem.send_once(&nvm::WriteLastChunk { data }).await?;
+                self.remaining = 0;
+            }
+        }
+
+        if self.remaining > 0 {
+            return Ok(());
+        }
+        if let Some(guard) = self.guard.take() {
+            guard.disarm();
+        }
 
-        debug!("NVM written");
+        debug!("Prompt data written");
 
         Ok(())
     }
 }
 
-impl<'sub, AtCl, const N: usize, const L: usize> Modem<'sub, AtCl, N, L>
+impl<'sub, AtCl, const N: usize, const L: usize, D, M> Modem<'sub, AtCl, N, L, D, M>
 where
     AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
 {
     /// Configures TLS/SSL security profile for use with e.g. MQTT.
     ///
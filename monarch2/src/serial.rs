@@ -0,0 +1,155 @@
+//! Transport running the driver over a serial port of the host, e.g. for desktop provisioning
+//! tools or integration tests against a devkit.
+//!
+//! The port is read on a thread of its own, feeding the ingress, while the commands are written by
+//! the task driving the [`Modem`]. The timeouts rely on the `std` time driver of `embassy-time`.
+//!
+//! ```ignore
+//! let res_slot = ResponseSlot::<INGRESS_BUF_SIZE>::new();
+//! let urc_chan = DefaultUrcChannel::new();
+//! let state = ModemState::new();
+//! let mut ingress_buf = [0; INGRESS_BUF_SIZE];
+//! let mut cmd_buf = [0; MAX_CMD_LEN];
+//! let stop = AtomicBool::new(false);
+//!
+//! let (mut rx, tx) = serial::open("/dev/ttyUSB0")?;
+//! let mut ingress = serial::ingress(&mut ingress_buf, &res_slot, &urc_chan, &state);
+//! let mut modem = serial::modem(tx, &res_slot, &mut cmd_buf, &urc_chan, &state, ModemConfig::default());
+//! let mut urc_handler = modem.urc_handler();
+//!
+//! std::thread::scope(|s| {
+//!     s.spawn(|| rx.feed(&mut ingress, &stop));
+//!     let info = block_on(select(modem.info(), urc_handler.run()));
+//!     stop.store(true, Ordering::Relaxed);
+//! });
+//! ```
+
+use std::{
+    boxed::Box,
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use atat::{AtDigester, AtatIngress, Config, Ingress, ResponseSlot, asynch::Client};
+use embassy_futures::block_on;
+use serialport::{FlowControl, SerialPort};
+
+use crate::{
+    channel::{
+        DefaultModem, DefaultUrcChannel, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS,
+        assert_ingress_buf_size,
+    },
+    command::{Urc, UrcParser},
+    config::ModemConfig,
+    error::CmeCodeDigester,
+    modem::{Modem, ModemState},
+};
+
+/// Default baud rate of the modem UART.
+pub const BAUD_RATE: u32 = 115_200;
+
+/// How long a read of the port waits for data before checking whether to stop.
+pub const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Client talking to the modem over the serial port.
+pub type SerialClient<'a, const B: usize = INGRESS_BUF_SIZE> = Client<'a, SerialTx, B>;
+
+/// Ingress parsing the data received from the modem over the serial port.
+pub type SerialIngress<'a, const B: usize = INGRESS_BUF_SIZE> =
+    Ingress<'a, CmeCodeDigester<'a, AtDigester<Urc>>, UrcParser, B, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+/// Opens the serial port at `path` with the default settings of the modem UART: [`BAUD_RATE`],
+/// 8N1 and RTS/CTS flow control.
+///
+/// Use [`split`] for a port opened with other settings, e.g. without flow control.
+pub fn open(path: &str) -> Result<(SerialRx, SerialTx), serialport::Error> {
+    let port = serialport::new(path, BAUD_RATE)
+        .flow_control(FlowControl::Hardware)
+        .timeout(READ_TIMEOUT)
+        .open()?;
+    split(port)
+}
+
+/// Splits an open serial port into its receiving and transmitting halves.
+///
+/// The read timeout of the port bounds how long [`SerialRx::feed`] takes to notice it should stop.
+pub fn split(port: Box<dyn SerialPort>) -> Result<(SerialRx, SerialTx), serialport::Error> {
+    let rx = port.try_clone()?;
+    Ok((SerialRx(rx), SerialTx(port)))
+}
+
+/// Creates the ingress, to be fed with [`SerialRx::feed`].
+///
+/// `state` must be the state of the [`Modem`], it receives the `+CME ERROR` codes, see
+/// [`CmeCodeDigester`].
+///
+/// The buffer must fit the largest URC and response lines, see
+/// [`INGRESS_BUF_SIZE`](crate::INGRESS_BUF_SIZE), a smaller one fails the build.
+pub fn ingress<'a, const B: usize>(
+    buf: &'a mut [u8; B],
+    res_slot: &'a ResponseSlot<B>,
+    urc_chan: &'a DefaultUrcChannel,
+    state: &'a ModemState,
+) -> SerialIngress<'a, B> {
+    assert_ingress_buf_size::<B>();
+    let digester = CmeCodeDigester::new(AtDigester::<Urc>::new(), state);
+    Ingress::new(digester, buf, res_slot, urc_chan)
+}
+
+/// Creates the [`Modem`] sending the commands over the serial port `tx`.
+///
+/// `buf` holds the serialized commands and must fit the largest command sent, see
+/// [`MAX_CMD_LEN`](crate::MAX_CMD_LEN).
+pub fn modem<'a, const B: usize>(
+    tx: SerialTx,
+    res_slot: &'a ResponseSlot<B>,
+    buf: &'a mut [u8],
+    urc_chan: &'a DefaultUrcChannel,
+    state: &'a ModemState,
+    config: ModemConfig,
+) -> DefaultModem<'a, SerialClient<'a, B>> {
+    assert_ingress_buf_size::<B>();
+    let client = Client::new(tx, res_slot, buf, Config::default());
+    Modem::new(client, urc_chan, state, config)
+}
+
+/// Receiving half of the serial port.
+pub struct SerialRx(Box<dyn SerialPort>);
+
+impl SerialRx {
+    /// Reads the port into `ingress` until `stop` is set, blocking the calling thread.
+    ///
+    /// Returns the first error reading the port, e.g. when the devkit is unplugged.
+    pub fn feed(&mut self, ingress: &mut impl AtatIngress, stop: &AtomicBool) -> io::Result<()> {
+        while !stop.load(Ordering::Relaxed) {
+            match io::Read::read(&mut self.0, ingress.write_buf()) {
+                // Waits for room in the URC channel, the handler runs on the other thread.
+                Ok(len) => block_on(ingress.advance(len)),
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Transmitting half of the serial port.
+///
+/// The writes block the task until the data is handed to the OS, which is short enough for the
+/// command lines sent to the modem.
+pub struct SerialTx(Box<dyn SerialPort>);
+
+impl embedded_io_async::ErrorType for SerialTx {
+    type Error = io::Error;
+}
+
+impl embedded_io_async::Write for SerialTx {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        io::Write::write(&mut self.0, buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        io::Write::flush(&mut self.0)
+    }
+}
@@ -0,0 +1,675 @@
+use atat::{AtatCmd, asynch::AtatClient};
+use embassy_sync::{
+    blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex},
+    mutex::{Mutex, MutexGuard},
+};
+
+#[cfg(feature = "nidd")]
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
+
+#[cfg(feature = "gnss")]
+use crate::gnss::{types::FixSensitivity, urc::GnssFixReady};
+#[cfg(feature = "http")]
+use crate::http;
+#[cfg(feature = "nidd")]
+use crate::nidd;
+#[cfg(feature = "socket")]
+use crate::socket;
+#[cfg(feature = "mqtt")]
+use crate::{
+    aws::AwsIotConfig,
+    azure::{AzureIotConfig, AzureSasToken},
+    modem::MqttAuth,
+    mqtt,
+};
+use crate::{
+    calendar::ClockTime,
+    config::{ClockSyncPolicy, PingOptions},
+    delay::DefaultDelay,
+    device, diagnostics, dns,
+    error::Error,
+    info::ModemInfo,
+    modem::{Attached, Detached, Manufacturing, Modem, ModemState},
+    network::types::NetworkRegistrationState,
+    ping, sim,
+};
+
+/// A [`Modem`] shared between multiple tasks.
+///
+/// The modem is split into handles for the individual subsystems ([`Control`], [`MqttClient`],
+/// [`GnssClient`], [`SocketStack`]) that can be handed to independent tasks. The handles share
+/// the underlying [`AtatClient`] behind an async mutex, locked only for each AT exchange (or from
+/// the command opening a prompt until its payload is written, e.g. for [`MqttClient::send`]).
+///
+/// Operations waiting for the network (e.g. [`MqttClient::connect`]) wait for their URC without
+/// the lock, the other handles go on meanwhile. The channel must accept a subscription for every
+/// handle waiting at the same time, see [`URC_SUBSCRIBERS`](crate::URC_SUBSCRIBERS).
+///
+/// Every handle runs its own [`Modem`] over the shared client, created from the one passed to
+/// [`new`](Self::new): set its observer and waker before sharing it. The driver state (e.g.
+/// whether [`Control::begin`] initialized it) is kept in the [`ModemState`] common to all handles.
+///
+/// `M` is the mutex of the shared client and of the handles, `SM` the one of the [`ModemState`]
+/// (see [`Modem`]), so that each can be picked for the tasks using it.
+pub struct SharedModem<
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    client: Mutex<M, AtCl>,
+    /// The modem the handles are created from, without its client.
+    modem: Modem<'a, (), N, L, D, SM>,
+}
+
+impl<'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    SharedModem<'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    pub fn new(modem: Modem<'a, AtCl, N, L, D, SM>) -> Self {
+        let (client, modem) = modem.into_client();
+        Self {
+            client: Mutex::new(client),
+            modem,
+        }
+    }
+
+    /// Returns a modem for a handle, sending through the shared client.
+    fn handle(&self) -> Mutex<M, HandleModem<'_, 'a, M, AtCl, N, L, D, SM>> {
+        Mutex::new(self.modem.with_client(SharedClient {
+            client: &self.client,
+        }))
+    }
+
+    /// Returns a copy of the delay provider of the modem.
+    pub(crate) fn delay(&self) -> D {
+        self.modem.delay()
+    }
+
+    /// Returns a handle for the general modem control.
+    pub fn control(&self) -> Control<'_, 'a, M, AtCl, N, L, D, SM> {
+        Control {
+            modem: self.handle(),
+            state: self.modem.state(),
+        }
+    }
+
+    /// Returns a handle for the MQTT client.
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt(&self) -> MqttClient<'_, 'a, M, AtCl, N, L, D, SM> {
+        MqttClient {
+            modem: self.handle(),
+        }
+    }
+
+    /// Returns a handle for the LwM2M client.
+    #[cfg(feature = "lwm2m")]
+    pub fn lwm2m(&self) -> Lwm2mClient<'_, 'a, M, AtCl, N, L, D, SM> {
+        Lwm2mClient {
+            modem: self.handle(),
+        }
+    }
+
+    /// Returns a handle for the GNSS subsystem.
+    #[cfg(feature = "gnss")]
+    pub fn gnss(&self) -> GnssClient<'_, 'a, M, AtCl, N, L, D, SM> {
+        GnssClient {
+            modem: self.handle(),
+        }
+    }
+
+    /// Returns a handle for the sockets.
+    #[cfg(feature = "socket")]
+    pub fn sockets(&self) -> SocketStack<'_, 'a, M, AtCl, N, L, D, SM> {
+        SocketStack {
+            modem: self.handle(),
+        }
+    }
+}
+
+/// The [`AtatClient`] of a [`SharedModem`], shared by its handles and locked for every command.
+pub struct SharedClient<'s, M: RawMutex, AtCl> {
+    client: &'s Mutex<M, AtCl>,
+}
+
+impl<M: RawMutex, AtCl: AtatClient> AtatClient for SharedClient<'_, M, AtCl> {
+    async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, atat::Error> {
+        self.client.lock().await.send(cmd).await
+    }
+}
+
+/// The [`Modem`] of a [`SharedModem`] handle.
+type HandleModem<'s, 'a, M, AtCl, const N: usize, const L: usize, D, SM> =
+    Modem<'a, SharedClient<'s, M, AtCl>, N, L, D, SM>;
+
+/// Handle for the general modem control: initialization, SIM and network attachment.
+pub struct Control<
+    's,
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Mutex<M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>>,
+    state: &'a ModemState<SM>,
+}
+
+impl<'s, 'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    Control<'s, 'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// Locks the modem of the handle, e.g. to send custom commands.
+    ///
+    /// The other handles still send their commands between the ones sent through the guard, it
+    /// only groups the operations of this handle.
+    pub async fn lock(&self) -> MutexGuard<'_, M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>> {
+        self.modem.lock().await
+    }
+
+    /// See [`Modem::begin`].
+    pub async fn begin(&self) -> Result<(), Error> {
+        self.lock().await.begin().await
+    }
+
+    /// See [`Modem::ping`].
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.lock().await.ping().await
+    }
+
+    /// See [`Modem::get_pin_status`].
+    pub async fn get_pin_status(&self) -> Result<sim::types::SIMState, Error> {
+        self.lock().await.get_pin_status().await
+    }
+
+    /// See [`Modem::define_pdp_context`].
+    pub async fn define_pdp_context(&self) -> Result<(), Error> {
+        self.lock().await.define_pdp_context().await
+    }
+
+    /// See [`Modem::lte_connect`].
+    pub async fn lte_connect(&self, detached: Detached) -> Result<Attached, Error> {
+        self.lock().await.lte_connect(detached).await
+    }
+
+    pub(crate) async fn attach(&self) -> Result<Attached, Error> {
+        self.lock().await.attach().await
+    }
+
+    /// See [`Modem::lte_disconnect`].
+    pub async fn lte_disconnect(&self, attached: Attached) -> Result<Detached, Error> {
+        self.lock().await.lte_disconnect(attached).await
+    }
+
+    /// See [`Modem::lte_force_disconnect`].
+    pub async fn lte_force_disconnect(
+        &self,
+        attached: Option<Attached>,
+    ) -> Result<Detached, Error> {
+        self.lock().await.lte_force_disconnect(attached).await
+    }
+
+    /// See [`Modem::get_autoconnect`].
+    pub async fn get_autoconnect(&self) -> Result<bool, Error> {
+        self.lock().await.get_autoconnect().await
+    }
+
+    /// See [`Modem::set_autoconnect`].
+    pub async fn set_autoconnect(&self, enabled: bool) -> Result<(), Error> {
+        self.lock().await.set_autoconnect(enabled).await
+    }
+
+    /// See [`Modem::reboot`].
+    pub async fn reboot(&self) -> Result<(), Error> {
+        self.lock().await.reboot().await
+    }
+
+    /// See [`Modem::get_carrier_profile`].
+    pub async fn get_carrier_profile(
+        &self,
+    ) -> Result<heapless::String<{ device::responses::CARRIER_PROFILE_LEN }>, Error> {
+        self.lock().await.get_carrier_profile().await
+    }
+
+    /// See [`Modem::set_carrier_profile`].
+    pub async fn set_carrier_profile(
+        &self,
+        detached: &Detached,
+        profile: &str,
+    ) -> Result<bool, Error> {
+        self.lock()
+            .await
+            .set_carrier_profile(detached, profile)
+            .await
+    }
+
+    /// See [`Modem::enter_manufacturing_mode`].
+    pub async fn enter_manufacturing_mode(
+        &self,
+        detached: Detached,
+    ) -> Result<Manufacturing, Error> {
+        self.lock().await.enter_manufacturing_mode(detached).await
+    }
+
+    /// See [`Modem::leave_manufacturing_mode`].
+    pub async fn leave_manufacturing_mode(
+        &self,
+        manufacturing: Manufacturing,
+    ) -> Result<Detached, Error> {
+        self.lock()
+            .await
+            .leave_manufacturing_mode(manufacturing)
+            .await
+    }
+
+    /// See [`Modem::rf_test_transmit`].
+    pub async fn rf_test_transmit(
+        &self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+        power: i8,
+    ) -> Result<(), Error> {
+        self.lock()
+            .await
+            .rf_test_transmit(manufacturing, earfcn, power)
+            .await
+    }
+
+    /// See [`Modem::rf_test_stop_transmit`].
+    pub async fn rf_test_stop_transmit(&self, manufacturing: &Manufacturing) -> Result<(), Error> {
+        self.lock().await.rf_test_stop_transmit(manufacturing).await
+    }
+
+    /// See [`Modem::rf_test_measure`].
+    pub async fn rf_test_measure(
+        &self,
+        manufacturing: &Manufacturing,
+        earfcn: u32,
+    ) -> Result<i16, Error> {
+        self.lock()
+            .await
+            .rf_test_measure(manufacturing, earfcn)
+            .await
+    }
+
+    /// See [`Modem::get_time`].
+    pub async fn get_time(
+        &self,
+        policy: ClockSyncPolicy,
+    ) -> Result<device::responses::Clock, Error> {
+        self.lock().await.get_time(policy).await
+    }
+
+    /// See [`Modem::set_time`].
+    pub async fn set_time(&self, time: &ClockTime) -> Result<(), Error> {
+        self.lock().await.set_time(time).await
+    }
+
+    /// See [`Modem::sync_time_ntp`].
+    pub async fn sync_time_ntp(
+        &self,
+        attached: &Attached,
+        server: &str,
+    ) -> Result<device::responses::Clock, Error> {
+        self.lock().await.sync_time_ntp(attached, server).await
+    }
+
+    /// See [`Modem::resolve`].
+    pub async fn resolve(
+        &self,
+        attached: &Attached,
+        hostname: &str,
+    ) -> Result<dns::responses::HostAddresses, Error> {
+        self.lock().await.resolve(attached, hostname).await
+    }
+
+    /// See [`Modem::icmp_ping`].
+    pub async fn icmp_ping(
+        &self,
+        attached: &Attached,
+        host: &str,
+        options: &PingOptions,
+    ) -> Result<ping::responses::PingReplies, Error> {
+        self.lock().await.icmp_ping(attached, host, options).await
+    }
+
+    /// See [`Modem::http_configure`].
+    #[cfg(feature = "http")]
+    pub async fn http_configure(
+        &self,
+        prof_id: u8,
+        host: &str,
+        port: u16,
+        sp_id: Option<u8>,
+    ) -> Result<(), Error> {
+        self.lock()
+            .await
+            .http_configure(prof_id, host, port, sp_id)
+            .await
+    }
+
+    /// See [`Modem::http_query`].
+    #[cfg(feature = "http")]
+    pub async fn http_query(
+        &self,
+        attached: &Attached,
+        prof_id: u8,
+        command: http::types::QueryCommand,
+        resource: &str,
+    ) -> Result<http::urc::Ring, Error> {
+        self.lock()
+            .await
+            .http_query(attached, prof_id, command, resource)
+            .await
+    }
+
+    /// See [`Modem::http_receive`].
+    #[cfg(feature = "http")]
+    pub async fn http_receive(&self, prof_id: u8, buf: &mut [u8]) -> Result<usize, Error> {
+        self.lock().await.http_receive(prof_id, buf).await
+    }
+
+    /// See [`Modem::read_diagnostic_log`].
+    pub async fn read_diagnostic_log(
+        &self,
+        log: diagnostics::types::DiagnosticLog,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.lock()
+            .await
+            .read_diagnostic_log(log, offset, buf)
+            .await
+    }
+
+    /// See [`Modem::nidd_configure`].
+    #[cfg(feature = "nidd")]
+    pub async fn nidd_configure(&self) -> Result<(), Error> {
+        self.lock().await.nidd_configure().await
+    }
+
+    /// See [`Modem::nidd_send`].
+    #[cfg(feature = "nidd")]
+    pub async fn nidd_send(&self, attached: &Attached, data: &[u8]) -> Result<(), Error> {
+        self.lock().await.nidd_send(attached, data).await
+    }
+
+    /// See [`Modem::control_plane_send`].
+    #[cfg(feature = "nidd")]
+    pub async fn control_plane_send(
+        &self,
+        attached: &Attached,
+        cid: u8,
+        data: &[u8],
+        rai: Option<nidd::types::ReleaseAssistance>,
+        user_data_type: Option<nidd::types::UserDataType>,
+    ) -> Result<(), Error> {
+        self.lock()
+            .await
+            .control_plane_send(attached, cid, data, rai, user_data_type)
+            .await
+    }
+
+    /// See [`Modem::nidd_receive`].
+    #[cfg(feature = "nidd")]
+    pub async fn nidd_receive(&self, timeout: Duration) -> Result<nidd::urc::DataReceived, Error> {
+        self.lock().await.nidd_receive(timeout).await
+    }
+
+    /// See [`Modem::get_network_registration_state`], read without locking the handle, e.g.
+    /// while another task attaches with it.
+    pub fn get_network_registration_state(&self) -> NetworkRegistrationState {
+        self.state.registration_state()
+    }
+
+    /// See [`Modem::info`].
+    pub async fn info(&self) -> Result<ModemInfo, Error> {
+        self.lock().await.info().await
+    }
+}
+
+/// Handle for the MQTT client.
+#[cfg(feature = "mqtt")]
+pub struct MqttClient<
+    's,
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Mutex<M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>>,
+}
+
+#[cfg(feature = "mqtt")]
+impl<'s, 'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    MqttClient<'s, 'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// See [`Modem::mqtt_configure`].
+    pub async fn configure(&self, client_id: &str, auth: Option<MqttAuth>) -> Result<(), Error> {
+        self.modem
+            .lock()
+            .await
+            .mqtt_configure(client_id, auth)
+            .await
+    }
+
+    /// See [`Modem::mqtt_connect`].
+    pub async fn connect(
+        &self,
+        attached: &Attached,
+        host: &str,
+        port: Option<u32>,
+    ) -> Result<(), Error> {
+        self.modem
+            .lock()
+            .await
+            .mqtt_connect(attached, host, port)
+            .await
+    }
+
+    /// See [`Modem::aws_iot_connect`].
+    pub async fn aws_iot_connect(
+        &self,
+        attached: &Attached,
+        config: &AwsIotConfig<'_>,
+    ) -> Result<(), Error> {
+        self.modem
+            .lock()
+            .await
+            .aws_iot_connect(attached, config)
+            .await
+    }
+
+    /// See [`Modem::azure_iot_connect`].
+    pub async fn azure_iot_connect(
+        &self,
+        attached: &Attached,
+        config: &AzureIotConfig<'_>,
+        sas_token: Option<&AzureSasToken>,
+    ) -> Result<(), Error> {
+        self.modem
+            .lock()
+            .await
+            .azure_iot_connect(attached, config, sas_token)
+            .await
+    }
+
+    /// See [`Modem::mqtt_send`].
+    pub async fn send(&self, topic: &str, qos: mqtt::types::Qos, data: &[u8]) -> Result<(), Error> {
+        self.modem.lock().await.mqtt_send(topic, qos, data).await
+    }
+
+    /// See [`Modem::mqtt_subscribe`].
+    pub async fn subscribe(&self, topic: &str, qos: mqtt::types::Qos) -> Result<(), Error> {
+        self.modem.lock().await.mqtt_subscribe(topic, qos).await
+    }
+
+    /// See [`Modem::mqtt_disconnect`].
+    pub async fn disconnect(&self) -> Result<(), Error> {
+        self.modem.lock().await.mqtt_disconnect().await
+    }
+}
+
+/// Handle for the LwM2M client.
+#[cfg(feature = "lwm2m")]
+pub struct Lwm2mClient<
+    's,
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Mutex<M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>>,
+}
+
+#[cfg(feature = "lwm2m")]
+impl<'s, 'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    Lwm2mClient<'s, 'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// See [`Modem::lwm2m_register`].
+    pub async fn register(&self, attached: &Attached) -> Result<(), Error> {
+        self.modem.lock().await.lwm2m_register(attached).await
+    }
+
+    /// See [`Modem::lwm2m_set_resource`].
+    pub async fn set_resource(
+        &self,
+        object: u16,
+        instance: u16,
+        resource: u16,
+        value: &str,
+    ) -> Result<(), Error> {
+        self.modem
+            .lock()
+            .await
+            .lwm2m_set_resource(object, instance, resource, value)
+            .await
+    }
+
+    /// See [`Modem::lwm2m_disable`].
+    pub async fn disable(&self) -> Result<(), Error> {
+        self.modem.lock().await.lwm2m_disable().await
+    }
+}
+
+/// Handle for the GNSS subsystem.
+#[cfg(feature = "gnss")]
+pub struct GnssClient<
+    's,
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Mutex<M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>>,
+}
+
+#[cfg(feature = "gnss")]
+impl<'s, 'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    GnssClient<'s, 'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// See [`Modem::set_gnss_config`].
+    pub async fn set_config(&self, sensitivity: FixSensitivity) -> Result<(), Error> {
+        self.modem.lock().await.set_gnss_config(sensitivity).await
+    }
+
+    /// See [`Modem::update_gnss_asistance`].
+    pub async fn update_assistance(&self, detached: Detached) -> Result<Detached, Error> {
+        self.modem
+            .lock()
+            .await
+            .update_gnss_asistance(detached)
+            .await
+    }
+
+    /// See [`Modem::get_gnss_fix`].
+    pub async fn get_fix(&self, detached: &Detached) -> Result<GnssFixReady, Error> {
+        self.modem.lock().await.get_gnss_fix(detached).await
+    }
+}
+
+/// Handle for the sockets.
+#[cfg(feature = "socket")]
+pub struct SocketStack<
+    's,
+    'a,
+    M: RawMutex,
+    AtCl,
+    const N: usize,
+    const L: usize,
+    D = DefaultDelay,
+    SM: RawMutex = CriticalSectionRawMutex,
+> {
+    modem: Mutex<M, HandleModem<'s, 'a, M, AtCl, N, L, D, SM>>,
+}
+
+#[cfg(feature = "socket")]
+impl<'s, 'a, M: RawMutex, AtCl, const N: usize, const L: usize, D, SM>
+    SocketStack<'s, 'a, M, AtCl, N, L, D, SM>
+where
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    SM: RawMutex,
+{
+    /// See [`Modem::socket_set_hex_mode`].
+    pub async fn set_hex_mode(&self, conn_id: u8) -> Result<(), Error> {
+        self.modem.lock().await.socket_set_hex_mode(conn_id).await
+    }
+
+    /// See [`Modem::socket_receive`].
+    pub async fn receive(
+        &self,
+        attached: &Attached,
+        conn_id: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.modem
+            .lock()
+            .await
+            .socket_receive(attached, conn_id, buf)
+            .await
+    }
+
+    /// See [`Modem::socket_data_usage`].
+    pub async fn data_usage(&self, conn_id: u8) -> Result<socket::types::DataUsage, Error> {
+        self.modem.lock().await.socket_data_usage(conn_id).await
+    }
+
+    /// See [`Modem::data_usage`].
+    pub async fn total_data_usage(&self) -> Result<socket::types::DataUsage, Error> {
+        self.modem.lock().await.data_usage().await
+    }
+}
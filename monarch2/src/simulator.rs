@@ -0,0 +1,550 @@
+//! Software model of a Monarch 2 modem, to test the high level operations of the
+//! [`Modem`](crate::Modem) end to end on the host, e.g. in CI.
+//!
+//! Unlike the [`ScriptedClient`](crate::ScriptedClient), the [`SimulatedModem`] doesn't expect an
+//! exact sequence of commands: it keeps the state of the modem (functionality level, network
+//! registration, clock, MQTT client and GNSS assistance data) and answers the commands like the
+//! modem would, sending the URCs of the network registration, MQTT and GNSS operations after the
+//! [simulated duration](SimulatedTimings) of the operation.
+//!
+//! ```ignore
+//! let client = SimulatedModem::new(ingress, YieldDelay);
+//! let mut modem = Modem::new_with_delay(client, &urc_chan, &state, config, YieldDelay);
+//! let mut urc_handler = modem.urc_handler();
+//!
+//! let clock = block_on(select(
+//!     modem.get_time(ClockSyncPolicy::ConnectIfNeeded),
+//!     urc_handler.run(),
+//! ));
+//! ```
+//!
+//! With the [`YieldDelay`](crate::YieldDelay) the simulated durations elapse immediately, the
+//! operations still complete in the order of the real modem so the tests are deterministic.
+
+use std::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use atat::{AtatCmd, AtatIngress, asynch::AtatClient};
+use embassy_time::Duration;
+use embedded_hal_async::delay::DelayNs;
+
+use crate::delay;
+
+/// Clock of the modem until the network or the host sets it.
+const UNSET_CLOCK: &str = "70/01/01,00:00:10+00";
+
+/// Durations of the simulated operations, the URC completing an operation is sent once its
+/// duration elapsed.
+#[derive(Clone, Debug)]
+pub struct SimulatedTimings {
+    /// From the radio turned on (`AT+CFUN=1`) to the registration to the network.
+    pub registration: Duration,
+
+    /// From `AT+SQNSMQTTCONNECT` to the connection to the broker.
+    pub mqtt_connect: Duration,
+
+    /// Round trip of an MQTT publication or subscription with the broker.
+    pub mqtt_operation: Duration,
+
+    /// Download of one kind of GNSS assistance data.
+    pub gnss_assistance: Duration,
+
+    /// Time to fix, with valid ephemeris data.
+    pub gnss_fix: Duration,
+}
+
+impl Default for SimulatedTimings {
+    fn default() -> Self {
+        Self {
+            registration: Duration::from_secs(3),
+            mqtt_connect: Duration::from_millis(800),
+            mqtt_operation: Duration::from_millis(300),
+            gnss_assistance: Duration::from_secs(4),
+            gnss_fix: Duration::from_secs(25),
+        }
+    }
+}
+
+/// [`AtatClient`] simulating a Monarch 2 modem, see the [module](self) documentation.
+///
+/// The modem starts with the radio off (`CFUN=0`), an unset clock, no GNSS assistance data and
+/// the SIM card ready. Unsupported commands fail with [`atat::Error::Error`].
+pub struct SimulatedModem<I: AtatIngress, D: DelayNs> {
+    ingress: I,
+    delay: D,
+    timings: SimulatedTimings,
+    roaming: bool,
+    network_time: String,
+
+    functionality: u8,
+    registered: bool,
+    clock: Option<String>,
+    mqtt_configured: bool,
+    mqtt_connected: bool,
+    /// Length of the payload announced by `AT+SQNSMQTTPUBLISH`, sent next.
+    publish_len: Option<usize>,
+    pmid: u16,
+    almanac: bool,
+    ephemeris: bool,
+    fix_id: u8,
+
+    commands: Vec<String>,
+}
+
+impl<I: AtatIngress, D: DelayNs> SimulatedModem<I, D> {
+    /// Creates the simulated modem, the URCs are fed through `ingress` and the simulated
+    /// durations are awaited with `delay`.
+    pub fn new(ingress: I, delay: D) -> Self {
+        Self {
+            ingress,
+            delay,
+            timings: SimulatedTimings::default(),
+            roaming: false,
+            network_time: "24/05/30,13:22:45+08".to_string(),
+            functionality: 0,
+            registered: false,
+            clock: None,
+            mqtt_configured: false,
+            mqtt_connected: false,
+            publish_len: None,
+            pmid: 0,
+            almanac: false,
+            ephemeris: false,
+            fix_id: 0,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Overrides the [default](SimulatedTimings::default) durations of the operations.
+    pub fn with_timings(mut self, timings: SimulatedTimings) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Registers on a visited network (`+CEREG: 5`) instead of the home network.
+    pub fn with_roaming(mut self, roaming: bool) -> Self {
+        self.roaming = roaming;
+        self
+    }
+
+    /// Time provided by the network once registered, as reported by `AT+CCLK?`, e.g.
+    /// `24/05/30,13:22:45+08`.
+    pub fn with_network_time(mut self, time: &str) -> Self {
+        self.network_time = time.to_string();
+        self
+    }
+
+    /// Starts with up to date GNSS assistance data, so no download is needed before a fix.
+    pub fn with_gnss_assistance(mut self) -> Self {
+        self.almanac = true;
+        self.ephemeris = true;
+        self
+    }
+
+    /// Returns the commands received so far, without the AT termination.
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// Whether the modem is registered to the network.
+    pub fn is_registered(&self) -> bool {
+        self.registered
+    }
+
+    /// Whether the MQTT client is connected to the broker.
+    pub fn is_mqtt_connected(&self) -> bool {
+        self.mqtt_connected
+    }
+
+    fn urc(&mut self, line: &str) {
+        if self
+            .ingress
+            .try_write(format!("\r\n{line}\r\n").as_bytes())
+            .is_err()
+        {
+            panic!("URC {line:?} dropped, the URC channel is full");
+        }
+    }
+
+    /// Answers a command line, returns the information text of the response.
+    async fn answer(&mut self, line: &str) -> Result<String, atat::Error> {
+        let line = line.strip_prefix("AT").ok_or(atat::Error::Error)?;
+        let (name, raw_args) = line.split_once('=').unwrap_or((line, ""));
+        let args: Vec<&str> = match raw_args {
+            "" => Vec::new(),
+            _ => raw_args.split(',').collect(),
+        };
+
+        match (name, args.as_slice()) {
+            ("", []) => {}
+            ("+CMEE" | "+CEREG" | "+CGEREP" | "+CNMI" | "+CTZU" | "+LPGNSSCFG", [_, ..]) => {}
+            ("+CPIN?", []) => return Ok("+CPIN: READY".to_string()),
+            ("+CFUN?", []) => return Ok(format!("+CFUN: {}", self.functionality)),
+            ("+CFUN", [fun, ..]) => match *fun {
+                "1" => self.radio_on().await,
+                "0" | "4" => self.radio_off(fun),
+                _ => return Err(atat::Error::Error),
+            },
+            // The selection is always automatic.
+            ("+COPS", ["0", ..]) => {}
+            ("+CCLK?", []) => {
+                let clock = self.clock.as_deref().unwrap_or(UNSET_CLOCK);
+                return Ok(format!("+CCLK: \"{clock}\""));
+            }
+            ("+CCLK", [_, ..]) => self.clock = Some(raw_args.trim_matches('"').to_string()),
+
+            ("+SQNSMQTTCFG", ["0", ..]) => self.mqtt_configured = true,
+            ("+SQNSMQTTCONNECT", ["0", ..]) if self.mqtt_configured => {
+                delay::sleep(&mut self.delay, self.timings.mqtt_connect).await;
+                // The connection fails asynchronously without the network.
+                let rc = if self.registered { 0 } else { -4 };
+                self.mqtt_connected = self.registered;
+                self.urc(&format!("+SQNSMQTTONCONNECT: 0,{rc}"));
+            }
+            ("+SQNSMQTTPUBLISH", ["0", _, _, len]) if self.mqtt_connected => {
+                self.publish_len = Some(len.parse().map_err(|_| atat::Error::Error)?);
+            }
+            ("+SQNSMQTTSUBSCRIBE", ["0", topic, ..]) if self.mqtt_connected => {
+                let topic = topic.to_string();
+                delay::sleep(&mut self.delay, self.timings.mqtt_operation).await;
+                self.urc(&format!("+SQNSMQTTONSUBSCRIBE: 0,{topic},0"));
+            }
+            ("+SQNSMQTTDISCONNECT", ["0"]) => {
+                if self.mqtt_connected {
+                    self.mqtt_connected = false;
+                    self.urc("+SQNSMQTTONDISCONNECT: 0,0");
+                }
+            }
+
+            ("+LPGNSSASSISTANCE?", []) => {
+                let lines = [
+                    (0, self.almanac, 86_400),
+                    (1, self.ephemeris, 1_800),
+                    (2, false, 0),
+                ]
+                .map(|(typ, available, time_to_update)| {
+                    if available {
+                        format!("+LPGNSSASSISTANCE: {typ},1,1717068165,{time_to_update},0")
+                    } else {
+                        format!("+LPGNSSASSISTANCE: {typ},0,0,0,0")
+                    }
+                });
+                return Ok(lines.join("\r\n"));
+            }
+            // The assistance data is downloaded from the cloud.
+            ("+LPGNSSASSISTANCE", [typ]) if self.registered => {
+                delay::sleep(&mut self.delay, self.timings.gnss_assistance).await;
+                match *typ {
+                    "0" => self.almanac = true,
+                    "1" => self.ephemeris = true,
+                    _ => return Err(atat::Error::Error),
+                }
+            }
+            // The radio is shared with LTE, and a fix needs the time.
+            ("+LPGNSSFIXPROG", ["\"single\""]) if !self.registered && self.clock.is_some() => {
+                self.gnss_fix().await
+            }
+            ("+LPGNSSFIXPROG", ["\"stop\""]) => {}
+
+            _ => return Err(atat::Error::Error),
+        }
+
+        Ok(String::new())
+    }
+
+    async fn radio_on(&mut self) {
+        self.functionality = 1;
+        if self.registered {
+            return;
+        }
+
+        self.urc("+CEREG: 2");
+        delay::sleep(&mut self.delay, self.timings.registration).await;
+        self.registered = true;
+        self.urc(if self.roaming {
+            "+CEREG: 5"
+        } else {
+            "+CEREG: 1"
+        });
+        // NITZ, the network sets the clock once registered.
+        self.clock = Some(self.network_time.clone());
+    }
+
+    fn radio_off(&mut self, fun: &str) {
+        self.functionality = fun.parse().unwrap_or_default();
+        self.registered = false;
+        if self.mqtt_connected {
+            self.mqtt_connected = false;
+            // Connection lost.
+            self.urc("+SQNSMQTTONDISCONNECT: 0,-7");
+        }
+        self.urc("+CEREG: 0");
+    }
+
+    async fn gnss_fix(&mut self) {
+        let mut ttf = self.timings.gnss_fix;
+        if !self.ephemeris {
+            // Cold start, the ephemeris are decoded from the satellites.
+            ttf *= 4;
+        }
+        delay::sleep(&mut self.delay, ttf).await;
+
+        let fix_id = self.fix_id;
+        // The modem keeps the ten last fixes.
+        self.fix_id = (self.fix_id + 1) % 10;
+        self.urc(&format!(
+            "+LPGNSSFIXREADY: {fix_id},\"2024-05-30T11:22:45.000000\",{},\"12.500000\",\"50.850000\",\"4.350000\",\"60.000000\",\"0.000000\",\"0.000000\",\"0.000000\",\"AAAA\",(\"12\",38)",
+            ttf.as_millis()
+        ));
+    }
+
+    /// Takes the MQTT payload announced by `AT+SQNSMQTTPUBLISH`, completing the publication.
+    async fn publish(&mut self, len: usize, payload: &[u8]) -> Result<(), atat::Error> {
+        if payload.len() != len {
+            return Err(atat::Error::Error);
+        }
+
+        delay::sleep(&mut self.delay, self.timings.mqtt_operation).await;
+        self.pmid = self.pmid.wrapping_add(1);
+        self.urc(&format!("+SQNSMQTTONPUBLISH: 0,{},0", self.pmid));
+        Ok(())
+    }
+}
+
+impl<I: AtatIngress, D: DelayNs> AtatClient for SimulatedModem<I, D> {
+    async fn send<Cmd: AtatCmd>(&mut self, cmd: &Cmd) -> Result<Cmd::Response, atat::Error> {
+        let mut buf = vec![0; Cmd::MAX_LEN];
+        let len = cmd.write(&mut buf);
+        let sent = &buf[..len];
+
+        let text = match self.publish_len.take() {
+            Some(len) => {
+                self.commands
+                    .push(format!("<{} bytes of payload>", sent.len()));
+                self.publish(len, sent).await.map(|()| String::new())
+            }
+            None => {
+                let line = String::from_utf8_lossy(sent);
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                let text = self.answer(&line).await;
+                self.commands.push(line);
+                text
+            }
+        };
+
+        if !Cmd::EXPECTS_RESPONSE_CODE {
+            return cmd.parse(Ok(&[]));
+        }
+        let text = text?;
+        cmd.parse(Ok(text.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use atat::{AtDigester, Ingress, ResponseSlot, UrcChannel};
+    use embassy_futures::{
+        block_on,
+        select::{Either, select},
+    };
+
+    use super::*;
+    use crate::{
+        calendar,
+        channel::{INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS},
+        command::{Urc, UrcParser},
+        config::{ClockSyncPolicy, ModemConfig},
+        error::Error,
+        mock::YieldDelay,
+        modem::{Attached, Modem, ModemState},
+        network::types::NetworkRegistrationState,
+    };
+
+    type TestIngress<'a> =
+        Ingress<'a, AtDigester<Urc>, UrcParser, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+    type TestSimulator<'a> = SimulatedModem<TestIngress<'a>, YieldDelay>;
+
+    type TestModem<'a> = Modem<'a, TestSimulator<'a>, URC_CAPACITY, URC_SUBSCRIBERS, YieldDelay>;
+
+    struct Harness {
+        urc_chan: UrcChannel<Urc, URC_CAPACITY, URC_SUBSCRIBERS>,
+        res_slot: ResponseSlot<INGRESS_BUF_SIZE>,
+        state: ModemState,
+        buf: [u8; INGRESS_BUF_SIZE],
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self {
+                urc_chan: UrcChannel::new(),
+                res_slot: ResponseSlot::new(),
+                state: ModemState::new(),
+                buf: [0; INGRESS_BUF_SIZE],
+            }
+        }
+
+        fn modem(
+            &mut self,
+            f: impl FnOnce(TestSimulator<'_>) -> TestSimulator<'_>,
+        ) -> TestModem<'_> {
+            let ingress = Ingress::new(
+                AtDigester::<Urc>::new(),
+                &mut self.buf,
+                &self.res_slot,
+                &self.urc_chan,
+            );
+            Modem::new_with_delay(
+                f(SimulatedModem::new(ingress, YieldDelay)),
+                &self.urc_chan,
+                &self.state,
+                ModemConfig::default(),
+                YieldDelay,
+            )
+        }
+    }
+
+    /// Runs `flow` along the URC handler of `modem`.
+    macro_rules! run {
+        ($modem:ident, $flow:expr) => {{
+            let mut urc_handler = $modem.urc_handler();
+            match block_on(select($flow, urc_handler.run())) {
+                Either::First(res) => res,
+                Either::Second(never) => never,
+            }
+        }};
+    }
+
+    #[test]
+    fn test_lte_connect_disconnect() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim);
+
+        run!(modem, async {
+            modem.begin().await?;
+            let detached = modem.lte_force_disconnect(None).await?;
+            let attached = modem.lte_connect(detached).await?;
+            assert_eq!(
+                modem.get_network_registration_state(),
+                NetworkRegistrationState::RegisteredHome
+            );
+            modem.lte_disconnect(attached).await?;
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        assert_eq!(
+            modem.get_network_registration_state(),
+            NetworkRegistrationState::NotSearching
+        );
+    }
+
+    #[test]
+    fn test_lte_connect_roaming() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim.with_roaming(true));
+
+        run!(modem, async {
+            let detached = modem.lte_force_disconnect(None).await?;
+            modem.lte_connect(detached).await
+        })
+        .unwrap();
+        assert_eq!(
+            modem.get_network_registration_state(),
+            NetworkRegistrationState::RegisteredRoaming
+        );
+    }
+
+    #[test]
+    fn test_get_time() {
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim.with_network_time("24/05/30,13:22:45+08"));
+
+        let err = run!(modem, modem.get_time(ClockSyncPolicy::UseExisting)).unwrap_err();
+        assert_eq!(err, Error::ClockInvalid);
+
+        // Attaches to synchronize the clock, then detaches again.
+        let clock = run!(modem, modem.get_time(ClockSyncPolicy::ConnectIfNeeded)).unwrap();
+        assert_eq!(
+            clock.time().map(calendar::unix_seconds),
+            Some(1_717_068_165)
+        );
+        assert_eq!(
+            modem.get_network_registration_state(),
+            NetworkRegistrationState::NotSearching
+        );
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_mqtt() {
+        use crate::mqtt::types::Qos;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim);
+
+        run!(modem, async {
+            modem.mqtt_configure("device-1", None).await?;
+            let detached = modem.lte_force_disconnect(None).await?;
+            let attached = modem.lte_connect(detached).await?;
+            modem
+                .mqtt_connect(&attached, "broker.example.com", None)
+                .await?;
+            modem.mqtt_subscribe("commands", Qos::AtLeastOnce).await?;
+            modem
+                .mqtt_send("sensors", Qos::AtLeastOnce, b"21.5")
+                .await?;
+            modem.mqtt_send("sensors", Qos::AtMostOnce, b"21.6").await?;
+            modem.mqtt_disconnect().await
+        })
+        .unwrap();
+
+        // Not connected to the broker anymore.
+        let res = run!(modem, modem.mqtt_send("sensors", Qos::AtMostOnce, b"21.7"));
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_mqtt_connect_without_network() {
+        use crate::mqtt::types::MQTTStatusCode;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim);
+
+        let err = run!(modem, async {
+            let detached = modem.lte_force_disconnect(None).await?;
+            let attached = modem.lte_connect(detached).await?;
+            modem.mqtt_configure("device-1", None).await?;
+            // Forged on purpose, the broker connection must fail once detached.
+            let stale = Attached::new_unchecked();
+            modem.lte_disconnect(attached).await?;
+            modem.mqtt_connect(&stale, "broker.example.com", None).await
+        })
+        .unwrap_err();
+        assert_eq!(err, Error::MqttConnect(MQTTStatusCode::NoConn));
+    }
+
+    #[cfg(feature = "gnss")]
+    #[test]
+    fn test_gnss_fix() {
+        use crate::gnss::types::FixSensitivity;
+
+        let mut harness = Harness::new();
+        let mut modem = harness.modem(|sim| sim);
+
+        let fix = run!(modem, async {
+            modem.begin().await?;
+            modem.set_gnss_config(FixSensitivity::Medium).await?;
+            let detached = modem.lte_force_disconnect(None).await?;
+            let detached = modem.update_gnss_asistance(detached).await?;
+            modem.get_gnss_fix(&detached).await
+        })
+        .unwrap();
+        assert_eq!(fix.fix_id, 0);
+        assert_eq!(fix.ttf, 25_000);
+    }
+}
@@ -0,0 +1,20 @@
+/// Wakes the modem from a sleep state (e.g. PSM) before a command is sent, set with
+/// [`Modem::set_waker`](crate::Modem::set_waker).
+///
+/// The UART of a sleeping modem is dead, the commands sent to it are lost and time out. Once the
+/// application reported the sleep with [`ModemState::set_asleep`](crate::ModemState::set_asleep),
+/// the [`Modem`](crate::Modem) calls the waker, then probes the modem with `AT` until it answers:
+///
+/// ```ignore
+/// struct WakePin<'d>(RefCell<Output<'d>>);
+///
+/// impl ModemWaker for WakePin<'_> {
+///     fn wake(&self) {
+///         self.0.borrow_mut().set_low();
+///     }
+/// }
+/// ```
+pub trait ModemWaker {
+    /// Starts waking the modem, e.g. by asserting its RTS or wake-up line. Must return quickly.
+    fn wake(&self);
+}
@@ -0,0 +1,166 @@
+//! Support for the DPTechnics [Walter](https://www.quickspot.io) board, an ESP32-S3 wired to a
+//! GM02SP modem.
+//!
+//! The modem is connected to the ESP32-S3 over a UART with hardware flow control, its reset line
+//! is driven by a GPIO:
+//!
+//! ```ignore
+//! static RES_SLOT: ResponseSlot<INGRESS_BUF_SIZE> = ResponseSlot::new();
+//! static URC_CHANNEL: DefaultUrcChannel = DefaultUrcChannel::new();
+//! static STATE: ModemState = ModemState::new();
+//!
+//! let config = UartConfig::default().with_baudrate(walter::BAUD_RATE);
+//! let (rx, tx) = Uart::new(p.UART1, config)?
+//!     .with_rx(p.GPIO14)
+//!     .with_tx(p.GPIO48)
+//!     .with_rts(p.GPIO21)
+//!     .with_cts(p.GPIO47)
+//!     .into_async()
+//!     .split();
+//! let mut reset = Output::new(p.GPIO45, Level::High, OutputConfig::default());
+//!
+//! let ingress = walter::ingress(INGRESS_BUF.init([0; INGRESS_BUF_SIZE]), &RES_SLOT, &URC_CHANNEL, &STATE);
+//! let mut modem = walter::modem(tx, &RES_SLOT, CMD_BUF.init([0; MAX_CMD_LEN]), &URC_CHANNEL, &STATE, ModemConfig::default());
+//!
+//! spawner.spawn(ingress_task(ingress, rx))?;
+//! spawner.spawn(urc_task(modem.urc_handler()))?;
+//!
+//! walter::reset(&mut reset, &modem).await?;
+//! modem.begin().await?;
+//! ```
+
+use core::convert::Infallible;
+
+use atat::{
+    AtDigester, Config, Ingress, ResponseSlot,
+    asynch::{AtatClient, Client},
+};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_time::Duration;
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait};
+use embedded_io_async::Write;
+
+use crate::{
+    channel::{
+        DefaultModem, DefaultUrcChannel, INGRESS_BUF_SIZE, URC_CAPACITY, URC_SUBSCRIBERS,
+        assert_ingress_buf_size,
+    },
+    command::{Urc, UrcParser},
+    config::ModemConfig,
+    delay,
+    error::{CmeCodeDigester, Error, Operation},
+    event::ModemEvent,
+    modem::{Modem, ModemState},
+};
+
+/// ESP32-S3 GPIO receiving from the modem (modem TX).
+pub const PIN_RX: u8 = 14;
+
+/// ESP32-S3 GPIO transmitting to the modem (modem RX).
+pub const PIN_TX: u8 = 48;
+
+/// ESP32-S3 GPIO used as UART RTS.
+pub const PIN_RTS: u8 = 21;
+
+/// ESP32-S3 GPIO used as UART CTS.
+pub const PIN_CTS: u8 = 47;
+
+/// ESP32-S3 GPIO driving the active low reset of the modem.
+pub const PIN_RESET: u8 = 45;
+
+/// Baud rate of the modem UART, with RTS/CTS flow control.
+pub const BAUD_RATE: u32 = 115_200;
+
+/// How long the reset line is held low.
+pub const RESET_PULSE: Duration = Duration::from_millis(10);
+
+/// How long the modem takes at most to start after a reset.
+pub const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client talking to the modem over the UART.
+pub type WalterClient<'a, W, const B: usize = INGRESS_BUF_SIZE> = Client<'a, W, B>;
+
+/// Ingress parsing the data received from the modem over the UART.
+pub type WalterIngress<'a, const B: usize = INGRESS_BUF_SIZE> =
+    Ingress<'a, CmeCodeDigester<'a, AtDigester<Urc>>, UrcParser, B, URC_CAPACITY, URC_SUBSCRIBERS>;
+
+/// Creates the ingress, to be fed with the data read from the UART, e.g. with
+/// `ingress.read_from(rx)`.
+///
+/// `state` must be the state of the [`Modem`], it receives the `+CME ERROR` codes, see
+/// [`CmeCodeDigester`].
+///
+/// The buffer must fit the largest URC and response lines, see
+/// [`INGRESS_BUF_SIZE`](crate::INGRESS_BUF_SIZE), a smaller one fails the build.
+pub fn ingress<'a, const B: usize>(
+    buf: &'a mut [u8; B],
+    res_slot: &'a ResponseSlot<B>,
+    urc_chan: &'a DefaultUrcChannel,
+    state: &'a ModemState,
+) -> WalterIngress<'a, B> {
+    assert_ingress_buf_size::<B>();
+    let digester = CmeCodeDigester::new(AtDigester::<Urc>::new(), state);
+    Ingress::new(digester, buf, res_slot, urc_chan)
+}
+
+/// Creates the [`Modem`] sending the commands over the UART `tx`.
+///
+/// `buf` holds the serialized commands and must fit the largest command sent, see
+/// [`MAX_CMD_LEN`](crate::MAX_CMD_LEN).
+pub fn modem<'a, W: Write, const B: usize>(
+    tx: W,
+    res_slot: &'a ResponseSlot<B>,
+    buf: &'a mut [u8],
+    urc_chan: &'a DefaultUrcChannel,
+    state: &'a ModemState,
+    config: ModemConfig,
+) -> DefaultModem<'a, WalterClient<'a, W, B>> {
+    assert_ingress_buf_size::<B>();
+    let client = Client::new(tx, res_slot, buf, Config::default());
+    Modem::new(client, urc_chan, state, config)
+}
+
+/// Resets the modem with the reset line and waits until it reports it started.
+///
+/// The [`UrcHandler`](crate::UrcHandler) must be running to receive the start notification.
+pub async fn reset<P, AtCl, const N: usize, const L: usize, D, M>(
+    reset: &mut P,
+    modem: &Modem<'_, AtCl, N, L, D, M>,
+) -> Result<(), Error>
+where
+    P: OutputPin<Error = Infallible>,
+    AtCl: AtatClient,
+    D: DelayNs + Clone,
+    M: RawMutex,
+{
+    // Subscribe first, the modem may start before the pulse ends.
+    let mut events = modem.events();
+    let mut delay = modem.delay();
+
+    let Ok(()) = reset.set_low();
+    delay::sleep(&mut delay, RESET_PULSE).await;
+    let Ok(()) = reset.set_high();
+
+    delay::with_timeout(&mut delay, STARTUP_TIMEOUT, async {
+        while !matches!(events.next_message_pure().await, ModemEvent::Started) {}
+    })
+    .await
+    .map_err(|_| Error::Timeout(Operation::Startup))?;
+
+    debug!("Walter modem started");
+    Ok(())
+}
+
+/// Waits until the modem pulses its ring indicator line, e.g. to wake from light sleep on a URC.
+///
+/// The events pulsing the line are set with
+/// [`UrcPolicy::ring_indicator`](crate::UrcPolicy::ring_indicator). The UART flow control holds
+/// the URC in the modem until the ESP32-S3 is awake to read it.
+///
+/// A pulse also tells that a modem reported [asleep](crate::ModemState::set_asleep) woke up, to be
+/// reported with [`ModemState::set_awake`](crate::ModemState::set_awake).
+pub async fn wait_for_ring<P: Wait>(ring: &mut P) -> Result<(), P::Error> {
+    // The line is active low.
+    ring.wait_for_falling_edge().await
+}
@@ -0,0 +1,174 @@
+//! Hardware-in-the-loop tests, running the connect, publish and GNSS flows against a GM02S(P) on a
+//! serial port of the host, e.g. to validate a release.
+//!
+//! The tests are configured with environment variables:
+//!
+//! - `MONARCH2_PORT`: serial port of the modem, e.g. `/dev/ttyUSB0`. The tests are skipped
+//!   without it.
+//! - `MONARCH2_APN`: APN of the SIM card, autodetected by default.
+//! - `MONARCH2_MQTT_HOST`, `MONARCH2_MQTT_PORT`: MQTT broker to publish to, the MQTT test is
+//!   skipped without a host.
+//! - `MONARCH2_MQTT_TOPIC`: topic published to, `monarch2/hil` by default.
+//!
+//! ```sh
+//! MONARCH2_PORT=/dev/ttyUSB0 cargo test --features hil,gm02sp --test hil
+//! ```
+
+use std::{
+    env,
+    process::ExitCode,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use atat::ResponseSlot;
+use embassy_futures::{
+    block_on,
+    select::{Either, select},
+};
+use monarch2::{prelude::*, serial};
+
+type HilModem<'a> = DefaultModem<'a, serial::SerialClient<'a>>;
+
+fn main() -> ExitCode {
+    let Ok(port) = env::var("MONARCH2_PORT") else {
+        println!("MONARCH2_PORT not set, skipping the hardware tests");
+        return ExitCode::SUCCESS;
+    };
+
+    let mut pdp = PdpConfig::default();
+    if let Ok(apn) = env::var("MONARCH2_APN") {
+        pdp.apn = apn.as_str().try_into().expect("MONARCH2_APN too long");
+    }
+    let config = ModemConfig::default().with_pdp(pdp);
+
+    let res_slot = ResponseSlot::<INGRESS_BUF_SIZE>::new();
+    let urc_chan = DefaultUrcChannel::new();
+    let state = ModemState::new();
+    let mut ingress_buf = [0; INGRESS_BUF_SIZE];
+    let mut cmd_buf = [0; MAX_CMD_LEN];
+    let stop = AtomicBool::new(false);
+
+    let (mut rx, tx) = serial::open(&port).expect("can't open MONARCH2_PORT");
+    let mut ingress = serial::ingress(&mut ingress_buf, &res_slot, &urc_chan, &state);
+    let mut modem = serial::modem(tx, &res_slot, &mut cmd_buf, &urc_chan, &state, config);
+    let mut urc_handler = modem.urc_handler();
+
+    let failures = thread::scope(|s| {
+        let reader = s.spawn(|| rx.feed(&mut ingress, &stop));
+        let failures = match block_on(select(run(&mut modem), urc_handler.run())) {
+            Either::First(failures) => failures,
+            Either::Second(never) => never,
+        };
+        stop.store(true, Ordering::Relaxed);
+        if let Ok(Err(err)) = reader.join() {
+            println!("reading {port} failed: {err}");
+        }
+        failures
+    });
+
+    println!("\nhil: {failures} failed");
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Runs the flows one after the other, returns the number of failures.
+async fn run(modem: &mut HilModem<'_>) -> usize {
+    if check("begin", modem.begin().await) > 0 {
+        // Nothing else works without it.
+        return 1;
+    }
+
+    let mut failures = check("info", modem.info().await.map(|info| println!("{info:?}")));
+
+    let attached = async {
+        let detached = modem.lte_force_disconnect(None).await?;
+        modem.lte_connect(detached).await
+    }
+    .await;
+    failures += check("lte_connect", attached.as_ref().map(|_| ()));
+    if let Ok(attached) = attached {
+        failures += check("get_time", get_time(modem).await);
+
+        if let Ok(host) = env::var("MONARCH2_MQTT_HOST") {
+            failures += check("mqtt", mqtt_publish(modem, &attached, &host).await);
+        } else {
+            println!("test mqtt ... skipped, MONARCH2_MQTT_HOST not set");
+        }
+
+        failures += check(
+            "lte_disconnect",
+            modem.lte_disconnect(attached).await.map(|_| ()),
+        );
+    }
+
+    #[cfg(feature = "gnss")]
+    {
+        failures += check("gnss", gnss_fix(modem).await);
+    }
+
+    failures
+}
+
+/// Reports the result of a flow, returns 1 if it failed.
+fn check<E: core::fmt::Debug>(name: &str, result: Result<(), E>) -> usize {
+    match result {
+        Ok(()) => {
+            println!("test {name} ... ok");
+            0
+        }
+        Err(err) => {
+            println!("test {name} ... FAILED: {err:?}");
+            1
+        }
+    }
+}
+
+/// The network synchronizes the clock once registered.
+async fn get_time(modem: &mut HilModem<'_>) -> Result<(), Error> {
+    let clock = modem.get_time(ClockSyncPolicy::UseExisting).await?;
+    println!("modem clock: {:?}", clock.unix_seconds());
+    Ok(())
+}
+
+async fn mqtt_publish(
+    modem: &mut HilModem<'_>,
+    attached: &Attached,
+    host: &str,
+) -> Result<(), Error> {
+    let port = env::var("MONARCH2_MQTT_PORT")
+        .ok()
+        .map(|port| port.parse().expect("invalid MONARCH2_MQTT_PORT"));
+    let topic = env::var("MONARCH2_MQTT_TOPIC").unwrap_or_else(|_| "monarch2/hil".into());
+
+    modem.mqtt_configure("monarch2-hil", None).await?;
+    modem.mqtt_connect(attached, host, port).await?;
+    let published = modem
+        .mqtt_send(
+            &topic,
+            mqtt::types::Qos::AtLeastOnce,
+            b"hello from monarch2",
+        )
+        .await;
+    modem.mqtt_disconnect().await?;
+    published
+}
+
+/// Downloads the assistance data if needed, then waits for a fix.
+#[cfg(feature = "gnss")]
+async fn gnss_fix(modem: &mut HilModem<'_>) -> Result<(), Error> {
+    modem
+        .set_gnss_config(gnss::types::FixSensitivity::Medium)
+        .await?;
+    let detached = modem.lte_force_disconnect(None).await?;
+    let detached = modem.update_gnss_asistance(detached).await?;
+    let fix = modem.get_gnss_fix(&detached).await?;
+    println!(
+        "GNSS fix: {} {} ({} m)",
+        fix.lat.0, fix.long.0, fix.confidence.0
+    );
+    Ok(())
+}